@@ -0,0 +1,34 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use sudoku::puzzle::SudokuPuzzle;
+
+/// Solvable by filling in squares that have only one possible number.
+const EASY: &str =
+    "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+
+/// Requires a great deal of guessing.
+const HARD: &str =
+    "800000000003600000070090200050007000000045700000100030001000068008500010090000400";
+
+/// A puzzle with the fewest clues that still has a unique solution.
+const SEVENTEEN_CLUE: &str =
+    "000000010400000000020000000000050407008000300001090000300400200050100000000806000";
+
+fn solve(c: &mut Criterion) {
+    let empty = "0".repeat(81);
+    let puzzles = [
+        ("easy", EASY),
+        ("hard", HARD),
+        ("17-clue", SEVENTEEN_CLUE),
+        ("empty", &empty[..]),
+    ];
+
+    let mut group = c.benchmark_group("solve");
+    for (name, source) in puzzles.iter() {
+        let puzzle = SudokuPuzzle::from_string(source).unwrap();
+        group.bench_function(*name, |b| b.iter(|| black_box(&puzzle).solve()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, solve);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+//! Read a puzzle from arbitrary bytes, then solve and rate it.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::convert::TryFrom;
+use sudoku::puzzle::SudokuPuzzle;
+
+fuzz_target!(|bytes: &[u8]| {
+    if let Ok(puzzle) = SudokuPuzzle::try_from(bytes) {
+        assert_eq!(bytes, &puzzle.to_vec()[..]);
+        let _ = puzzle.solutions().take(2).count();
+        let _ = puzzle.difficulty();
+    }
+});
@@ -0,0 +1,12 @@
+//! Read a difficulty from arbitrary text.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::rating::Difficulty;
+
+fuzz_target!(|text: &str| {
+    if let Ok(difficulty) = text.parse::<Difficulty>() {
+        assert_eq!(Ok(difficulty), difficulty.name().parse());
+    }
+});
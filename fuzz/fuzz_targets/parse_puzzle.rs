@@ -0,0 +1,17 @@
+//! Read a puzzle from arbitrary text, then solve, rate, and canonicalize it.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::puzzle::SudokuPuzzle;
+
+fuzz_target!(|text: &str| {
+    if let Ok(puzzle) = SudokuPuzzle::from_string(text) {
+        let _ = puzzle.conflicts();
+        let _ = puzzle.remaining_by_digit();
+        let _ = puzzle.solve_with_steps();
+        let _ = puzzle.count_solutions(2);
+        let _ = puzzle.rating();
+        let _ = puzzle.canonical();
+    }
+});
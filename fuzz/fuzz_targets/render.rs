@@ -0,0 +1,24 @@
+//! Draw a puzzle read from arbitrary text with arbitrary options.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::{
+    puzzle::SudokuPuzzle,
+    render::{self, RenderOptions},
+};
+
+fuzz_target!(|input: (&str, bool, bool, Vec<(usize, usize)>)| {
+    let (text, solution, candidates, highlight) = input;
+    if let Ok(puzzle) = SudokuPuzzle::from_string(text) {
+        let options = RenderOptions {
+            solution,
+            candidates,
+            highlight,
+        };
+        let _ = render::svg(&puzzle, &options);
+        let _ = render::html(&puzzle, &options);
+        let image = render::raster(&puzzle, &options);
+        assert_eq!(image.width * image.height * 3, image.pixels.len());
+    }
+});
@@ -0,0 +1,16 @@
+//! Restore a game from an arbitrary saved game, then undo and redo every
+//! move.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::history::History;
+
+fuzz_target!(|text: &str| {
+    if let Ok(mut history) = History::restore(text) {
+        assert!(History::restore(&history.save()).is_ok());
+        while history.undo() {}
+        while history.redo() {}
+        assert!(History::restore(&history.save()).is_ok());
+    }
+});
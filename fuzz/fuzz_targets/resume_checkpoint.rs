@@ -0,0 +1,14 @@
+//! Resume a search from an arbitrary checkpoint and continue it.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::search::Solutions;
+
+fuzz_target!(|text: &str| {
+    if let Ok(mut search) = Solutions::resume(text) {
+        let _ = search.next();
+        assert!(Solutions::resume(&search.checkpoint()).is_ok());
+        let _ = search.take(2).count();
+    }
+});
@@ -0,0 +1,64 @@
+//! Solving, counting, and rating many puzzles at once.
+//!
+//! With the `parallel` feature the puzzles are spread across rayon's thread
+//! pool; without it they are worked through one at a time on the calling
+//! thread. The functions have the same signatures either way, and return
+//! their results in the order of the puzzles given, so code using them does
+//! not need to know which it got.
+//!
+//! ```
+//! # use sudoku::{batch, puzzle::SudokuPuzzle};
+//! let puzzles: Vec<SudokuPuzzle> = [
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//!     "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+//! ]
+//! .iter()
+//! .map(|text| SudokuPuzzle::from_string(text).unwrap())
+//! .collect();
+//!
+//! assert_eq!(vec![1, 1], batch::count_all(&puzzles, 2));
+//! let solutions = batch::solve_all(&puzzles);
+//! assert_eq!(puzzles[1].solve(), solutions[1]);
+//! ```
+
+use crate::{
+    puzzle::SudokuPuzzle,
+    rating::{Rating, RatingConfig},
+    solution::Solution,
+};
+use alloc::vec::Vec;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Apply f to every puzzle, returning the results in the same order.
+pub fn map<T, F>(puzzles: &[SudokuPuzzle], f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&SudokuPuzzle) -> T + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        puzzles.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        puzzles.iter().map(f).collect()
+    }
+}
+
+/// Solve every puzzle, as with [`SudokuPuzzle::solve`].
+pub fn solve_all(puzzles: &[SudokuPuzzle]) -> Vec<Option<Solution>> {
+    map(puzzles, SudokuPuzzle::solve)
+}
+
+/// Count the solutions of every puzzle, stopping each once limit have been
+/// found.
+pub fn count_all(puzzles: &[SudokuPuzzle], limit: usize) -> Vec<usize> {
+    map(puzzles, |puzzle| puzzle.count_solutions(limit))
+}
+
+/// Rate every puzzle with the weights in config, as with
+/// [`SudokuPuzzle::rating_with`].
+pub fn rate_all(puzzles: &[SudokuPuzzle], config: &RatingConfig) -> Vec<Option<Rating>> {
+    map(puzzles, |puzzle| puzzle.rating_with(config))
+}
@@ -0,0 +1,614 @@
+//! The shape of a Sudoku-like grid, so that one solver can work on the
+//! classic 9x9 puzzle and on boards of other sizes or with other units.
+//!
+//! A board is a square grid of squares, identified like those of
+//! [`SudokuPuzzle`](crate::puzzle::SudokuPuzzle) by their linear, row-major
+//! index, together with the units that must each hold every symbol once.
+//!
+//! ```
+//! # use sudoku::{board, puzzle::SudokuPuzzle};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let solved = board::solve(&puzzle).unwrap();
+//! assert!(solved.is_solved());
+//! assert_eq!(puzzle.solve().unwrap().to_vec(), solved.to_vec());
+//! assert_eq!(1, board::count_solutions(&puzzle, 2));
+//! ```
+
+use crate::{
+    error::LimitExceeded,
+    limits::SolverLimits,
+    puzzle::SudokuPuzzle,
+    step::Step,
+    units::{Unit, PEERS, UNITS},
+};
+use alloc::vec::Vec;
+use core::{
+    ops::RangeInclusive,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The most squares a board can have, with 32 symbols.
+const MAX_SQUARES: usize = 32 * 32;
+
+/// A grid whose squares must be filled so that no unit repeats a symbol.
+pub trait Board {
+    /// The number of rows, which is also the number of columns and of
+    /// symbols. At most 32.
+    fn size(&self) -> usize;
+
+    /// The symbols that can fill a square.
+    fn symbols(&self) -> RangeInclusive<u8> {
+        1..=self.size() as u8
+    }
+
+    /// The symbol in (row, col), or `None` if it is empty.
+    fn cell(&self, row: usize, col: usize) -> Option<u8>;
+
+    /// Fill or empty (row, col), without checking the rules.
+    fn set_cell(&mut self, row: usize, col: usize, val: Option<u8>);
+
+    /// How many units the board has.
+    fn unit_count(&self) -> usize;
+
+    /// The squares in unit i, which must each hold a different symbol.
+    fn unit(&self, i: usize) -> &[usize];
+
+    /// The other squares that share a unit with square.
+    fn peers(&self, square: usize) -> &[usize];
+
+    /// The symbols that could fill the empty square without repeating one
+    /// of its peers', as a set of bits with bit 0 for the first symbol.
+    /// Boards that keep track of their candidates can look them up instead.
+    fn candidate_bits(&self, square: usize) -> u32 {
+        let size = self.size();
+        let all = all_symbols(self);
+        self.peers(square).iter().fold(all, |left, peer| {
+            match self.cell(peer / size, peer % size) {
+                Some(n) => left & !symbol_bit(self, n),
+                None => left,
+            }
+        })
+    }
+
+    /// Indicates whether no unit holds the same symbol twice.
+    fn is_consistent(&self) -> bool {
+        let size = self.size();
+        (0..self.unit_count()).all(|i| {
+            let mut seen = 0u32;
+            self.unit(i)
+                .iter()
+                .all(|square| match self.cell(square / size, square % size) {
+                    Some(n) => {
+                        let bit = symbol_bit(self, n);
+                        let repeated = seen & bit != 0;
+                        seen |= bit;
+                        !repeated
+                    }
+                    None => true,
+                })
+        })
+    }
+
+    /// Unit i as a row, column, or box of a 9x9 puzzle, to name it in the
+    /// steps of a search, or `None` if the board's units aren't those.
+    fn named_unit(&self, _i: usize) -> Option<Unit> {
+        None
+    }
+}
+
+impl Board for SudokuPuzzle {
+    fn size(&self) -> usize {
+        9
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Option<u8> {
+        self.get(row, col)
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, val: Option<u8>) {
+        self.put(row, col, val);
+    }
+
+    fn unit_count(&self) -> usize {
+        UNITS.len()
+    }
+
+    fn unit(&self, i: usize) -> &[usize] {
+        &UNITS[i]
+    }
+
+    fn peers(&self, square: usize) -> &[usize] {
+        &PEERS[square]
+    }
+
+    fn candidate_bits(&self, square: usize) -> u32 {
+        u32::from(self.candidates(square / 9, square % 9).bits())
+    }
+
+    fn is_consistent(&self) -> bool {
+        SudokuPuzzle::is_consistent(self)
+    }
+
+    fn named_unit(&self, i: usize) -> Option<Unit> {
+        Some(Unit::from_index(i))
+    }
+}
+
+/// Solve board, if possible, returning it with every square filled. This is
+/// the search behind [`SudokuPuzzle::solve`]: it fills what it can with
+/// naked and hidden singles, then guesses at the last square with more than
+/// one candidate.
+pub fn solve<B: Board + Clone>(board: &B) -> Option<B> {
+    let mut working = board.clone();
+    if search_in_place(&mut working, &mut Trail::new()) {
+        Some(working)
+    } else {
+        None
+    }
+}
+
+/// Solve board without deduction, filling the square with the fewest
+/// candidates and guessing when there is more than one, and also return how
+/// many guesses were made.
+pub(crate) fn backtrack<B: Board + Clone>(board: &B) -> (Option<B>, u64) {
+    let mut working = board.clone();
+    let mut trail = Trail {
+        deduce: false,
+        fewest_first: true,
+        ..Trail::new()
+    };
+    if board.is_consistent() && search_in_place(&mut working, &mut trail) {
+        (Some(working), trail.guesses)
+    } else {
+        (None, trail.guesses)
+    }
+}
+
+/// Count the solutions of board, stopping once limit have been found.
+pub fn count_solutions<B: Board + Clone>(board: &B, limit: usize) -> usize {
+    count_solutions_within(board, limit, &SolverLimits::default())
+        .expect("a search without limits never breaks one")
+}
+
+/// Count the solutions of board like `count_solutions`, giving up once the
+/// search breaks one of limits.
+pub fn count_solutions_within<B: Board + Clone>(
+    board: &B,
+    limit: usize,
+    limits: &SolverLimits,
+) -> Result<usize, LimitExceeded> {
+    if limit == 0 {
+        return Ok(0);
+    }
+    let mut trail = Trail {
+        count_to: Some(limit),
+        ..Trail::within(limits)
+    };
+    search_in_place(&mut board.clone(), &mut trail);
+    match trail.exceeded {
+        Some(exceeded) => Err(exceeded),
+        None => Ok(trail.found),
+    }
+}
+
+/// Indicates whether no unit of board holds the same symbol twice.
+pub fn is_consistent<B: Board>(board: &B) -> bool {
+    board.is_consistent()
+}
+
+/// Solve board in place, recording each square that gets filled on the
+/// trail. Returns `false` if there is no solution, in which case every
+/// square filled by this call has been emptied again.
+pub(crate) fn search_in_place<B: Board>(board: &mut B, trail: &mut Trail) -> bool {
+    let start = trail.len();
+
+    if trail.visit()
+        && (!trail.deduce || fill_all(board, trail))
+        && if is_filled(board) {
+            trail.solved()
+        } else {
+            try_guesses(board, trail)
+        }
+    {
+        return true;
+    }
+
+    undo_to(board, trail, start);
+    false
+}
+
+/// Find a square that could be filled multiple ways. Try each
+/// choice and return `true` once one leads to a valid solution,
+/// or `false` if no choice leads to a valid solution.
+fn try_guesses<B: Board>(board: &mut B, trail: &mut Trail) -> bool {
+    let square = if trail.fewest_first {
+        fewest_square(board)
+    } else {
+        guess_square(board)
+    };
+    let size = board.size();
+    let (row, col) = (square / size, square % size);
+    let candidates = board.candidate_bits(square);
+    // Without deduction, the square may have only one candidate to fill
+    let guessing = candidates.count_ones() > 1;
+    if guessing && !trail.descend() {
+        return false;
+    }
+
+    // Try each guess, recursively attempting to solve the board that
+    // results from making that guess, until some guess yields a solution.
+    // A failed guess undoes its own changes, so the next guess starts
+    // from the same state.
+    for n in board.symbols() {
+        if candidates & symbol_bit(board, n) == 0 {
+            continue;
+        }
+        if trail.stopped() || guessing && !trail.spend_guess() {
+            return false;
+        }
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("guess", row, col, n).entered();
+        board.set_cell(row, col, Some(n));
+        trail.push(square);
+        trail.log(if guessing {
+            Step::Guess { row, col, n }
+        } else {
+            Step::Single { row, col, n }
+        });
+        if search_in_place(board, trail) {
+            return true;
+        }
+        undo_to(board, trail, trail.len() - 1);
+        if guessing {
+            trail.log(Step::Backtrack { row, col });
+        }
+    }
+
+    if guessing {
+        trail.depth -= 1;
+    }
+    false
+}
+
+/// The square to guess at when deduction is not enough, which is the last
+/// empty square that could be filled multiple ways.
+pub(crate) fn guess_square<B: Board>(board: &B) -> usize {
+    let size = board.size();
+    let mut last = 0;
+    for row in 0..size {
+        for col in 0..size {
+            let square = row * size + col;
+            if board.cell(row, col).is_none() && board.candidate_bits(square).count_ones() > 1 {
+                last = square;
+            }
+        }
+    }
+    last
+}
+
+/// The first empty square with the fewest candidates.
+fn fewest_square<B: Board>(board: &B) -> usize {
+    let size = board.size();
+    let mut best: Option<(usize, u32)> = None;
+    for row in 0..size {
+        for col in 0..size {
+            if board.cell(row, col).is_some() {
+                continue;
+            }
+            let square = row * size + col;
+            let count = board.candidate_bits(square).count_ones();
+            if best.is_none_or(|(_, fewest)| count < fewest) {
+                best = Some((square, count));
+                if count <= 1 {
+                    return square;
+                }
+            }
+        }
+    }
+    best.map_or(0, |(square, _)| square)
+}
+
+/// Empty every square recorded on the trail after its first len entries.
+pub(crate) fn undo_to<B: Board>(board: &mut B, trail: &mut Trail, len: usize) {
+    let size = board.size();
+    while trail.len() > len {
+        let square = trail.pop().unwrap();
+        board.set_cell(square / size, square % size, None);
+    }
+}
+
+/// Fill every square that can be filled by iterative deduction, recording
+/// each one on the trail. Return `false` if some square could never be
+/// filled or the board is no longer consistent.
+pub(crate) fn fill_all<B: Board>(board: &mut B, trail: &mut Trail) -> bool {
+    loop {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("pass", unfilled = count_unfilled(board)).entered();
+        // Only look for hidden singles once there are no naked singles
+        let filled = match fill_once(board, trail) {
+            Some(0) if trail.hidden_singles => fill_hidden(board, trail),
+            filled => filled,
+        };
+        match filled {
+            Some(0) => return board.is_consistent(),
+            Some(_) if board.is_consistent() => {}
+            _ => return false,
+        }
+    }
+}
+
+/// Do one pass of the board and fill any symbols that can be deduced,
+/// returning how many squares were filled.
+/// Return `None` if some square could never be filled while maintaining
+/// consistency with the other squares that have already been filled.
+fn fill_once<B: Board>(board: &mut B, trail: &mut Trail) -> Option<usize> {
+    let size = board.size();
+    let mut filled = 0;
+    for row in 0..size {
+        for col in 0..size {
+            if board.cell(row, col).is_some() {
+                continue;
+            }
+            let square = row * size + col;
+            let candidates = board.candidate_bits(square);
+            match candidates.count_ones() {
+                0 => return None,
+                1 => {
+                    let n = symbol_of(board, candidates);
+                    board.set_cell(row, col, Some(n));
+                    trail.push(square);
+                    trail.log(Step::Single { row, col, n });
+                    filled += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Some(filled)
+}
+
+/// Do one pass over the units of the board and fill any symbol that could
+/// only go in one square of one of them, returning how many squares were
+/// filled.
+/// Return `None` if some symbol could not go anywhere in some unit.
+fn fill_hidden<B: Board>(board: &mut B, trail: &mut Trail) -> Option<usize> {
+    let size = board.size();
+    let all = all_symbols(board);
+    let mut filled = 0;
+    for i in 0..board.unit_count() {
+        let (mut placed, mut once, mut twice) = (0, 0, 0);
+        for square in board.unit(i).iter() {
+            match board.cell(square / size, square % size) {
+                Some(n) => placed |= symbol_bit(board, n),
+                None => {
+                    let could_be = board.candidate_bits(*square);
+                    twice |= once & could_be;
+                    once |= could_be;
+                }
+            }
+        }
+        if placed | once != all {
+            return None;
+        }
+
+        let mut singles = once & !twice & !placed;
+        while singles != 0 {
+            let bit = singles & singles.wrapping_neg();
+            singles &= !bit;
+            // A symbol placed earlier in this pass may have taken its square
+            let square = board.unit(i).iter().copied().find(|square| {
+                board.cell(square / size, square % size).is_none()
+                    && board.candidate_bits(*square) & bit != 0
+            });
+            if let Some(square) = square {
+                let (row, col) = (square / size, square % size);
+                let n = symbol_of(board, bit);
+                board.set_cell(row, col, Some(n));
+                trail.push(square);
+                trail.log(match board.named_unit(i) {
+                    Some(unit) => Step::HiddenSingle { row, col, n, unit },
+                    None => Step::Single { row, col, n },
+                });
+                filled += 1;
+            }
+        }
+    }
+
+    Some(filled)
+}
+
+/// Indicates whether every square of board is filled.
+fn is_filled<B: Board>(board: &B) -> bool {
+    let size = board.size();
+    (0..size).all(|row| (0..size).all(|col| board.cell(row, col).is_some()))
+}
+
+/// How many squares of board are empty.
+#[cfg(feature = "tracing")]
+fn count_unfilled<B: Board>(board: &B) -> usize {
+    let size = board.size();
+    (0..size)
+        .flat_map(|row| (0..size).map(move |col| (row, col)))
+        .filter(|(row, col)| board.cell(*row, *col).is_none())
+        .count()
+}
+
+/// The bit standing for n in a set of a board's symbols.
+fn symbol_bit<B: Board + ?Sized>(board: &B, n: u8) -> u32 {
+    1 << (n - board.symbols().start())
+}
+
+/// The lowest symbol in a set of a board's symbols.
+fn symbol_of<B: Board + ?Sized>(board: &B, bits: u32) -> u8 {
+    board.symbols().start() + bits.trailing_zeros() as u8
+}
+
+/// The set of all of a board's symbols.
+fn all_symbols<B: Board + ?Sized>(board: &B) -> u32 {
+    board.symbols().fold(0, |all, n| all | symbol_bit(board, n))
+}
+
+/// The squares filled during a search, in the order they were filled, so
+/// that they can be emptied again when backtracking.
+///
+/// A square is only on the trail while it is filled, so the trail never
+/// holds more than a board's squares and can live entirely on the stack.
+pub(crate) struct Trail<'a> {
+    pub(crate) squares: [u16; MAX_SQUARES],
+    pub(crate) len: usize,
+    /// Every step of the search, if it is being logged.
+    pub(crate) steps: Option<Vec<Step>>,
+    /// Set from another thread to make the search give up.
+    pub(crate) stop: Option<&'a AtomicBool>,
+    /// How many more guesses the search may make, if it is limited.
+    pub(crate) guesses_left: Option<usize>,
+    /// Whether the search gave up because it ran out of guesses.
+    pub(crate) exhausted: bool,
+    /// How many guesses the search has made.
+    pub(crate) guesses: u64,
+    /// Whether to fill squares by deduction before guessing.
+    pub(crate) deduce: bool,
+    /// Whether to look for hidden singles once there are no naked singles.
+    pub(crate) hidden_singles: bool,
+    /// Whether to guess at the square with the fewest candidates rather than
+    /// the last square with several.
+    pub(crate) fewest_first: bool,
+    /// The limits the search must stay within, and the first it broke.
+    pub(crate) limits: SolverLimits,
+    pub(crate) exceeded: Option<LimitExceeded>,
+    /// How many points of the search have been visited.
+    pub(crate) nodes: u64,
+    /// How many guesses are on top of each other.
+    pub(crate) depth: usize,
+    /// When the search started, if it has a time limit.
+    #[cfg(feature = "std")]
+    pub(crate) started: Option<std::time::Instant>,
+    /// Count solutions until this many have been found, instead of stopping
+    /// at the first.
+    pub(crate) count_to: Option<usize>,
+    pub(crate) found: usize,
+}
+
+impl Trail<'_> {
+    pub(crate) fn new() -> Self {
+        Trail {
+            squares: [0; MAX_SQUARES],
+            len: 0,
+            steps: None,
+            stop: None,
+            guesses_left: None,
+            exhausted: false,
+            guesses: 0,
+            deduce: true,
+            hidden_singles: true,
+            fewest_first: false,
+            limits: SolverLimits::default(),
+            exceeded: None,
+            nodes: 0,
+            depth: 0,
+            #[cfg(feature = "std")]
+            started: None,
+            count_to: None,
+            found: 0,
+        }
+    }
+
+    /// A trail for a search that gives up once it breaks one of limits.
+    pub(crate) fn within(limits: &SolverLimits) -> Self {
+        Trail {
+            limits: *limits,
+            #[cfg(feature = "std")]
+            started: limits.max_time.map(|_| std::time::Instant::now()),
+            ..Trail::new()
+        }
+    }
+
+    /// A trail that also logs every step of the search.
+    pub(crate) fn logged() -> Self {
+        Trail {
+            steps: Some(Vec::new()),
+            ..Trail::new()
+        }
+    }
+
+    fn log(&mut self, step: Step) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(%step);
+        if let Some(steps) = &mut self.steps {
+            steps.push(step);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn stopped(&self) -> bool {
+        self.exceeded.is_some() || self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+
+    /// Count a point of the search, returning `false` if that breaks a
+    /// limit.
+    fn visit(&mut self) -> bool {
+        self.nodes += 1;
+        if let Some(max) = self.limits.max_nodes.filter(|max| self.nodes > *max) {
+            self.exceeded = Some(LimitExceeded::Nodes { max });
+        }
+        #[cfg(feature = "std")]
+        if let (Some(max), Some(started)) = (self.limits.max_time, self.started) {
+            if started.elapsed() > max {
+                self.exceeded = Some(LimitExceeded::Time { max });
+            }
+        }
+        self.exceeded.is_none()
+    }
+
+    /// Start guessing on top of the guesses so far, returning `false` if
+    /// that breaks a limit.
+    fn descend(&mut self) -> bool {
+        if let Some(max) = self.limits.max_guess_depth.filter(|max| self.depth >= *max) {
+            self.exceeded = Some(LimitExceeded::GuessDepth { max });
+            return false;
+        }
+        self.depth += 1;
+        true
+    }
+
+    /// Note that every square has been filled, returning `true` if the
+    /// search should stop there.
+    fn solved(&mut self) -> bool {
+        self.found += 1;
+        self.count_to.is_none_or(|limit| self.found >= limit)
+    }
+
+    /// Use up one of the guesses left, returning `false` if there are none.
+    fn spend_guess(&mut self) -> bool {
+        match &mut self.guesses_left {
+            Some(0) => {
+                self.exhausted = true;
+                return false;
+            }
+            Some(left) => *left -= 1,
+            None => {}
+        }
+        self.guesses += 1;
+        true
+    }
+
+    fn push(&mut self, square: usize) {
+        self.squares[self.len] = square as u16;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(usize::from(self.squares[self.len]))
+    }
+}
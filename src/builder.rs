@@ -0,0 +1,62 @@
+use crate::{
+    error::{check_number, Error, PlacementError},
+    puzzle::SudokuPuzzle,
+};
+use alloc::vec::Vec;
+
+/// Constructs a SudokuPuzzle one clue at a time.
+///
+/// ```
+/// # use sudoku::builder::SudokuPuzzleBuilder;
+/// let puzzle = SudokuPuzzleBuilder::new()
+///     .clue(0, 0, 4)
+///     .clue(0, 1, 1)
+///     .clue(8, 8, 9)
+///     .build()
+///     .unwrap();
+/// assert_eq!(Some(1), puzzle.get(0, 1));
+///
+/// let conflicting = SudokuPuzzleBuilder::new().clue(0, 0, 4).clue(0, 8, 4).build();
+/// assert!(conflicting.is_err());
+/// ```
+#[derive(Clone, Default)]
+pub struct SudokuPuzzleBuilder {
+    clues: Vec<(usize, usize, u8)>,
+}
+
+impl SudokuPuzzleBuilder {
+    /// Create a builder with no clues.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fill the square at (row, col) with n. row and column must each be
+    /// in the range [0, 9) and n must be in the range [1,9].
+    pub fn clue(mut self, row: usize, col: usize, n: u8) -> Self {
+        self.clues.push((row, col, n));
+        self
+    }
+
+    /// Create the puzzle described by the clues, provided that the clues
+    /// are all valid and consistent with one another.
+    pub fn build(self) -> Result<SudokuPuzzle, Error> {
+        let mut puzzle = SudokuPuzzle::empty();
+
+        for (row, col, n) in self.clues {
+            check_number(row, col, n)?;
+            match puzzle.get(row, col) {
+                Some(existing) if existing != n => {
+                    return Err(PlacementError::Overlapping { row, col }.into())
+                }
+                _ => puzzle.set(row, col, Some(n))?,
+            }
+        }
+
+        if puzzle.is_consistent() {
+            puzzle.fix_givens();
+            Ok(puzzle)
+        } else {
+            Err(PlacementError::Inconsistent.into())
+        }
+    }
+}
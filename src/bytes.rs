@@ -0,0 +1,92 @@
+//! Helpers for the binary files that databases and caches are saved as, in
+//! which every number is little-endian and a grid's 81 squares are packed
+//! two to a byte, first in the high half.
+
+use crate::error::ParseError;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+/// Append the squares of a grid, packed into 41 bytes.
+pub(crate) fn push_squares(bytes: &mut Vec<u8>, nums: &[u8]) {
+    bytes.extend(
+        nums.chunks(2)
+            .map(|pair| pair[0] << 4 | pair.get(1).unwrap_or(&0)),
+    );
+}
+
+/// A position in a saved file.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    pub(crate) offset: usize,
+    /// The error for a file that is not valid from an offset on.
+    invalid: fn(usize) -> ParseError,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8], invalid: fn(usize) -> ParseError) -> Self {
+        Reader {
+            bytes,
+            offset: 0,
+            invalid,
+        }
+    }
+
+    /// The error for a file that is not valid from offset on.
+    pub(crate) fn invalid(&self, offset: usize) -> ParseError {
+        (self.invalid)(offset)
+    }
+
+    /// The next len bytes.
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        let taken = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .ok_or_else(|| self.invalid(self.offset))?;
+        self.offset += len;
+        Ok(taken)
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8, ParseError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, ParseError> {
+        Ok(u16::from_le_bytes(
+            <[u8; 2]>::try_from(self.take(2)?).unwrap(),
+        ))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, ParseError> {
+        Ok(u32::from_le_bytes(
+            <[u8; 4]>::try_from(self.take(4)?).unwrap(),
+        ))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, ParseError> {
+        Ok(u64::from_le_bytes(
+            <[u8; 8]>::try_from(self.take(8)?).unwrap(),
+        ))
+    }
+
+    /// The squares of a grid written by `push_squares`, which are not
+    /// checked to be in the range [0,9].
+    pub(crate) fn squares(&mut self) -> Result<[u8; 81], ParseError> {
+        let mut nums = [0; 81];
+        for (i, byte) in self.take(41)?.iter().enumerate() {
+            nums[i * 2] = byte >> 4;
+            if let Some(n) = nums.get_mut(i * 2 + 1) {
+                *n = byte & 0xf;
+            }
+        }
+        Ok(nums)
+    }
+
+    /// Check that the whole file has been read.
+    pub(crate) fn finish(&self) -> Result<(), ParseError> {
+        if self.offset == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(self.invalid(self.offset))
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Remembered solutions and solution counts, so that puzzles that come up
+//! again are not solved again. A cache can be saved as bytes with
+//! `to_bytes` and read back with `from_bytes` to keep it between runs.
+//!
+//! ```
+//! # use sudoku::{cache::{Key, SolveCache}, puzzle::SudokuPuzzle, transform::Transform};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let mut cache = SolveCache::new(Key::Canonical);
+//! assert!(cache.is_unique(&puzzle));
+//!
+//! // An equivalent puzzle has the same canonical form, so its count is known
+//! let equivalent = puzzle.transform(Transform::Transpose).unwrap();
+//! assert!(cache.is_unique(&equivalent));
+//! assert_eq!((1, 1), (cache.hits(), cache.misses()));
+//!
+//! let cache = SolveCache::from_bytes(&cache.to_bytes()).unwrap();
+//! assert_eq!(1, cache.len());
+//! ```
+
+use crate::{
+    bytes::{push_squares, Reader},
+    error::{LimitExceeded, ParseError},
+    limits::SolverLimits,
+    puzzle::SudokuPuzzle,
+    solution::Solution,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::convert::TryFrom;
+
+/// The first bytes of a saved cache, followed by the version of the format.
+const MAGIC: &[u8; 4] = b"SUCA";
+const VERSION: u8 = 1;
+
+/// What solution counts are remembered by.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Key {
+    /// The puzzle's squares, which are cheap to compare but only match the
+    /// same puzzle.
+    Exact,
+    /// The puzzle's canonical form, which every equivalent puzzle shares.
+    /// Finding it takes far longer than solving most puzzles, so this only
+    /// pays off when the puzzles are hard and many are equivalent.
+    Canonical,
+}
+
+/// A number of solutions, counted up to a limit.
+#[derive(Debug, Clone, Copy)]
+struct Count {
+    count: usize,
+    limit: usize,
+}
+
+/// Solutions and solution counts of the puzzles seen so far. Solutions are
+/// always remembered by the puzzle's squares, since they change when the
+/// puzzle is rearranged; solution counts are remembered by the cache's
+/// [`Key`].
+#[derive(Debug, Clone)]
+pub struct SolveCache {
+    key: Key,
+    solutions: BTreeMap<[u8; 81], Option<Solution>>,
+    counts: BTreeMap<[u8; 81], Count>,
+    hits: usize,
+    misses: usize,
+}
+
+impl SolveCache {
+    /// Create an empty cache that remembers solution counts by key.
+    pub fn new(key: Key) -> Self {
+        SolveCache {
+            key,
+            solutions: BTreeMap::new(),
+            counts: BTreeMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// What solution counts are remembered by.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    /// How many solutions and solution counts are remembered.
+    pub fn len(&self) -> usize {
+        self.solutions.len() + self.counts.len()
+    }
+
+    /// Indicates whether nothing is remembered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many lookups were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// How many lookups had to solve the puzzle.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Solve puzzle like [`SudokuPuzzle::solve`], or return its solution
+    /// from the last time it was solved.
+    pub fn solve(&mut self, puzzle: &SudokuPuzzle) -> Option<Solution> {
+        let squares = squares(puzzle);
+        if let Some(solution) = self.solutions.get(&squares) {
+            self.hits += 1;
+            return *solution;
+        }
+
+        self.misses += 1;
+        let solution = puzzle.solve();
+        self.solutions.insert(squares, solution);
+        solution
+    }
+
+    /// Count the solutions of puzzle like [`SudokuPuzzle::count_solutions`],
+    /// or return the count from the last time, as long as it was counted
+    /// far enough to answer for limit.
+    ///
+    /// ```
+    /// # use sudoku::{cache::{Key, SolveCache}, puzzle::SudokuPuzzle};
+    /// let mut cache = SolveCache::new(Key::Exact);
+    /// let puzzle = SudokuPuzzle::empty();
+    /// assert_eq!(5, cache.count_solutions(&puzzle, 5));
+    /// assert_eq!(2, cache.count_solutions(&puzzle, 2));
+    /// assert_eq!(6, cache.count_solutions(&puzzle, 6));
+    /// assert_eq!((1, 2), (cache.hits(), cache.misses()));
+    /// ```
+    pub fn count_solutions(&mut self, puzzle: &SudokuPuzzle, limit: usize) -> usize {
+        self.count_solutions_within(puzzle, limit, &SolverLimits::default())
+            .expect("a search without limits never breaks one")
+    }
+
+    /// Count the solutions of puzzle like `count_solutions`, giving up once
+    /// the search breaks one of limits. A search that gives up is not
+    /// remembered.
+    ///
+    /// ```
+    /// # use sudoku::{cache::{Key, SolveCache}, limits::SolverLimits, puzzle::SudokuPuzzle};
+    /// let mut cache = SolveCache::new(Key::Exact);
+    /// let puzzle = SudokuPuzzle::empty();
+    /// let limits = SolverLimits { max_nodes: Some(10), ..SolverLimits::default() };
+    /// assert!(cache.count_solutions_within(&puzzle, 2, &limits).is_err());
+    /// assert_eq!(2, cache.count_solutions(&puzzle, 2));
+    /// assert_eq!(Ok(2), cache.count_solutions_within(&puzzle, 2, &limits));
+    /// ```
+    pub fn count_solutions_within(
+        &mut self,
+        puzzle: &SudokuPuzzle,
+        limit: usize,
+        limits: &SolverLimits,
+    ) -> Result<usize, LimitExceeded> {
+        let key = match self.key {
+            Key::Exact => squares(puzzle),
+            Key::Canonical => squares(&puzzle.canonical()),
+        };
+        if let Some(known) = self.counts.get(&key) {
+            // Below its limit, a count is exact
+            if known.count < known.limit || limit <= known.limit {
+                self.hits += 1;
+                return Ok(known.count.min(limit));
+            }
+        }
+
+        self.misses += 1;
+        let count = puzzle.count_solutions_within(limit, limits)?;
+        self.counts.insert(key, Count { count, limit });
+        Ok(count)
+    }
+
+    /// Indicates whether puzzle has exactly one solution.
+    pub fn is_unique(&mut self, puzzle: &SudokuPuzzle) -> bool {
+        self.count_solutions(puzzle, 2) == 1
+    }
+
+    /// The cache as bytes, to be written to a file.
+    ///
+    /// After the magic bytes "SUCA", a version byte, and the key as a byte
+    /// (0 for exact, 1 for canonical) come the number of solutions as a u32
+    /// and each puzzle as 41 bytes followed by its solution as 41 bytes, all
+    /// 0 if it has none. Then come the number of counts as a u32 and each key
+    /// as 41 bytes followed by its count and limit as u32s. Every number is
+    /// little-endian, and squares are packed two to a byte, first in the
+    /// high half.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::from(&MAGIC[..]);
+        bytes.push(VERSION);
+        bytes.push(match self.key {
+            Key::Exact => 0,
+            Key::Canonical => 1,
+        });
+
+        bytes.extend_from_slice(&(self.solutions.len() as u32).to_le_bytes());
+        for (squares, solution) in &self.solutions {
+            push_squares(&mut bytes, squares);
+            match solution {
+                Some(solution) => push_squares(&mut bytes, &solution.to_vec()),
+                None => push_squares(&mut bytes, &[0; 81]),
+            }
+        }
+
+        bytes.extend_from_slice(&(self.counts.len() as u32).to_le_bytes());
+        for (key, count) in &self.counts {
+            push_squares(&mut bytes, key);
+            for n in [count.count, count.limit].iter() {
+                bytes.extend_from_slice(&((*n).min(u32::MAX as usize) as u32).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Read a cache saved with `to_bytes`. Its hits and misses start at 0.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(bytes, |offset| ParseError::InvalidCache { offset });
+        if reader.take(4)? != MAGIC || reader.take(1)? != [VERSION] {
+            return Err(reader.invalid(0));
+        }
+        let mut cache = match reader.byte()? {
+            0 => SolveCache::new(Key::Exact),
+            1 => SolveCache::new(Key::Canonical),
+            _ => return Err(reader.invalid(5)),
+        };
+
+        for _ in 0..reader.u32()? {
+            let invalid = reader.invalid(reader.offset);
+            let squares = reader.squares()?;
+            let solved = reader.squares()?;
+            let solution = if solved == [0; 81] {
+                None
+            } else {
+                let solved = SudokuPuzzle::try_from(&solved[..]).map_err(|_| invalid)?;
+                if !solved.is_solved() {
+                    return Err(invalid);
+                }
+                Some(Solution::new(&solved))
+            };
+            if squares.iter().any(|n| *n > 9) {
+                return Err(invalid);
+            }
+            cache.solutions.insert(squares, solution);
+        }
+
+        for _ in 0..reader.u32()? {
+            let invalid = reader.invalid(reader.offset);
+            let key = reader.squares()?;
+            let count = reader.u32()? as usize;
+            let limit = reader.u32()? as usize;
+            if key.iter().any(|n| *n > 9) || count > limit {
+                return Err(invalid);
+            }
+            cache.counts.insert(key, Count { count, limit });
+        }
+
+        reader.finish()?;
+        Ok(cache)
+    }
+}
+
+/// The squares of puzzle, where 0 is an empty square.
+fn squares(puzzle: &SudokuPuzzle) -> [u8; 81] {
+    <[u8; 81]>::try_from(&puzzle.to_vec()[..]).expect("puzzles have 81 squares")
+}
@@ -0,0 +1,176 @@
+//! Checking ratings against puzzles rated on another scale, such as the
+//! Sudoku Explainer ratings published with many puzzle collections, to see
+//! how well the two agree and to tune `RatingConfig` until they do.
+//!
+//! ```
+//! # use sudoku::{calibration::{self, Bands, Sample}, puzzle::SudokuPuzzle, rating::RatingConfig};
+//! let samples: Vec<Sample> = [
+//!     ("003020600900305001001806400008102900700000008006708200002609500800203009005010300", 1.2),
+//!     ("410036000007000850600000000090000200006070008000000091002014000000003000740008509", 2.0),
+//!     ("800000000003600000070090200050007000000045700000100030001000068008500010090000400", 10.5),
+//! ]
+//! .iter()
+//! .map(|(text, rating)| Sample {
+//!     puzzle: SudokuPuzzle::from_string(text).unwrap(),
+//!     rating: *rating,
+//! })
+//! .collect();
+//!
+//! let report = calibration::calibrate(&samples, &Bands([1.5, 4.0, 7.0]), &RatingConfig::default());
+//! assert_eq!(3, report.rated);
+//! assert_eq!(1.0, report.correlation);
+//! assert_eq!(1.0, report.accuracy());
+//! ```
+
+use crate::{
+    batch,
+    puzzle::SudokuPuzzle,
+    rating::{Difficulty, RatingConfig},
+};
+
+/// A puzzle and its rating on another scale.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub puzzle: SudokuPuzzle,
+    pub rating: f64,
+}
+
+/// Where each difficulty starts on another scale: the lowest rating of a
+/// medium, a hard, and an expert puzzle, in that order.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Bands(pub [f64; 3]);
+
+impl Bands {
+    /// The difficulty that a rating on the other scale falls in.
+    ///
+    /// ```
+    /// # use sudoku::{calibration::Bands, rating::Difficulty};
+    /// let bands = Bands([1.5, 4.0, 7.0]);
+    /// assert_eq!(Difficulty::Easy, bands.difficulty(1.2));
+    /// assert_eq!(Difficulty::Hard, bands.difficulty(4.0));
+    /// ```
+    pub fn difficulty(&self, rating: f64) -> Difficulty {
+        let above = self.0.iter().filter(|start| rating >= **start).count();
+        Difficulty::ALL[above]
+    }
+}
+
+/// A sample that was rated a different difficulty than its band.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Misclassified {
+    /// The position of the sample in the samples given.
+    pub index: usize,
+    /// The sample's rating on the other scale.
+    pub rating: f64,
+    /// The difficulty of the band that rating falls in.
+    pub expected: Difficulty,
+    /// The difficulty it was rated instead.
+    pub actual: Difficulty,
+    pub score: u32,
+}
+
+/// How well ratings agree with another scale.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Report {
+    /// How many samples have a solution and were rated.
+    pub rated: usize,
+    /// The positions of the samples with no solution, which are left out of
+    /// everything else.
+    pub unsolvable: Vec<usize>,
+    /// The Spearman rank correlation between the scores and the other
+    /// ratings, from -1 to 1, or NaN when fewer than two samples were rated
+    /// or either side has only one value.
+    pub correlation: f64,
+    /// How many samples in the band of each difficulty (the row) were rated
+    /// each difficulty (the column), in the order of `Difficulty::ALL`.
+    pub confusion: [[usize; 4]; 4],
+    pub misclassified: Vec<Misclassified>,
+}
+
+impl Report {
+    /// The fraction of rated samples that were rated the difficulty of
+    /// their band, or NaN when none were rated.
+    pub fn accuracy(&self) -> f64 {
+        (self.rated - self.misclassified.len()) as f64 / self.rated as f64
+    }
+}
+
+/// Rate every sample with the weights in config, and compare the ratings
+/// with the samples' own.
+pub fn calibrate(samples: &[Sample], bands: &Bands, config: &RatingConfig) -> Report {
+    let mut report = Report {
+        rated: 0,
+        unsolvable: Vec::new(),
+        correlation: f64::NAN,
+        confusion: [[0; 4]; 4],
+        misclassified: Vec::new(),
+    };
+    let mut scores = Vec::new();
+    let mut ratings = Vec::new();
+    let puzzles: Vec<SudokuPuzzle> = samples.iter().map(|sample| sample.puzzle.clone()).collect();
+    let rated = batch::rate_all(&puzzles, config);
+    for (index, (sample, rating)) in samples.iter().zip(rated).enumerate() {
+        let rating = match rating {
+            Some(rating) => rating,
+            None => {
+                report.unsolvable.push(index);
+                continue;
+            }
+        };
+        report.rated += 1;
+        scores.push(f64::from(rating.score));
+        ratings.push(sample.rating);
+
+        let expected = bands.difficulty(sample.rating);
+        report.confusion[expected as usize][rating.difficulty as usize] += 1;
+        if expected != rating.difficulty {
+            report.misclassified.push(Misclassified {
+                index,
+                rating: sample.rating,
+                expected,
+                actual: rating.difficulty,
+                score: rating.score,
+            });
+        }
+    }
+
+    if report.rated > 1 {
+        report.correlation = pearson(&ranks(&scores), &ranks(&ratings));
+    }
+    report
+}
+
+/// The rank of each value from 1, where tied values share the average of
+/// the ranks they cover.
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for i in &order[start..end] {
+            ranks[*i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// The Pearson correlation of two equally long lists.
+fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in x.iter().zip(y) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x) * (x - mean_x);
+        variance_y += (y - mean_y) * (y - mean_y);
+    }
+    covariance / (variance_x * variance_y).sqrt()
+}
@@ -0,0 +1,147 @@
+//! The minlex canonical form of a grid: the smallest 81-digit string that
+//! it can be turned into by the transformations that preserve Sudoku.
+
+use alloc::vec::Vec;
+
+/// Every ordering of three things.
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+/// Every order of the nine rows (or columns) that keeps rows within bands:
+/// the bands may be reordered, as may the rows within each band.
+fn line_orders() -> Vec<[usize; 9]> {
+    let mut orders = Vec::with_capacity(6 * 6 * 6 * 6);
+    for bands in PERMUTATIONS.iter() {
+        for first in PERMUTATIONS.iter() {
+            for second in PERMUTATIONS.iter() {
+                for third in PERMUTATIONS.iter() {
+                    let within = [first, second, third];
+                    let mut order = [0; 9];
+                    for (i, line) in order.iter_mut().enumerate() {
+                        *line = bands[i / 3] * 3 + within[i / 3][i % 3];
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+/// The minlex form of nums, a row-major grid where 0 is an empty square.
+///
+/// Rows within a band, bands, columns within a stack, and stacks can be
+/// reordered, the grid can be transposed, and the digits relabeled. Of
+/// every grid that can be reached that way, this is the one that is first
+/// when read as a string of digits, with empty squares as 0.
+pub(crate) fn minlex(nums: &[u8; 81]) -> [u8; 81] {
+    let mut transposed = [0; 81];
+    for (square, n) in transposed.iter_mut().enumerate() {
+        *n = nums[(square % 9) * 9 + square / 9];
+    }
+
+    let orders = line_orders();
+    let mut best = [10; 81];
+    for grid in [nums, &transposed].iter() {
+        for cols in &orders {
+            let mut search = Search {
+                grid,
+                cols,
+                best: &mut best,
+            };
+            search.place_row(0, 0, 0, 0, Labels::default());
+        }
+    }
+    best
+}
+
+/// The digits relabeled so far, in the order they first appeared.
+#[derive(Clone, Copy, Default)]
+struct Labels {
+    labels: [u8; 10],
+    next: u8,
+}
+
+impl Labels {
+    fn relabel(&mut self, n: u8) -> u8 {
+        if n != 0 && self.labels[n as usize] == 0 {
+            self.next += 1;
+            self.labels[n as usize] = self.next;
+        }
+        self.labels[n as usize]
+    }
+}
+
+/// A search for the smallest order of rows for a grid with its columns in a
+/// fixed order.
+struct Search<'a> {
+    grid: &'a [u8; 81],
+    cols: &'a [usize; 9],
+    /// The smallest grid found so far. Once a row is found that is smaller
+    /// than the one in best, it replaces it and the rows after it are set
+    /// to 10, larger than any row, until the search fills them in.
+    best: &'a mut [u8; 81],
+}
+
+impl Search<'_> {
+    /// Try every row allowed at position depth and go on to the next
+    /// position from each one that is not larger than the best row there.
+    /// Rows must come from a band not in bands_used when starting a band,
+    /// and otherwise from the current band, without repeating rows_used.
+    fn place_row(
+        &mut self,
+        depth: usize,
+        bands_used: u8,
+        band: usize,
+        rows_used: u8,
+        labels: Labels,
+    ) {
+        if depth == 9 {
+            return;
+        }
+
+        for row in 0..9 {
+            let starts_band = depth.is_multiple_of(3);
+            let allowed = if starts_band {
+                bands_used & (1 << (row / 3)) == 0
+            } else {
+                row / 3 == band && rows_used & (1 << (row % 3)) == 0
+            };
+            if !allowed {
+                continue;
+            }
+
+            let mut labels = labels;
+            let mut line = [0; 9];
+            for (col, label) in line.iter_mut().enumerate() {
+                *label = labels.relabel(self.grid[row * 9 + self.cols[col]]);
+            }
+
+            let best = &mut self.best[depth * 9..depth * 9 + 9];
+            if line[..] > *best {
+                continue;
+            }
+            if line[..] < *best {
+                best.copy_from_slice(&line);
+                for label in self.best[depth * 9 + 9..].iter_mut() {
+                    *label = 10;
+                }
+            }
+
+            let rows_used = if starts_band { 0 } else { rows_used } | 1 << (row % 3);
+            self.place_row(
+                depth + 1,
+                bands_used | 1 << (row / 3),
+                row / 3,
+                rows_used,
+                labels,
+            );
+        }
+    }
+}
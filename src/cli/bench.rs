@@ -0,0 +1,157 @@
+//! `sudoku bench`: time the solver on a set of puzzles.
+
+use crate::cli::{exit::Exit, input::Source, output::Format};
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use sudoku::{puzzle::SudokuPuzzle, step::Step};
+
+/// The puzzles solved when no file is given, from easy to very hard.
+const PUZZLES: [&str; 5] = [
+    "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+    "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    "000000010400000000020000000000050407008000300001090000300400200050100000000806000",
+    "400000805030000000000700000020000060000080400000010000000603070500200000104000000",
+    "520006000000000701300000000000400800600000050000000000041800000000030020008700000",
+];
+
+#[derive(clap::Args)]
+pub struct BenchArgs {
+    /// A file of puzzles to solve, read the same way as for solve. A
+    /// built-in set of puzzles is used if no file is given.
+    pub file: Option<PathBuf>,
+
+    /// How many times to solve each puzzle
+    #[arg(long, value_name = "N", default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub repeat: u64,
+}
+
+/// Read the puzzles in file, or the built-in set if there is no file,
+/// skipping invalid puzzles with a warning and a `ParseError` exit.
+pub fn load(file: Option<&PathBuf>) -> io::Result<(Vec<SudokuPuzzle>, Exit)> {
+    let sources: Vec<String> = match file {
+        Some(file) => Source {
+            file: Some(file.clone()),
+            puzzle: None,
+        }
+        .puzzles()?
+        .collect::<io::Result<_>>()?,
+        None => PUZZLES.iter().map(|puzzle| String::from(*puzzle)).collect(),
+    };
+
+    let mut exit = Exit::Success;
+    let mut puzzles = Vec::new();
+    for source in &sources {
+        match SudokuPuzzle::from_string(source) {
+            Ok(puzzle) => puzzles.push(puzzle),
+            Err(e) => {
+                eprintln!("Skipping invalid puzzle {}: {}", source, e);
+                exit = exit.max(Exit::ParseError);
+            }
+        }
+    }
+    Ok((puzzles, exit))
+}
+
+/// Solve every puzzle, timing each solve, and report throughput, solve
+/// times, and how much guessing the solver needed.
+pub fn run(args: &BenchArgs, format: Format) -> io::Result<Exit> {
+    let (puzzles, mut exit) = load(args.file.as_ref())?;
+
+    // Steps are counted in a separate, untimed solve, since recording them
+    // slows the solver down
+    let (mut guesses, mut backtracks) = (0, 0);
+    for puzzle in &puzzles {
+        let (solution, steps) = puzzle.solve_with_steps();
+        if solution.is_none() {
+            exit = exit.max(Exit::Unsolvable);
+        }
+        for step in steps {
+            match step {
+                Step::Guess { .. } => guesses += 1,
+                Step::Backtrack { .. } => backtracks += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let mut times = Vec::new();
+    for _ in 0..args.repeat {
+        for puzzle in &puzzles {
+            let start = Instant::now();
+            std::hint::black_box(std::hint::black_box(puzzle).solve());
+            times.push(start.elapsed());
+        }
+    }
+    times.sort();
+
+    let total: Duration = times.iter().sum();
+    let solves = times.len() as u32;
+    let mean = total.checked_div(solves).unwrap_or_default();
+    let median = times.get(times.len() / 2).copied().unwrap_or_default();
+    let max = times.last().copied().unwrap_or_default();
+    let per_second = match total.as_secs_f64() {
+        secs if secs > 0.0 => f64::from(solves) / secs,
+        _ => 0.0,
+    };
+
+    let mut out = io::stdout().lock();
+    match format {
+        Format::Grid | Format::Line | Format::Spoken => {
+            writeln!(
+                out,
+                "{} puzzles solved {} times each in {:.3?}",
+                puzzles.len(),
+                args.repeat,
+                total
+            )?;
+            writeln!(out, "{:.0} puzzles/second", per_second)?;
+            writeln!(
+                out,
+                "solve time: mean {:.3?}, median {:.3?}, max {:.3?}",
+                mean, median, max
+            )?;
+            writeln!(
+                out,
+                "per pass: {} guesses, {} backtracks",
+                guesses, backtracks
+            )?;
+        }
+        Format::Json => writeln!(
+            out,
+            "{}",
+            json!({
+                "puzzles": puzzles.len(),
+                "repeat": args.repeat,
+                "total_us": total.as_micros() as u64,
+                "puzzles_per_second": per_second.round(),
+                "mean_ns": mean.as_nanos() as u64,
+                "median_ns": median.as_nanos() as u64,
+                "max_ns": max.as_nanos() as u64,
+                "guesses": guesses,
+                "backtracks": backtracks,
+            })
+        )?,
+        Format::Csv => {
+            writeln!(out, "puzzles,repeat,total_us,puzzles_per_second,mean_ns,median_ns,max_ns,guesses,backtracks")?;
+            writeln!(
+                out,
+                "{},{},{},{:.0},{},{},{},{},{}",
+                puzzles.len(),
+                args.repeat,
+                total.as_micros(),
+                per_second,
+                mean.as_nanos(),
+                median.as_nanos(),
+                max.as_nanos(),
+                guesses,
+                backtracks
+            )?;
+        }
+    }
+
+    Ok(exit)
+}
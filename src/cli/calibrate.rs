@@ -0,0 +1,196 @@
+//! `sudoku calibrate`: compare ratings with those of a rated puzzle list.
+
+use crate::cli::{exit::Exit, output::Format};
+use serde_json::json;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+use sudoku::{
+    calibration::{self, Bands, Sample},
+    puzzle::SudokuPuzzle,
+    rating::{Difficulty, RatingConfig},
+};
+
+#[derive(clap::Args)]
+pub struct CalibrateArgs {
+    /// A file with a puzzle and its rating on each line, separated by
+    /// spaces, a comma, or a semicolon. Ratings can be plain numbers or in
+    /// Sudoku Explainer's ED=8.3/1.2/1.2 form, of which the first is used.
+    /// Lines starting with '#' are skipped.
+    pub file: PathBuf,
+
+    /// The ratings in the file where medium, hard, and expert start
+    #[arg(long, value_name = "MEDIUM,HARD,EXPERT", value_parser = bands)]
+    pub bands: Bands,
+
+    /// The score of a naked single [default: 1]
+    #[arg(long, value_name = "WEIGHT")]
+    pub single: Option<u32>,
+
+    /// The score of a hidden single [default: 2]
+    #[arg(long, value_name = "WEIGHT")]
+    pub hidden_single: Option<u32>,
+
+    /// The score of a guess [default: 10]
+    #[arg(long, value_name = "WEIGHT")]
+    pub guess: Option<u32>,
+
+    /// The score of a backtrack [default: 20]
+    #[arg(long, value_name = "WEIGHT")]
+    pub backtrack: Option<u32>,
+
+    /// The weights from the configuration file, which the flags override
+    #[arg(skip)]
+    pub weights: RatingConfig,
+}
+
+/// Read three increasing ratings separated by commas.
+fn bands(text: &str) -> Result<Bands, String> {
+    let starts = text
+        .split(',')
+        .map(|start| start.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    match starts[..] {
+        [medium, hard, expert] if medium <= hard && hard <= expert => {
+            Ok(Bands([medium, hard, expert]))
+        }
+        _ => Err(String::from("expected three increasing ratings")),
+    }
+}
+
+/// Read a puzzle and its rating from a line of the file.
+fn sample(line: &str) -> Result<Sample, String> {
+    let mut fields = line
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|field| !field.is_empty());
+    let puzzle = fields.next().unwrap_or_default().replace('.', "0");
+    let puzzle = SudokuPuzzle::from_string(&puzzle).map_err(|e| e.to_string())?;
+
+    // ED=8.3/1.2/1.2 gives the rating followed by the pearl and diamond
+    // ratings
+    let field = fields.next().ok_or("there is no rating")?;
+    let number = field.rsplit('=').next().unwrap_or(field);
+    let number = number.split('/').next().unwrap_or(number);
+    let rating = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a rating", field))?;
+    Ok(Sample { puzzle, rating })
+}
+
+/// Rate every puzzle in the file and report how well the ratings agree with
+/// the file's.
+pub fn run(args: &CalibrateArgs, format: Format) -> io::Result<Exit> {
+    let weights = RatingConfig {
+        single: args.single.unwrap_or(args.weights.single),
+        hidden_single: args.hidden_single.unwrap_or(args.weights.hidden_single),
+        guess: args.guess.unwrap_or(args.weights.guess),
+        backtrack: args.backtrack.unwrap_or(args.weights.backtrack),
+    };
+
+    let mut exit = Exit::Success;
+    let mut samples = Vec::new();
+    for (i, line) in fs::read_to_string(&args.file)?.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match sample(line) {
+            Ok(sample) => samples.push(sample),
+            Err(e) => {
+                eprintln!("Skipping line {}: {}", i + 1, e);
+                exit = exit.max(Exit::ParseError);
+            }
+        }
+    }
+
+    let report = calibration::calibrate(&samples, &args.bands, &weights);
+    if !report.unsolvable.is_empty() {
+        exit = exit.max(Exit::Unsolvable);
+    }
+
+    let mut out = io::stdout().lock();
+    match format {
+        Format::Grid | Format::Line | Format::Spoken => {
+            writeln!(
+                out,
+                "{} puzzles rated, {} without a solution",
+                report.rated,
+                report.unsolvable.len()
+            )?;
+            writeln!(out, "rank correlation: {:.3}", report.correlation)?;
+            writeln!(out, "same difficulty: {:.1}%", report.accuracy() * 100.0)?;
+            writeln!(out, "\nexpected   rated as")?;
+            write!(out, "{:<10}", "")?;
+            for difficulty in Difficulty::ALL.iter() {
+                write!(out, " {:>7}", difficulty.name())?;
+            }
+            writeln!(out)?;
+            for (expected, row) in Difficulty::ALL.iter().zip(&report.confusion) {
+                write!(out, "{:<10}", expected.name())?;
+                for count in row {
+                    write!(out, " {:>7}", count)?;
+                }
+                writeln!(out)?;
+            }
+            if !report.misclassified.is_empty() {
+                writeln!(out)?;
+            }
+            for miss in &report.misclassified {
+                writeln!(
+                    out,
+                    "puzzle {}: rated {} ({}), but {} with score {}",
+                    miss.index + 1,
+                    miss.rating,
+                    miss.expected,
+                    miss.actual,
+                    miss.score
+                )?;
+            }
+        }
+        Format::Json => {
+            let misclassified: Vec<_> = report
+                .misclassified
+                .iter()
+                .map(|miss| {
+                    json!({
+                        "puzzle": miss.index + 1,
+                        "rating": miss.rating,
+                        "expected": miss.expected.name(),
+                        "difficulty": miss.actual.name(),
+                        "score": miss.score,
+                    })
+                })
+                .collect();
+            let unsolvable: Vec<_> = report.unsolvable.iter().map(|i| i + 1).collect();
+            // NaN is written as null
+            writeln!(
+                out,
+                "{}",
+                json!({
+                    "rated": report.rated,
+                    "unsolvable": unsolvable,
+                    "correlation": report.correlation,
+                    "accuracy": report.accuracy(),
+                    "confusion": report.confusion,
+                    "misclassified": misclassified,
+                })
+            )?;
+        }
+        Format::Csv => {
+            writeln!(out, "rated,unsolvable,correlation,accuracy")?;
+            writeln!(
+                out,
+                "{},{},{:.3},{:.3}",
+                report.rated,
+                report.unsolvable.len(),
+                report.correlation,
+                report.accuracy()
+            )?;
+        }
+    }
+
+    Ok(exit)
+}
@@ -0,0 +1,89 @@
+//! `sudoku canonicalize`: rewrite puzzles in canonical form, so that
+//! equivalent puzzles can be recognized.
+
+use crate::cli::{
+    exit::Exit,
+    input::Source,
+    output::{csv_field, digits, puzzle_grid, Format},
+};
+use serde_json::json;
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+};
+use sudoku::puzzle::SudokuPuzzle;
+
+#[derive(clap::Args)]
+pub struct CanonicalizeArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Print each canonical form only once, dropping puzzles equivalent to
+    /// one already printed
+    #[arg(long)]
+    pub dedupe: bool,
+}
+
+/// Print the minlex canonical form of each puzzle.
+pub fn run(args: &CanonicalizeArgs, format: Format, color: bool) -> io::Result<Exit> {
+    let mut out = io::stdout().lock();
+    let mut exit = Exit::Success;
+    if format == Format::Csv {
+        writeln!(out, "puzzle,canonical,error")?;
+    }
+
+    let mut seen = HashSet::new();
+    let (mut read, mut kept) = (0, 0);
+    for puzzle_source in args.source.puzzles()? {
+        let puzzle_source = puzzle_source?;
+        read += 1;
+        let puzzle = match SudokuPuzzle::from_string(&puzzle_source) {
+            Ok(puzzle) => puzzle,
+            Err(e) => {
+                exit = exit.max(Exit::ParseError);
+                match format {
+                    Format::Grid | Format::Line | Format::Spoken => {
+                        writeln!(out, "Invalid puzzle: {}", e)?
+                    }
+                    Format::Json => writeln!(
+                        out,
+                        "{}",
+                        json!({ "puzzle": puzzle_source, "error": e.to_string() })
+                    )?,
+                    Format::Csv => writeln!(
+                        out,
+                        "{},,{}",
+                        csv_field(&puzzle_source),
+                        csv_field(&e.to_string())
+                    )?,
+                }
+                continue;
+            }
+        };
+
+        let canonical = puzzle.canonical();
+        let line = digits(&canonical.to_vec());
+        if args.dedupe && !seen.insert(line.clone()) {
+            continue;
+        }
+        kept += 1;
+
+        match format {
+            Format::Grid | Format::Spoken => {
+                writeln!(out, "{}\n", puzzle_grid(&canonical, format, color))?
+            }
+            Format::Line => writeln!(out, "{}", line)?,
+            Format::Json => writeln!(
+                out,
+                "{}",
+                json!({ "puzzle": digits(&puzzle.to_vec()), "canonical": line })
+            )?,
+            Format::Csv => writeln!(out, "{},{},", digits(&puzzle.to_vec()), line)?,
+        }
+    }
+
+    if args.dedupe {
+        eprintln!("{} of {} puzzles kept", kept, read);
+    }
+    Ok(exit)
+}
@@ -0,0 +1,347 @@
+//! `sudoku check`: validate puzzles before they are published.
+
+use crate::cli::{
+    exit::Exit,
+    input::{BoxShape, Source},
+    output::{csv_field, digits, grid, puzzle_grid, solution_count, Format, Highlight},
+};
+use serde_json::json;
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use sudoku::{
+    board,
+    cache::{Key, SolveCache},
+    error::LimitExceeded,
+    limits::SolverLimits,
+    puzzle::{Ambiguity, SudokuPuzzle},
+};
+
+#[derive(clap::Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Count solutions, up to CAP (2 by default), and report how many were found
+    #[arg(long, value_name = "CAP", num_args = 0..=1, require_equals = true, default_missing_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+    pub count_solutions: Option<u64>,
+
+    /// Remember solution counts in this file, and skip puzzles counted on an
+    /// earlier run
+    #[arg(long, value_name = "FILE")]
+    pub cache: Option<PathBuf>,
+
+    /// Check puzzles with boxes of R rows and C columns instead of 9x9
+    /// puzzles, such as 2x3 for 6x6 puzzles or 3x4 for 12x12 ones
+    #[arg(long = "box", value_name = "RxC", conflicts_with = "cache")]
+    pub box_shape: Option<BoxShape>,
+
+    /// Give up on a puzzle once counting its solutions has visited N points,
+    /// the puzzle and each guess made from it
+    #[arg(long, value_name = "N")]
+    pub max_nodes: Option<u64>,
+
+    /// Give up on a puzzle once counting its solutions would make more than
+    /// N guesses on top of each other
+    #[arg(long, value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// Give up on a puzzle once counting its solutions has taken MS
+    /// milliseconds
+    #[arg(long, value_name = "MS")]
+    pub max_time: Option<u64>,
+}
+
+impl CheckArgs {
+    /// The limits given on the command line.
+    fn limits(&self) -> SolverLimits {
+        SolverLimits {
+            max_nodes: self.max_nodes,
+            max_guess_depth: self.max_depth,
+            max_time: self.max_time.map(Duration::from_millis),
+        }
+    }
+}
+
+/// The outcome of checking one puzzle.
+enum Verdict {
+    Invalid(sudoku::Error),
+    /// A puzzle that breaks the rules, and where, for 9x9 puzzles.
+    Inconsistent(Option<Box<SudokuPuzzle>>, Vec<(usize, usize)>),
+    Unsolvable,
+    /// Some number of solutions, found while counting up to a cap, and two
+    /// of them to show where they differ, for 9x9 puzzles.
+    Multiple {
+        count: usize,
+        cap: usize,
+        ambiguity: Option<Box<Ambiguity>>,
+    },
+    Unique,
+    /// Counting the solutions broke one of the solver limits.
+    GaveUp(LimitExceeded),
+}
+
+impl Verdict {
+    /// Check a puzzle, counting its solutions up to cap, which is at least 2,
+    /// within limits.
+    fn of(puzzle_source: &str, cap: usize, limits: &SolverLimits, cache: &mut SolveCache) -> Self {
+        let puzzle = match SudokuPuzzle::from_string(puzzle_source) {
+            Ok(puzzle) => puzzle,
+            Err(e) => return Verdict::Invalid(e),
+        };
+
+        let conflicts = puzzle.conflicts();
+        if !conflicts.is_empty() {
+            return Verdict::Inconsistent(Some(Box::new(puzzle)), conflicts);
+        }
+
+        match cache.count_solutions_within(&puzzle, cap, limits) {
+            Err(e) => Verdict::GaveUp(e),
+            Ok(0) => Verdict::Unsolvable,
+            Ok(1) => Verdict::Unique,
+            Ok(count) => Verdict::Multiple {
+                count,
+                cap,
+                ambiguity: Some(Box::new(
+                    puzzle.ambiguity().expect("the puzzle has two solutions"),
+                )),
+            },
+        }
+    }
+
+    /// Check a puzzle with boxes of shape, like `of`, without finding where
+    /// it breaks the rules or where its solutions differ.
+    fn of_grid(puzzle_source: &str, shape: BoxShape, cap: usize, limits: &SolverLimits) -> Self {
+        let grid = match shape.grid(puzzle_source) {
+            Ok(grid) => grid,
+            Err(e) => return Verdict::Invalid(e.into()),
+        };
+        if !board::is_consistent(&grid) {
+            return Verdict::Inconsistent(None, Vec::new());
+        }
+        match grid.count_solutions_within(cap, limits) {
+            Err(e) => Verdict::GaveUp(e),
+            Ok(0) => Verdict::Unsolvable,
+            Ok(1) => Verdict::Unique,
+            Ok(count) => Verdict::Multiple {
+                count,
+                cap,
+                ambiguity: None,
+            },
+        }
+    }
+
+    /// A short name for this outcome, and its position in the summary counts.
+    fn status(&self) -> (&'static str, usize) {
+        match self {
+            Verdict::Invalid(_) => ("invalid", 0),
+            Verdict::Inconsistent(..) => ("inconsistent", 1),
+            Verdict::Unsolvable => ("unsolvable", 2),
+            Verdict::Multiple { .. } => ("multiple", 3),
+            Verdict::Unique => ("unique", 4),
+            Verdict::GaveUp(_) => ("gave-up", 5),
+        }
+    }
+
+    /// The exit code for this outcome.
+    fn exit(&self) -> Exit {
+        match self {
+            Verdict::Invalid(_) => Exit::ParseError,
+            Verdict::Inconsistent(..) | Verdict::Unsolvable => Exit::Unsolvable,
+            Verdict::Multiple { .. } => Exit::Multiple,
+            Verdict::Unique => Exit::Success,
+            Verdict::GaveUp(_) => Exit::LimitExceeded,
+        }
+    }
+
+    /// A description of this outcome for a person to read.
+    fn describe(&self) -> String {
+        match self {
+            Verdict::Invalid(e) => format!("invalid: {}", e),
+            Verdict::Inconsistent(_, conflicts) if conflicts.is_empty() => {
+                String::from("inconsistent")
+            }
+            Verdict::Inconsistent(_, conflicts) => {
+                let squares: Vec<_> = conflicts
+                    .iter()
+                    .map(|(row, col)| format!("({}, {})", row, col))
+                    .collect();
+                format!("inconsistent, conflicts at {}", squares.join(", "))
+            }
+            Verdict::Unsolvable => String::from("consistent, no solution"),
+            Verdict::Multiple {
+                count,
+                cap,
+                ambiguity,
+            } => {
+                let count = match *cap {
+                    2 => String::from("multiple"),
+                    _ => solution_count(*count, *cap),
+                };
+                match ambiguity {
+                    Some(ambiguity) => {
+                        let squares: Vec<_> = ambiguity
+                            .squares
+                            .iter()
+                            .map(|(row, col)| format!("({}, {})", row, col))
+                            .collect();
+                        format!(
+                            "consistent, {} solutions, two of which differ at {}",
+                            count,
+                            squares.join(", ")
+                        )
+                    }
+                    None => format!("consistent, {} solutions", count),
+                }
+            }
+            Verdict::Unique => String::from("consistent, unique solution"),
+            Verdict::GaveUp(e) => format!("gave up: {}", e),
+        }
+    }
+}
+
+/// Print whether each puzzle is consistent, solvable, and has a unique
+/// solution, followed by a summary. Succeeds only if every puzzle passes.
+/// For machine-readable formats the summary goes to stderr.
+pub fn run(args: &CheckArgs, format: Format, color: bool) -> io::Result<Exit> {
+    let mut out = io::stdout().lock();
+    if format == Format::Csv {
+        writeln!(out, "puzzle,status,detail")?;
+    }
+
+    // Invalid, inconsistent, unsolvable, multiple, unique, gave up
+    let mut counts = [0; 6];
+    let mut exit = Exit::Success;
+    let mut cache = match &args.cache {
+        Some(path) => open_cache(path)?,
+        None => SolveCache::new(Key::Exact),
+    };
+
+    let squares = args.box_shape.map_or(81, BoxShape::squares);
+    let cap = args.count_solutions.map_or(2, |cap| cap.max(2) as usize);
+    let limits = args.limits();
+    for (i, puzzle_source) in args.source.puzzles_of(squares)?.enumerate() {
+        let puzzle_source = puzzle_source?;
+        let verdict = match args.box_shape {
+            Some(shape) => Verdict::of_grid(&puzzle_source, shape, cap, &limits),
+            None => Verdict::of(&puzzle_source, cap, &limits, &mut cache),
+        };
+        let (status, kind) = verdict.status();
+        counts[kind] += 1;
+        exit = exit.max(verdict.exit());
+
+        match format {
+            Format::Grid | Format::Line | Format::Spoken => {
+                writeln!(out, "puzzle {}: {}", i + 1, verdict.describe())?;
+                // Show where the conflicts are
+                if let (Format::Grid | Format::Spoken, Verdict::Inconsistent(Some(puzzle), _)) =
+                    (format, &verdict)
+                {
+                    writeln!(out, "{}", puzzle_grid(puzzle, format, color))?;
+                }
+                // Show two of the solutions, with the squares that differ
+                if let (
+                    Format::Grid | Format::Spoken,
+                    Verdict::Multiple {
+                        ambiguity: Some(ambiguity),
+                        ..
+                    },
+                ) = (format, &verdict)
+                {
+                    for (label, solution) in [
+                        ("One solution", &ambiguity.first),
+                        ("Another solution", &ambiguity.second),
+                    ]
+                    .iter()
+                    {
+                        let solution = grid(
+                            |row, col| {
+                                let highlight = if ambiguity.squares.contains(&(row, col)) {
+                                    Highlight::Current
+                                } else {
+                                    Highlight::Filled
+                                };
+                                Some((solution.get(row, col), highlight))
+                            },
+                            format,
+                            color,
+                        );
+                        writeln!(out, "{}:\n{}", label, solution)?;
+                    }
+                }
+            }
+            Format::Json => {
+                let mut record = json!({ "puzzle": puzzle_source, "status": status });
+                match &verdict {
+                    Verdict::Invalid(e) => record["error"] = json!(e.to_string()),
+                    Verdict::GaveUp(e) => record["error"] = json!(e.to_string()),
+                    Verdict::Inconsistent(_, conflicts) => record["conflicts"] = json!(conflicts),
+                    Verdict::Multiple {
+                        count,
+                        cap,
+                        ambiguity,
+                    } => {
+                        record["solutions"] = json!(count);
+                        record["capped"] = json!(count >= cap);
+                        if let Some(ambiguity) = ambiguity {
+                            record["first"] = json!(digits(&ambiguity.first.to_vec()));
+                            record["second"] = json!(digits(&ambiguity.second.to_vec()));
+                            record["differences"] = json!(ambiguity.squares);
+                        }
+                    }
+                    _ => {}
+                }
+                writeln!(out, "{}", record)?;
+            }
+            Format::Csv => {
+                let detail = match &verdict {
+                    Verdict::Invalid(_)
+                    | Verdict::Inconsistent(..)
+                    | Verdict::Multiple { .. }
+                    | Verdict::GaveUp(_) => verdict.describe(),
+                    _ => String::new(),
+                };
+                writeln!(
+                    out,
+                    "{},{},{}",
+                    csv_field(&puzzle_source),
+                    status,
+                    csv_field(&detail)
+                )?;
+            }
+        }
+    }
+
+    let total: usize = counts.iter().sum();
+    let mut summary = format!(
+        "{} checked: {} unique, {} multiple solutions, {} no solution, {} inconsistent, {} invalid",
+        total, counts[4], counts[3], counts[2], counts[1], counts[0]
+    );
+    if counts[5] > 0 {
+        summary.push_str(&format!(", {} gave up", counts[5]));
+    }
+    match format {
+        Format::Grid | Format::Line | Format::Spoken => writeln!(out, "\n{}", summary)?,
+        Format::Json | Format::Csv => eprintln!("{}", summary),
+    }
+
+    if let Some(path) = &args.cache {
+        fs::write(path, cache.to_bytes())?;
+    }
+    Ok(exit)
+}
+
+/// Read the cache at path, or an empty one if there is no such file.
+fn open_cache(path: &Path) -> io::Result<SolveCache> {
+    match fs::read(path) {
+        Ok(bytes) => SolveCache::from_bytes(&bytes).map_err(|e| {
+            let message = format!("{}: {}", path.display(), e);
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(SolveCache::new(Key::Exact)),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,159 @@
+//! `sudoku compare`: time several solving strategies on the same puzzles.
+
+use crate::cli::{bench, exit::Exit, output::Format};
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use sudoku::{puzzle::SudokuPuzzle, strategy::Strategy};
+
+#[derive(clap::Args)]
+pub struct CompareArgs {
+    /// A file of puzzles to solve, read the same way as for solve. The
+    /// puzzles of bench are used if no file is given.
+    pub file: Option<PathBuf>,
+
+    /// The strategies to compare, separated by commas: deduction,
+    /// fewest-candidates, naked-singles, backtracking, and dlx. All of them
+    /// by default.
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    pub strategies: Vec<Strategy>,
+
+    /// How many times each strategy solves each puzzle
+    #[arg(long, value_name = "N", default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub repeat: u64,
+}
+
+/// How one strategy did on every puzzle.
+struct Results {
+    strategy: Strategy,
+    solved: usize,
+    /// Guesses made over one pass of the puzzles.
+    guesses: u64,
+    total: Duration,
+    mean: Duration,
+    median: Duration,
+    max: Duration,
+}
+
+impl Results {
+    /// Solve every puzzle with strategy, repeat times, timing each solve.
+    fn of(strategy: Strategy, puzzles: &[SudokuPuzzle], repeat: u64) -> Self {
+        let (mut solved, mut guesses) = (0, 0);
+        let mut times = Vec::new();
+        for pass in 0..repeat {
+            for puzzle in puzzles {
+                let start = Instant::now();
+                let run = std::hint::black_box(strategy.solve(std::hint::black_box(puzzle)));
+                times.push(start.elapsed());
+                if pass == 0 {
+                    solved += usize::from(run.solution.is_some());
+                    guesses += run.guesses;
+                }
+            }
+        }
+        times.sort();
+
+        let total: Duration = times.iter().sum();
+        Results {
+            strategy,
+            solved,
+            guesses,
+            total,
+            mean: total.checked_div(times.len() as u32).unwrap_or_default(),
+            median: times.get(times.len() / 2).copied().unwrap_or_default(),
+            max: times.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Solve every puzzle with each strategy and report their solve times and
+/// guesses side by side.
+pub fn run(args: &CompareArgs, format: Format) -> io::Result<Exit> {
+    let (puzzles, mut exit) = bench::load(args.file.as_ref())?;
+    let strategies = match args.strategies.len() {
+        0 => Strategy::ALL.to_vec(),
+        _ => args.strategies.clone(),
+    };
+
+    let results: Vec<Results> = strategies
+        .iter()
+        .map(|strategy| Results::of(*strategy, &puzzles, args.repeat))
+        .collect();
+    if results.iter().any(|results| results.solved < puzzles.len()) {
+        exit = exit.max(Exit::Unsolvable);
+    }
+
+    let mut out = io::stdout().lock();
+    match format {
+        Format::Grid | Format::Line | Format::Spoken => {
+            writeln!(
+                out,
+                "{} puzzles solved {} times each\n",
+                puzzles.len(),
+                args.repeat
+            )?;
+            writeln!(
+                out,
+                "{:<18} {:>7} {:>11} {:>11} {:>11} {:>9}",
+                "strategy", "solved", "mean", "median", "max", "guesses"
+            )?;
+            for results in &results {
+                writeln!(
+                    out,
+                    "{:<18} {:>7} {:>11} {:>11} {:>11} {:>9}",
+                    results.strategy.name(),
+                    results.solved,
+                    format!("{:.3?}", results.mean),
+                    format!("{:.3?}", results.median),
+                    format!("{:.3?}", results.max),
+                    results.guesses
+                )?;
+            }
+        }
+        Format::Json => {
+            for results in &results {
+                writeln!(
+                    out,
+                    "{}",
+                    json!({
+                        "strategy": results.strategy.name(),
+                        "puzzles": puzzles.len(),
+                        "repeat": args.repeat,
+                        "solved": results.solved,
+                        "total_us": results.total.as_micros() as u64,
+                        "mean_ns": results.mean.as_nanos() as u64,
+                        "median_ns": results.median.as_nanos() as u64,
+                        "max_ns": results.max.as_nanos() as u64,
+                        "guesses": results.guesses,
+                    })
+                )?;
+            }
+        }
+        Format::Csv => {
+            writeln!(
+                out,
+                "strategy,puzzles,repeat,solved,total_us,mean_ns,median_ns,max_ns,guesses"
+            )?;
+            for results in &results {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    results.strategy.name(),
+                    puzzles.len(),
+                    args.repeat,
+                    results.solved,
+                    results.total.as_micros(),
+                    results.mean.as_nanos(),
+                    results.median.as_nanos(),
+                    results.max.as_nanos(),
+                    results.guesses
+                )?;
+            }
+        }
+    }
+
+    Ok(exit)
+}
@@ -0,0 +1,143 @@
+//! Defaults read from a configuration file, which flags override.
+
+use crate::cli::output::Format;
+use serde::{Deserialize, Deserializer};
+use std::{
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use sudoku::{
+    generate::Symmetry,
+    rating::{Difficulty, RatingConfig},
+};
+
+/// The settings in a configuration file, such as
+///
+/// ```toml
+/// format = "line"
+/// color = false
+///
+/// [solve]
+/// max_nodes = 100000
+/// max_time = 2000
+///
+/// [generate]
+/// difficulty = "hard"
+/// symmetry = "rotational"
+///
+/// [play]
+/// save = "/home/me/sudoku-save.json"
+///
+/// [rating]
+/// guess = 15
+/// ```
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The default for --format
+    pub format: Option<Format>,
+    /// Whether to print in color where possible; false is the same as
+    /// --no-color
+    pub color: Option<bool>,
+    pub solve: SolveConfig,
+    pub generate: GenerateConfig,
+    pub play: PlayConfig,
+    pub rating: RatingWeights,
+}
+
+/// The solver limits for solve and check.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SolveConfig {
+    /// The default for --max-nodes
+    pub max_nodes: Option<u64>,
+    /// The default for --max-depth
+    pub max_depth: Option<usize>,
+    /// The default for --max-time, in milliseconds
+    pub max_time: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenerateConfig {
+    /// The default for generate --difficulty
+    #[serde(deserialize_with = "difficulty")]
+    pub difficulty: Option<Difficulty>,
+    /// The default for generate --symmetry
+    #[serde(deserialize_with = "symmetry")]
+    pub symmetry: Option<Symmetry>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayConfig {
+    /// The default for play --save
+    pub save: Option<PathBuf>,
+}
+
+/// The weight of each kind of step in rating scores, for generate and
+/// calibrate. Missing weights keep their defaults.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct RatingWeights {
+    pub single: Option<u32>,
+    pub hidden_single: Option<u32>,
+    pub guess: Option<u32>,
+    pub backtrack: Option<u32>,
+}
+
+impl RatingWeights {
+    pub fn weights(&self) -> RatingConfig {
+        let default = RatingConfig::default();
+        RatingConfig {
+            single: self.single.unwrap_or(default.single),
+            hidden_single: self.hidden_single.unwrap_or(default.hidden_single),
+            guess: self.guess.unwrap_or(default.guess),
+            backtrack: self.backtrack.unwrap_or(default.backtrack),
+        }
+    }
+}
+
+/// Read a difficulty by its name.
+fn difficulty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Difficulty>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+/// Read a symmetry by its name.
+fn symmetry<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Symmetry>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+impl Config {
+    /// The configuration file to read when none is given:
+    /// $XDG_CONFIG_HOME/sudoku/config.toml, or ~/.config/sudoku/config.toml.
+    fn default_path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_home.join("sudoku").join("config.toml"))
+    }
+
+    /// Read the configuration from path, or from the default path if not
+    /// given. A missing default file is the same as an empty one.
+    pub fn load(path: Option<&Path>) -> Result<Config, String> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Config::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound && !required => return Ok(Config::default()),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
@@ -0,0 +1,257 @@
+//! `sudoku convert`: rewrite puzzles from one file format in another.
+
+use crate::cli::{
+    exit::Exit,
+    output::{csv_field, digits},
+};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use sudoku::puzzle::SudokuPuzzle;
+
+/// The puzzle file formats that can be converted between.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum FileFormat {
+    /// One puzzle per line as 81 characters, with '.' for empty squares
+    Line,
+    /// One puzzle per line as 81 digits, with '0' for empty squares
+    Sdm,
+    /// SadMan Sudoku: 9 lines of 9 characters, with '.' for empty squares
+    /// and '#' comment lines
+    Sdk,
+    /// Simple Sudoku: a 9x9 grid with '|' between boxes and dashed lines
+    /// between bands
+    Ss,
+    /// Comma-separated values with a "puzzle" column
+    Csv,
+    /// One JSON object per line with a "puzzle" field
+    Json,
+}
+
+impl FileFormat {
+    /// The format usually stored with the extension of path, if any.
+    fn of_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match &extension[..] {
+            "txt" => Some(FileFormat::Line),
+            "sdm" => Some(FileFormat::Sdm),
+            "sdk" => Some(FileFormat::Sdk),
+            "ss" => Some(FileFormat::Ss),
+            "csv" => Some(FileFormat::Csv),
+            "json" | "jsonl" => Some(FileFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Whether each puzzle is written as a 9x9 grid over several lines.
+    fn is_grid(self) -> bool {
+        matches!(self, FileFormat::Sdk | FileFormat::Ss)
+    }
+}
+
+#[derive(clap::Args)]
+pub struct ConvertArgs {
+    /// The file to read, or stdin if not given
+    pub input: Option<PathBuf>,
+
+    /// The file to write, or stdout if not given
+    pub output: Option<PathBuf>,
+
+    /// The format to read, by default the one that matches INPUT's extension
+    #[arg(long, value_enum)]
+    pub from: Option<FileFormat>,
+
+    /// The format to write, by default the one that matches OUTPUT's extension
+    #[arg(long, value_enum)]
+    pub to: Option<FileFormat>,
+}
+
+/// Read every puzzle in the input and write it in the output format.
+/// Puzzles that cannot be read are reported on stderr and skipped.
+pub fn run(args: &ConvertArgs) -> io::Result<Exit> {
+    let from = args
+        .from
+        .or_else(|| args.input.as_deref().and_then(FileFormat::of_path));
+    let to = args
+        .to
+        .or_else(|| args.output.as_deref().and_then(FileFormat::of_path));
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) => (from, to),
+        (None, _) => {
+            eprintln!("Error: the input format can't be guessed; give it with --from");
+            return Ok(Exit::Usage);
+        }
+        (_, None) => {
+            eprintln!("Error: the output format can't be guessed; give it with --to");
+            return Ok(Exit::Usage);
+        }
+    };
+
+    let input: Box<dyn BufRead> = match &args.input {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut out = BufWriter::new(output);
+
+    if to == FileFormat::Csv {
+        writeln!(out, "puzzle")?;
+    }
+    let mut exit = Exit::Success;
+    let mut written = 0;
+    for (i, source) in read(input, from).enumerate() {
+        match SudokuPuzzle::from_string(&source?) {
+            Ok(puzzle) => {
+                write(&mut out, &puzzle, to, written)?;
+                written += 1;
+            }
+            Err(e) => {
+                eprintln!("Skipping puzzle {}: {}", i + 1, e);
+                exit = exit.max(Exit::ParseError);
+            }
+        }
+    }
+    out.flush()?;
+
+    Ok(exit)
+}
+
+/// The text of each puzzle in input, with '0' for empty squares.
+fn read(
+    input: Box<dyn BufRead>,
+    format: FileFormat,
+) -> Box<dyn Iterator<Item = io::Result<String>>> {
+    let lines = input.lines();
+    match format {
+        FileFormat::Line | FileFormat::Sdm => Box::new(
+            lines
+                .map(|line| line.map(|line| line.trim().replace('.', "0")))
+                .filter(|line| !matches!(line, Ok(line) if line.is_empty())),
+        ),
+        FileFormat::Sdk | FileFormat::Ss => Box::new(Grids {
+            lines: Box::new(lines),
+            current: String::new(),
+        }),
+        FileFormat::Csv => {
+            // The "puzzle" column, or else the first, found from the header
+            let mut column = None;
+            Box::new(lines.filter_map(move |line| {
+                let line = match line {
+                    Ok(line) if line.trim().is_empty() => return None,
+                    Ok(line) => line,
+                    Err(e) => return Some(Err(e)),
+                };
+                let fields: Vec<&str> = line
+                    .split(',')
+                    .map(|f| f.trim().trim_matches('"'))
+                    .collect();
+                match column {
+                    Some(column) => Some(Ok(fields
+                        .get(column)
+                        .copied()
+                        .unwrap_or("")
+                        .replace('.', "0"))),
+                    None => {
+                        column = Some(fields.iter().position(|f| *f == "puzzle").unwrap_or(0));
+                        None
+                    }
+                }
+            }))
+        }
+        FileFormat::Json => Box::new(lines.filter_map(|line| {
+            let line = match line {
+                Ok(line) if line.trim().is_empty() => return None,
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            let puzzle = match serde_json::from_str::<Value>(&line) {
+                Ok(Value::String(puzzle)) => puzzle,
+                Ok(record) => record["puzzle"].as_str().unwrap_or("").to_string(),
+                // Leave the text for the parser to report
+                Err(_) => line,
+            };
+            Some(Ok(puzzle.replace('.', "0")))
+        })),
+    }
+}
+
+/// The puzzles in a sequence of 9x9 grids, found by collecting squares and
+/// ignoring comments, borders, and other decoration.
+struct Grids {
+    lines: Box<dyn Iterator<Item = io::Result<String>>>,
+    current: String,
+}
+
+impl Iterator for Grids {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        for line in &mut self.lines {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let squares = line.chars().filter(|c| c.is_ascii_digit() || *c == '.');
+            self.current
+                .extend(squares.map(|c| if c == '.' { '0' } else { c }));
+            let ended = line.trim().is_empty() && !self.current.is_empty();
+            if ended || self.current.len() >= 81 {
+                return Some(Ok(std::mem::take(&mut self.current)));
+            }
+        }
+
+        if self.current.is_empty() {
+            None
+        } else {
+            Some(Ok(std::mem::take(&mut self.current)))
+        }
+    }
+}
+
+/// Write a puzzle in format, where `index` puzzles have already been written.
+fn write<W: Write>(
+    out: &mut W,
+    puzzle: &SudokuPuzzle,
+    format: FileFormat,
+    index: usize,
+) -> io::Result<()> {
+    let nums = puzzle.to_vec();
+    let dotted: String = digits(&nums).replace('0', ".");
+    if format.is_grid() && index > 0 {
+        writeln!(out)?;
+    }
+
+    match format {
+        FileFormat::Line => writeln!(out, "{}", dotted),
+        FileFormat::Sdm => writeln!(out, "{}", digits(&nums)),
+        FileFormat::Sdk => {
+            for row in 0..9 {
+                writeln!(out, "{}", &dotted[row * 9..row * 9 + 9])?;
+            }
+            Ok(())
+        }
+        FileFormat::Ss => {
+            for row in 0..9 {
+                if row == 3 || row == 6 {
+                    writeln!(out, "-----------")?;
+                }
+                let row = &dotted[row * 9..row * 9 + 9];
+                writeln!(out, "{}|{}|{}", &row[..3], &row[3..6], &row[6..])?;
+            }
+            Ok(())
+        }
+        FileFormat::Csv => writeln!(out, "{}", csv_field(&digits(&nums))),
+        FileFormat::Json => writeln!(out, "{}", json!({ "puzzle": digits(&nums) })),
+    }
+}
@@ -0,0 +1,167 @@
+//! `sudoku daily`: the same generated puzzle for everyone on a given day.
+
+use crate::cli::{
+    exit::Exit,
+    output::{digits, puzzle_grid, Format},
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde_json::json;
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    time::SystemTime,
+};
+use sudoku::{deterministic, generate::generate, rating::Difficulty};
+
+#[derive(clap::Args)]
+pub struct DailyArgs {
+    /// The day of the puzzle as YYYY-MM-DD, today in UTC by default
+    #[arg(long)]
+    pub date: Option<Date>,
+
+    /// How hard the puzzle is: easy, medium, hard, or expert
+    #[arg(long, value_name = "LEVEL", default_value_t = Difficulty::Medium)]
+    pub difficulty: Difficulty,
+
+    /// Disguise the puzzle as an equivalent one that looks different, a new
+    /// one on each run unless --seed is given
+    #[arg(long)]
+    pub scramble: bool,
+}
+
+/// A day of the proleptic Gregorian calendar.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Date {
+    year: u32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// The current day in UTC.
+    fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Date::from_days(secs / 86_400)
+    }
+
+    /// The day that is days after 1970-01-01, using Howard Hinnant's
+    /// civil_from_days algorithm.
+    fn from_days(days: u64) -> Self {
+        let days = days + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+        Date {
+            year: year as u32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    fn days_in_month(&self) -> u32 {
+        let leap = self.year.is_multiple_of(4)
+            && (!self.year.is_multiple_of(100) || self.year.is_multiple_of(400));
+        match self.month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let invalid = || format!("'{}' is not a date of the form YYYY-MM-DD", s);
+        let parts: Vec<&str> = s.split('-').collect();
+        let date = match parts[..] {
+            [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => Date {
+                year: year.parse().map_err(|_| invalid())?,
+                month: month.parse().map_err(|_| invalid())?,
+                day: day.parse().map_err(|_| invalid())?,
+            },
+            _ => return Err(invalid()),
+        };
+
+        if !(1..=12).contains(&date.month) || !(1..=date.days_in_month()).contains(&date.day) {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The seed for a day's puzzle: an FNV-1a hash of the date and difficulty,
+/// which unlike the standard library's hashers is the same on every platform
+/// and in every release.
+fn daily_seed(date: Date, difficulty: Difficulty) -> u64 {
+    format!("{} {}", date, difficulty)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+}
+
+/// Generate and print the puzzle for the day, scrambled with seed if asked
+/// to be.
+pub fn run(args: &DailyArgs, seed: Option<u64>, format: Format, color: bool) -> io::Result<Exit> {
+    let date = args.date.unwrap_or_else(Date::today);
+    let mut rng = deterministic::rng(daily_seed(date, args.difficulty));
+    let mut puzzle = generate(&mut rng, args.difficulty);
+    if args.scramble {
+        let mut rng = match seed {
+            Some(seed) => deterministic::rng(seed),
+            None => ChaCha8Rng::from_os_rng(),
+        };
+        puzzle = puzzle.scramble(&mut rng);
+    }
+
+    let mut out = io::stdout().lock();
+    let line = digits(&puzzle.to_vec());
+    match format {
+        Format::Grid | Format::Spoken => writeln!(
+            out,
+            "Daily puzzle for {} ({}):\n{}",
+            date,
+            args.difficulty,
+            puzzle_grid(&puzzle, format, color)
+        )?,
+        Format::Line => writeln!(out, "{}", line)?,
+        Format::Json => writeln!(
+            out,
+            "{}",
+            json!({
+                "date": date.to_string(),
+                "difficulty": args.difficulty.to_string(),
+                "puzzle": line,
+            })
+        )?,
+        Format::Csv => {
+            writeln!(out, "date,difficulty,puzzle")?;
+            writeln!(out, "{},{},{}", date, args.difficulty, line)?;
+        }
+    }
+
+    Ok(Exit::Success)
+}
@@ -0,0 +1,203 @@
+//! `sudoku db`: keep puzzles in a database file, rated once when they are
+//! added, and look them up by difficulty, clue count, and source.
+
+use crate::cli::{
+    exit::Exit,
+    input::Source,
+    output::{csv_field, digits, puzzle_grid, Format},
+};
+use serde_json::json;
+use std::{
+    fs,
+    io::{self, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use sudoku::{
+    db::{Database, Query},
+    puzzle::SudokuPuzzle,
+    rating::Difficulty,
+};
+
+#[derive(clap::Args)]
+pub struct DbArgs {
+    #[command(subcommand)]
+    pub command: DbCommand,
+}
+
+#[derive(clap::Subcommand)]
+pub enum DbCommand {
+    /// Rate puzzles and add them to a database, creating it if needed
+    Add(AddArgs),
+    /// Print the puzzles in a database that match a query
+    Query(QueryArgs),
+}
+
+#[derive(clap::Args)]
+pub struct AddArgs {
+    /// The database file
+    pub db: PathBuf,
+
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Where the puzzles came from, the file's name by default
+    #[arg(long = "source", value_name = "NAME")]
+    pub name: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct QueryArgs {
+    /// The database file
+    pub db: PathBuf,
+
+    /// Only puzzles of this difficulty: easy, medium, hard, or expert
+    #[arg(long, value_name = "LEVEL")]
+    pub difficulty: Option<Difficulty>,
+
+    /// Only puzzles with this many clues, such as 24 or 24-26
+    #[arg(long, value_name = "MIN[-MAX]", value_parser = clues)]
+    pub clues: Option<RangeInclusive<usize>>,
+
+    /// Only puzzles added with this source
+    #[arg(long, value_name = "NAME")]
+    pub source: Option<String>,
+
+    /// Print at most this many puzzles
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+}
+
+/// Read a clue count or an inclusive range of them.
+fn clues(s: &str) -> Result<RangeInclusive<usize>, String> {
+    let invalid = || format!("'{}' is not a number of clues or a range like 24-26", s);
+    let (min, max) = s.split_once('-').unwrap_or((s, s));
+    let min = min.trim().parse().map_err(|_| invalid())?;
+    let max = max.trim().parse().map_err(|_| invalid())?;
+    if min > max {
+        return Err(invalid());
+    }
+    Ok(min..=max)
+}
+
+pub fn run(args: &DbArgs, format: Format, color: bool) -> io::Result<Exit> {
+    match &args.command {
+        DbCommand::Add(args) => add(args),
+        DbCommand::Query(args) => query(args, format, color),
+    }
+}
+
+/// Read the database at path, or an empty one if there is no such file.
+fn open(path: &Path) -> io::Result<Database> {
+    match fs::read(path) {
+        Ok(bytes) => Database::from_bytes(&bytes).map_err(|e| {
+            let message = format!("{}: {}", path.display(), e);
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Database::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Rate and add each puzzle that is not already in the database.
+fn add(args: &AddArgs) -> io::Result<Exit> {
+    let mut db = open(&args.db)?;
+    let name = match (&args.name, &args.source.file) {
+        (Some(name), _) => name.clone(),
+        (None, Some(file)) => file.display().to_string(),
+        (None, None) => String::from("stdin"),
+    };
+
+    let mut exit = Exit::Success;
+    let (mut added, mut present) = (0, 0);
+    for puzzle_source in args.source.puzzles()? {
+        let puzzle_source = puzzle_source?;
+        match SudokuPuzzle::from_string(&puzzle_source) {
+            Ok(puzzle) => {
+                if db.insert(puzzle, &name) {
+                    added += 1;
+                } else {
+                    present += 1;
+                }
+            }
+            Err(e) => {
+                exit = exit.max(Exit::ParseError);
+                eprintln!("Invalid puzzle: {}", e);
+            }
+        }
+    }
+
+    fs::write(&args.db, db.to_bytes())?;
+    eprintln!(
+        "Added {} puzzles, {} already present, {} in total",
+        added,
+        present,
+        db.len()
+    );
+    Ok(exit)
+}
+
+/// Print the puzzles that match the query, with their ratings.
+fn query(args: &QueryArgs, format: Format, color: bool) -> io::Result<Exit> {
+    if !args.db.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{}: no such database", args.db.display()),
+        ));
+    }
+    let db = open(&args.db)?;
+    let query = Query {
+        difficulty: args.difficulty,
+        clues: args.clues.clone(),
+        source: args.source.clone(),
+    };
+
+    let mut out = io::stdout().lock();
+    if format == Format::Csv {
+        writeln!(out, "puzzle,difficulty,score,clues,source")?;
+    }
+    for record in db.query(&query).take(args.limit.unwrap_or(usize::MAX)) {
+        let line = digits(&record.puzzle.to_vec());
+        let difficulty = record.rating.map(|rating| rating.difficulty.to_string());
+        let score = record.rating.map(|rating| rating.score);
+        match format {
+            Format::Grid | Format::Spoken => {
+                let rating = match record.rating {
+                    Some(rating) => format!("{} ({})", rating.difficulty, rating.score),
+                    None => String::from("no solution"),
+                };
+                writeln!(
+                    out,
+                    "{}, {} clues, from {}:\n{}\n",
+                    rating,
+                    record.clues,
+                    record.source,
+                    puzzle_grid(&record.puzzle, format, color)
+                )?
+            }
+            Format::Line => writeln!(out, "{}", line)?,
+            Format::Json => writeln!(
+                out,
+                "{}",
+                json!({
+                    "puzzle": line,
+                    "difficulty": difficulty,
+                    "score": score,
+                    "clues": record.clues,
+                    "source": &*record.source,
+                })
+            )?,
+            Format::Csv => writeln!(
+                out,
+                "{},{},{},{},{}",
+                line,
+                difficulty.unwrap_or_default(),
+                score.map(|score| score.to_string()).unwrap_or_default(),
+                record.clues,
+                csv_field(&record.source)
+            )?,
+        }
+    }
+
+    Ok(Exit::Success)
+}
@@ -0,0 +1,40 @@
+//! The exit codes of the command line interface.
+
+use std::process::ExitCode;
+
+/// The outcome of a command, reported through its exit code. When a
+/// command handles several puzzles, it exits with the greatest code among
+/// them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Exit {
+    /// Every puzzle was solved, or was valid
+    Success = 0,
+    /// Some puzzle has no solution, including because it is inconsistent
+    Unsolvable = 1,
+    /// Some puzzle has more than one solution
+    Multiple = 2,
+    /// Some puzzle could not be read
+    ParseError = 3,
+    /// The solver gave up on some puzzle at a limit set on the command line
+    LimitExceeded = 4,
+    /// The command line arguments were not valid
+    Usage = 64,
+    /// Input could not be read or output could not be written
+    IoError = 74,
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> Self {
+        ExitCode::from(exit as u8)
+    }
+}
+
+/// The exit codes, for the end of the help text.
+pub const HELP: &str = "Exit codes:
+  0   every puzzle was solved or is valid
+  1   some puzzle has no solution
+  2   some puzzle has more than one solution
+  3   some puzzle could not be read
+  4   a solver resource limit was hit
+  64  the command line arguments were not valid
+  74  input could not be read or output could not be written";
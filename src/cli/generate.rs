@@ -0,0 +1,141 @@
+//! `sudoku generate`: make new puzzles of a chosen difficulty.
+
+use crate::cli::{
+    exit::Exit,
+    output::{digits, puzzle_grid, Format},
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde_json::json;
+use std::io::{self, Write};
+use sudoku::{
+    deterministic,
+    generate::{generate, generate_symmetric, Symmetry},
+    rating::{Difficulty, RatingConfig},
+    technique::Technique,
+};
+
+/// How many puzzles of the right difficulty to generate, looking for one
+/// within the score range, before giving up. With --techniques or
+/// --symmetry, each grid that gives no such puzzle counts as an attempt too.
+const ATTEMPTS: usize = 1000;
+
+#[derive(clap::Args)]
+pub struct GenerateArgs {
+    /// How many puzzles to generate
+    #[arg(short = 'n', long, default_value_t = 1)]
+    pub count: usize,
+
+    /// How hard the puzzles are: easy, medium, hard, or expert [default: medium]
+    #[arg(long, value_name = "LEVEL")]
+    pub difficulty: Option<Difficulty>,
+
+    /// The lowest rating score to accept
+    #[arg(long, value_name = "SCORE")]
+    pub min_score: Option<u32>,
+
+    /// The highest rating score to accept
+    #[arg(long, value_name = "SCORE")]
+    pub max_score: Option<u32>,
+
+    /// Only make puzzles that can be solved by hand with these techniques,
+    /// separated by commas, and no guessing: naked-single, hidden-single,
+    /// naked-pair, pointing-pair, and x-wing
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    pub techniques: Vec<Technique>,
+
+    /// Arrange the givens symmetrically: none, rotational, mirror, or
+    /// diagonal [default: none]
+    #[arg(long, value_name = "KIND")]
+    pub symmetry: Option<Symmetry>,
+
+    /// The weights of each step in scores, from the configuration file
+    #[arg(skip)]
+    pub weights: RatingConfig,
+}
+
+/// Generate and print puzzles, each with a unique solution, retrying until
+/// their ratings match the difficulty and score range. The same seed always
+/// gives the same puzzles.
+pub fn run(
+    args: &GenerateArgs,
+    seed: Option<u64>,
+    format: Format,
+    color: bool,
+) -> io::Result<Exit> {
+    let mut rng = match seed {
+        Some(seed) => deterministic::rng(seed),
+        None => ChaCha8Rng::from_os_rng(),
+    };
+    let mut out = io::stdout().lock();
+    if format == Format::Csv {
+        writeln!(out, "puzzle,difficulty,score")?;
+    }
+
+    let difficulty = args.difficulty.unwrap_or(Difficulty::Medium);
+    let symmetry = args.symmetry.unwrap_or(Symmetry::None);
+    let scores = args.min_score.unwrap_or(0)..=args.max_score.unwrap_or(u32::MAX);
+    for i in 0..args.count {
+        let found = (0..ATTEMPTS)
+            .filter_map(|_| match (symmetry, &args.techniques[..]) {
+                (Symmetry::None, []) => Some(generate(&mut rng, difficulty)),
+                (symmetry, techniques) => {
+                    generate_symmetric(&mut rng, difficulty, symmetry, techniques)
+                }
+            })
+            .find_map(|puzzle| {
+                let score = puzzle.rating_with(&args.weights)?.score;
+                Some((puzzle, score)).filter(|_| scores.contains(&score))
+            });
+        let (puzzle, score) = match found {
+            Some(found) => found,
+            None => {
+                let solvable = if args.techniques.is_empty() {
+                    String::new()
+                } else {
+                    let names: Vec<&str> = args.techniques.iter().map(|t| t.name()).collect();
+                    format!(" solvable with {}", names.join(", "))
+                };
+                let arranged = match symmetry {
+                    Symmetry::None => String::new(),
+                    symmetry => format!(" with {} symmetry", symmetry),
+                };
+                eprintln!(
+                    "Error: no {} puzzle{}{} with a score from {} to {} was found in {} attempts",
+                    difficulty,
+                    arranged,
+                    solvable,
+                    scores.start(),
+                    scores.end(),
+                    ATTEMPTS
+                );
+                return Ok(Exit::Usage);
+            }
+        };
+
+        let line = digits(&puzzle.to_vec());
+        match format {
+            Format::Grid | Format::Spoken => {
+                if i > 0 {
+                    writeln!(out)?;
+                }
+                writeln!(
+                    out,
+                    "{} puzzle, score {}:\n{}",
+                    difficulty,
+                    score,
+                    puzzle_grid(&puzzle, format, color)
+                )?;
+            }
+            Format::Line => writeln!(out, "{}", line)?,
+            Format::Json => writeln!(
+                out,
+                "{}",
+                json!({ "puzzle": line, "difficulty": difficulty.name(), "score": score })
+            )?,
+            Format::Csv => writeln!(out, "{},{},{}", line, difficulty, score)?,
+        }
+    }
+
+    Ok(Exit::Success)
+}
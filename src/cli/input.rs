@@ -0,0 +1,208 @@
+//! Reading puzzles from files, arguments, and stdin.
+
+use std::{
+    error::Error,
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, IsTerminal},
+    mem,
+    path::PathBuf,
+    str::FromStr,
+};
+use sudoku::{error::ParseError, grid::Grid};
+
+/// The rows and columns of each box of a puzzle other than a 9x9 one, for
+/// the --box option.
+#[derive(Clone, Copy)]
+pub struct BoxShape {
+    rows: usize,
+    cols: usize,
+}
+
+impl BoxShape {
+    /// The number of squares in a puzzle with these boxes.
+    pub fn squares(self) -> usize {
+        let size = self.rows * self.cols;
+        size * size
+    }
+
+    /// Read a puzzle with these boxes.
+    pub fn grid(self, source: &str) -> Result<Grid, ParseError> {
+        Grid::from_string(self.rows, self.cols, source)
+    }
+}
+
+impl FromStr for BoxShape {
+    type Err = String;
+
+    /// Read a shape such as "2x3".
+    fn from_str(shape: &str) -> Result<Self, String> {
+        let invalid = || format!("expected a box shape such as 2x3, not \"{}\"", shape);
+        let (rows, cols) = shape.split_once(['x', 'X']).ok_or_else(invalid)?;
+        let rows: usize = rows.trim().parse().map_err(|_| invalid())?;
+        let cols: usize = cols.trim().parse().map_err(|_| invalid())?;
+        match rows.checked_mul(cols) {
+            Some(1..=32) => Ok(BoxShape { rows, cols }),
+            _ => Err(String::from("boxes must have from 1 to 32 squares")),
+        }
+    }
+}
+
+/// Where to read puzzles from.
+#[derive(clap::Args, Default)]
+pub struct Source {
+    /// A file of puzzles, each either on a single line or split over
+    /// several, with blank lines between them. Reads stdin if neither FILE
+    /// nor --puzzle is given.
+    #[arg(conflicts_with = "puzzle")]
+    pub file: Option<PathBuf>,
+
+    /// A puzzle given as 81 digits, where 0 is an empty square
+    #[arg(short, long, value_name = "81 DIGITS")]
+    pub puzzle: Option<String>,
+}
+
+impl Source {
+    /// The text of every puzzle from this source, read lazily so that large
+    /// files can be processed as a stream.
+    pub fn puzzles(&self) -> io::Result<Puzzles> {
+        self.puzzles_of(81)
+    }
+
+    /// The text of every puzzle from this source, like `puzzles`, for
+    /// puzzles with a number of squares other than 81.
+    pub fn puzzles_of(&self, squares: usize) -> io::Result<Puzzles> {
+        if let Some(puzzle) = &self.puzzle {
+            let line = Ok(puzzle.clone());
+            return Ok(Puzzles::new(
+                Box::new(std::iter::once(line)),
+                false,
+                squares,
+            ));
+        }
+        if let Some(file) = &self.file {
+            let reader = BufReader::new(File::open(file)?);
+            return Ok(Puzzles::new(Box::new(reader.lines()), false, squares));
+        }
+
+        let stdin = io::stdin();
+        let interactive = stdin.is_terminal();
+        if interactive {
+            println!(
+                "Please enter the puzzle as a sequence of {} numbers.",
+                squares
+            );
+            println!("Use '0' to indicate an empty space.");
+            println!("You may include line breaks, but no other whitespace.");
+            println!("Press enter on an empty line when you are done.\n");
+        }
+        Ok(Puzzles::new(
+            Box::new(stdin.lock().lines()),
+            interactive,
+            squares,
+        ))
+    }
+}
+
+/// A line of puzzles that is not UTF-8 text. It is a puzzle that cannot be
+/// read, not input that cannot be, so it exits with `Exit::ParseError`.
+#[derive(Debug)]
+struct NotText;
+
+impl fmt::Display for NotText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a puzzle is not valid UTF-8")
+    }
+}
+
+impl Error for NotText {}
+
+/// Indicates whether e came from a line of puzzles that is not UTF-8.
+pub fn is_not_text(e: &io::Error) -> bool {
+    e.get_ref().is_some_and(|inner| inner.is::<NotText>())
+}
+
+/// The puzzles in a sequence of lines. A puzzle ends once it has 81 squares
+/// (or however many puzzles of another size have) or at a blank line, and a
+/// line with that many squares or more is always a puzzle of its own, so
+/// both one-puzzle-per-line (SDM) files and 9x9 grids can be read. A '.' is
+/// read as an empty square.
+pub struct Puzzles {
+    lines: Box<dyn Iterator<Item = io::Result<String>>>,
+    /// The lines of the puzzle being read.
+    current: String,
+    /// A complete puzzle that was read while finishing the previous one.
+    held: Option<String>,
+    /// Stop after the first puzzle, as when it is typed in by hand.
+    first_only: bool,
+    done: bool,
+    /// How many squares a puzzle has.
+    squares: usize,
+}
+
+impl Puzzles {
+    fn new(
+        lines: Box<dyn Iterator<Item = io::Result<String>>>,
+        first_only: bool,
+        squares: usize,
+    ) -> Self {
+        Puzzles {
+            lines,
+            current: String::new(),
+            held: None,
+            first_only,
+            done: false,
+            squares,
+        }
+    }
+
+    /// Finish the current puzzle.
+    fn take(&mut self) -> String {
+        self.done = self.first_only;
+        mem::take(&mut self.current)
+    }
+}
+
+impl Iterator for Puzzles {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        if let Some(puzzle) = self.held.take() {
+            self.done = self.first_only;
+            return Some(Ok(puzzle));
+        }
+
+        while !self.done {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line.trim().replace('.', "0"),
+                Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData => {
+                    return Some(Err(io::Error::new(io::ErrorKind::InvalidData, NotText)))
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => break,
+            };
+
+            if line.chars().count() >= self.squares {
+                if self.current.is_empty() {
+                    self.done = self.first_only;
+                    return Some(Ok(line));
+                }
+                self.held = Some(line);
+                return Some(Ok(self.take()));
+            }
+
+            self.current.push_str(&line);
+            if (line.is_empty() || self.current.chars().count() >= self.squares)
+                && !self.current.is_empty()
+            {
+                return Some(Ok(self.take()));
+            }
+        }
+
+        if self.current.is_empty() {
+            None
+        } else {
+            Some(Ok(self.take()))
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! `sudoku minimize`: remove redundant givens from puzzles.
+
+use crate::cli::{
+    exit::Exit,
+    input::{BoxShape, Source},
+    output::{csv_field, digits, puzzle_grid, Format},
+};
+use serde_json::json;
+use std::io::{self, Write};
+use sudoku::puzzle::SudokuPuzzle;
+
+#[derive(clap::Args)]
+pub struct MinimizeArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Minimize puzzles with boxes of R rows and C columns instead of 9x9
+    /// puzzles, such as 2x3 for 6x6 puzzles or 3x4 for 12x12 ones
+    #[arg(long = "box", value_name = "RxC")]
+    pub box_shape: Option<BoxShape>,
+}
+
+/// A puzzle and the same puzzle minimized, each on one line, with the
+/// minimized one also drawn as a grid.
+struct Minimized {
+    puzzle: String,
+    minimized: String,
+    grid: String,
+}
+
+/// The number of filled squares in a puzzle on one line.
+fn clues(line: &str) -> usize {
+    line.chars().filter(|c| *c != '0' && *c != '.').count()
+}
+
+/// Read and minimize a puzzle, or say why it can't be, with the exit code
+/// for that.
+fn minimize(
+    puzzle_source: &str,
+    shape: Option<BoxShape>,
+    format: Format,
+    color: bool,
+) -> Result<Minimized, (Exit, String)> {
+    let parse_error = |e: sudoku::Error| (Exit::ParseError, e.to_string());
+    let (minimized, solutions) = match shape {
+        Some(shape) => {
+            let grid = shape
+                .grid(puzzle_source)
+                .map_err(|e| parse_error(e.into()))?;
+            let minimized = grid.minimized().map(|minimized| Minimized {
+                puzzle: grid.to_line(),
+                minimized: minimized.to_line(),
+                grid: minimized.to_string(),
+            });
+            (minimized, grid.count_solutions(1))
+        }
+        None => {
+            let puzzle = SudokuPuzzle::from_string(puzzle_source).map_err(parse_error)?;
+            let minimized = puzzle.minimized().map(|minimized| Minimized {
+                puzzle: digits(&puzzle.to_vec()),
+                minimized: digits(&minimized.to_vec()),
+                grid: puzzle_grid(&minimized, format, color),
+            });
+            (minimized, puzzle.count_solutions(1))
+        }
+    };
+    match (minimized, solutions) {
+        (Some(minimized), _) => Ok(minimized),
+        (None, 0) => Err((Exit::Unsolvable, String::from("the puzzle has no solution"))),
+        (None, _) => Err((
+            Exit::Multiple,
+            String::from("the puzzle has more than one solution"),
+        )),
+    }
+}
+
+/// Print each puzzle with as many givens removed as possible while keeping
+/// its solution unique, along with how many clues it had before and after.
+pub fn run(args: &MinimizeArgs, format: Format, color: bool) -> io::Result<Exit> {
+    let mut out = io::stdout().lock();
+    let mut exit = Exit::Success;
+    if format == Format::Csv {
+        writeln!(out, "puzzle,minimized,clues_before,clues_after,error")?;
+    }
+
+    let squares = args.box_shape.map_or(81, BoxShape::squares);
+    for (i, puzzle_source) in args.source.puzzles_of(squares)?.enumerate() {
+        let puzzle_source = puzzle_source?;
+        let minimized = match minimize(&puzzle_source, args.box_shape, format, color) {
+            Ok(minimized) => minimized,
+            Err((error_exit, message)) => {
+                exit = exit.max(error_exit);
+                match format {
+                    Format::Grid | Format::Line | Format::Spoken => {
+                        writeln!(out, "puzzle {}: {}", i + 1, message)?
+                    }
+                    Format::Json => writeln!(
+                        out,
+                        "{}",
+                        json!({ "puzzle": puzzle_source, "error": message })
+                    )?,
+                    Format::Csv => writeln!(
+                        out,
+                        "{},,,,{}",
+                        csv_field(&puzzle_source),
+                        csv_field(&message)
+                    )?,
+                }
+                continue;
+            }
+        };
+
+        let (before, after) = (clues(&minimized.puzzle), clues(&minimized.minimized));
+        let line = &minimized.minimized;
+        match format {
+            Format::Grid | Format::Spoken => writeln!(
+                out,
+                "puzzle {}: {} clues, minimized to {}\n{}",
+                i + 1,
+                before,
+                after,
+                minimized.grid
+            )?,
+            Format::Line => writeln!(out, "{}", line)?,
+            Format::Json => writeln!(
+                out,
+                "{}",
+                json!({
+                    "puzzle": minimized.puzzle,
+                    "minimized": line,
+                    "clues_before": before,
+                    "clues_after": after,
+                })
+            )?,
+            Format::Csv => writeln!(out, "{},{},{},{},", minimized.puzzle, line, before, after)?,
+        }
+    }
+
+    Ok(exit)
+}
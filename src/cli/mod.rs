@@ -0,0 +1,24 @@
+//! The subcommands of the sudoku command line interface.
+
+pub mod bench;
+pub mod calibrate;
+pub mod canonicalize;
+pub mod check;
+pub mod compare;
+pub mod config;
+pub mod convert;
+pub mod daily;
+pub mod db;
+pub mod exit;
+pub mod generate;
+pub mod input;
+pub mod minimize;
+pub mod output;
+pub mod play;
+pub mod render;
+#[cfg(feature = "server")]
+pub mod serve;
+pub mod solve;
+pub mod train;
+pub mod watch;
+pub mod worksheet;
@@ -0,0 +1,258 @@
+//! How puzzles, solutions, and results are printed.
+
+use clap::ValueEnum;
+use std::io::IsTerminal;
+use sudoku::{puzzle::SudokuPuzzle, step::Step};
+
+/// The ways that results can be printed.
+#[derive(Clone, Copy, PartialEq, Default, ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// 9x9 grids, for reading in a terminal
+    #[default]
+    Grid,
+    /// One line per puzzle, with squares as 81 digits
+    Line,
+    /// One JSON object per puzzle, one per line
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Grids read out row by row in words, and steps as sentences, for
+    /// screen readers
+    Spoken,
+}
+
+/// A puzzle or solution as 81 digits, where 0 is an empty square.
+pub fn digits(nums: &[u8]) -> String {
+    nums.iter().map(|n| char::from(b'0' + n)).collect()
+}
+
+/// A number of solutions that was counted up to cap, such as "2+" when
+/// the count stopped at the cap.
+pub fn solution_count(count: usize, cap: usize) -> String {
+    if count >= cap {
+        format!("{}+", count)
+    } else {
+        count.to_string()
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote, or line break.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+/// How the number in a square is highlighted in a grid.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Highlight {
+    /// One of the puzzle's givens
+    Given,
+    /// A number filled in by the solver
+    Filled,
+    /// A number that repeats within its row, column, or 3x3 square
+    Conflict,
+    /// The square that the solver has just filled
+    Current,
+}
+
+impl Highlight {
+    /// The ANSI escape code that starts this highlight.
+    fn code(self) -> &'static str {
+        match self {
+            Highlight::Given => "\x1b[1m",
+            Highlight::Filled => "\x1b[32m",
+            Highlight::Conflict => "\x1b[1;31m",
+            Highlight::Current => "\x1b[1;30;43m",
+        }
+    }
+}
+
+/// Whether to print in color: not when asked not to with --no-color or the
+/// NO_COLOR environment variable, nor when stdout is not a terminal.
+pub fn use_color(no_color: bool) -> bool {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    !no_color && !no_color_env && std::io::stdout().is_terminal()
+}
+
+/// The width of the terminal that stdout is, or `None` when output is
+/// redirected. `COLUMNS` takes precedence over asking the terminal, as it
+/// does for other command line tools.
+pub fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .or_else(|| {
+            ratatui::crossterm::terminal::size()
+                .ok()
+                .map(|(columns, _)| usize::from(columns))
+        })
+}
+
+/// How a grid is drawn, by how much room there is for it.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Layout {
+    /// ASCII lines, the same everywhere, for output that is redirected to a
+    /// file or another program.
+    Ascii,
+    /// Box-drawing lines, heavier between the 3x3 boxes.
+    Unicode,
+    /// One character per square and lines only between the boxes, for
+    /// terminals too narrow for the full grid.
+    Compact,
+}
+
+impl Layout {
+    /// The layout for a grid that is width columns wide when drawn in full:
+    /// ASCII when output is redirected, Unicode when the terminal has room,
+    /// and compact when it does not.
+    pub fn for_width(width: usize) -> Layout {
+        match terminal_width() {
+            None => Layout::Ascii,
+            Some(columns) if columns >= width => Layout::Unicode,
+            Some(_) => Layout::Compact,
+        }
+    }
+}
+
+/// The width of a full 9x9 grid of numbers.
+const GRID_WIDTH: usize = 37;
+
+/// A 9x9 grid of the squares given by `square(row, col)`, drawn to fit the
+/// terminal (see [`Layout`]), with each number highlighted if `color` is
+/// set. Redirected output is laid out the same way as the Debug output of a
+/// puzzle. The spoken format describes the grid in words instead.
+pub fn grid<F>(square: F, format: Format, color: bool) -> String
+where
+    F: Fn(usize, usize) -> Option<(u8, Highlight)>,
+{
+    if format == Format::Spoken {
+        return spoken_grid(square);
+    }
+    let number = |row, col, empty: &str| match square(row, col) {
+        Some((n, highlight)) if color => format!("{}{}\x1b[0m", highlight.code(), n),
+        Some((n, _)) => n.to_string(),
+        None => String::from(empty),
+    };
+
+    let mut lines = Vec::new();
+    match Layout::for_width(GRID_WIDTH) {
+        Layout::Ascii => {
+            let separator = "-".repeat(GRID_WIDTH);
+            for row in 0..9 {
+                lines.push(separator.clone());
+                let squares: Vec<String> = (0..9).map(|col| number(row, col, " ")).collect();
+                lines.push(format!("| {} |", squares.join(" | ")));
+            }
+            lines.push(separator);
+        }
+        Layout::Unicode => {
+            lines.push(rule('━', ['┏', '┯', '┳', '┓']));
+            for row in 0..9 {
+                let mut line = String::from("┃");
+                for col in 0..9 {
+                    let side = if col % 3 == 2 { '┃' } else { '│' };
+                    line.push_str(&format!(" {} {}", number(row, col, " "), side));
+                }
+                lines.push(line);
+                lines.push(match row {
+                    8 => rule('━', ['┗', '┷', '┻', '┛']),
+                    _ if row % 3 == 2 => rule('━', ['┣', '┿', '╋', '┫']),
+                    _ => rule('─', ['┠', '┼', '╂', '┨']),
+                });
+            }
+        }
+        Layout::Compact => {
+            for row in 0..9 {
+                if row > 0 && row % 3 == 0 {
+                    lines.push(String::from("---+---+---"));
+                }
+                let boxes: Vec<String> = (0..3)
+                    .map(|bx| (bx * 3..bx * 3 + 3).map(|col| number(row, col, ".")).collect())
+                    .collect();
+                lines.push(boxes.join("|"));
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// A line across a Unicode grid drawn with line, with the joints given as
+/// [left end, between squares, between 3x3 boxes, right end].
+fn rule(line: char, [left, between, between_boxes, right]: [char; 4]) -> String {
+    let mut rule = String::from(left);
+    for col in 0..9 {
+        rule.extend([line; 3]);
+        rule.push(match col {
+            8 => right,
+            _ if col % 3 == 2 => between_boxes,
+            _ => between,
+        });
+    }
+    rule
+}
+
+/// The squares given by `square(row, col)` as one line per row, such as
+/// "Row 1: 4, 1, blank, blank, 3, 6, blank, blank, blank", which a screen
+/// reader can read out. Conflicting numbers are announced as such.
+fn spoken_grid<F>(square: F) -> String
+where
+    F: Fn(usize, usize) -> Option<(u8, Highlight)>,
+{
+    let rows: Vec<String> = (0..9)
+        .map(|row| {
+            let squares: Vec<String> = (0..9)
+                .map(|col| match square(row, col) {
+                    Some((n, Highlight::Conflict)) => format!("{} conflicting", n),
+                    Some((n, _)) => n.to_string(),
+                    None => String::from("blank"),
+                })
+                .collect();
+            format!("Row {}: {}", row + 1, squares.join(", "))
+        })
+        .collect();
+    rows.join("\n")
+}
+
+/// A grid of the numbers in puzzle, with givens highlighted and any
+/// conflicting numbers highlighted as conflicts.
+pub fn puzzle_grid(puzzle: &SudokuPuzzle, format: Format, color: bool) -> String {
+    let conflicts = puzzle.conflicts();
+    grid(
+        |row, col| {
+            let highlight = if conflicts.contains(&(row, col)) {
+                Highlight::Conflict
+            } else {
+                Highlight::Given
+            };
+            puzzle.get(row, col).map(|n| (n, highlight))
+        },
+        format,
+        color,
+    )
+}
+
+/// A step as a sentence, such as "Row 4, column 7 is 2, the only place for
+/// 2 in column 7." for the spoken format, or as its usual one line
+/// description otherwise.
+pub fn step_text(step: &Step, format: Format) -> String {
+    if format != Format::Spoken {
+        return step.to_string();
+    }
+    let (row, col) = step.square();
+    let square = format!("Row {}, column {}", row + 1, col + 1);
+    match *step {
+        Step::Single { n, .. } => format!("{} is {}, the only number that fits there.", square, n),
+        Step::HiddenSingle { n, unit, .. } => {
+            format!("{} is {}, the only place for {} in {}.", square, n, n, unit)
+        }
+        Step::Guess { n, .. } => format!("{}: guess {}.", square, n),
+        Step::Backtrack { .. } => format!("{}: the guess failed, so take it back.", square),
+    }
+}
@@ -0,0 +1,703 @@
+//! `sudoku play`: solve a puzzle by hand in the terminal.
+
+use crate::cli::{exit::Exit, input::Source};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Flex, Layout},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+    DefaultTerminal, Frame,
+};
+use serde_json::json;
+use std::{
+    convert::TryFrom,
+    fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use sudoku::{
+    hint::Level,
+    history::History,
+    puzzle::{Mistake, SudokuPuzzle},
+    replay::{Action, Recording},
+    solution::Solution,
+    stuck,
+};
+
+/// Where games are saved when neither --save nor --resume is given.
+const DEFAULT_SAVE: &str = "sudoku-save.json";
+
+#[derive(clap::Args)]
+pub struct PlayArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Continue a game saved with the s key
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["file", "puzzle"])]
+    pub resume: Option<PathBuf>,
+
+    /// Where the s key saves the game: the file given to --resume, or
+    /// sudoku-save.json, by default
+    #[arg(long, value_name = "FILE")]
+    pub save: Option<PathBuf>,
+
+    /// Record every move, with its time, to FILE when the game ends
+    #[arg(long, value_name = "FILE", conflicts_with = "resume")]
+    pub record: Option<PathBuf>,
+
+    /// Play back a game recorded with --record instead of playing
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["file", "puzzle", "resume", "save", "record"])]
+    pub replay: Option<PathBuf>,
+
+    /// With --replay, how many times faster than it was played to play the
+    /// game back
+    #[arg(long, value_name = "FACTOR", default_value_t = 1.0, requires = "replay", value_parser = speed)]
+    pub speed: f64,
+}
+
+/// Read a playback speed, which must be a positive number.
+fn speed(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(speed) if speed > 0.0 && speed.is_finite() => Ok(speed),
+        Ok(_) => Err(String::from("expected a positive number")),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Play the first puzzle from the source, continue a saved game, or play
+/// back a recorded one.
+pub fn run(args: &PlayArgs) -> io::Result<Exit> {
+    if let Some(path) = &args.replay {
+        return replay(&fs::read_to_string(path)?, args.speed);
+    }
+
+    let save = args
+        .save
+        .clone()
+        .or_else(|| args.resume.clone())
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SAVE));
+
+    let mut game = match &args.resume {
+        Some(path) => match Game::load(&fs::read_to_string(path)?) {
+            Ok(game) => game,
+            Err(e) => {
+                eprintln!("Invalid saved game: {}", e);
+                return Ok(Exit::ParseError);
+            }
+        },
+        None => {
+            let puzzle_source = match args.source.puzzles()?.next() {
+                Some(puzzle_source) => puzzle_source?,
+                None => {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidInput,
+                        "no puzzle was given",
+                    ))
+                }
+            };
+            match SudokuPuzzle::from_string(&puzzle_source) {
+                Ok(puzzle) => Game::new(puzzle),
+                Err(e) => {
+                    eprintln!("Invalid puzzle: {}", e);
+                    return Ok(Exit::ParseError);
+                }
+            }
+        }
+    };
+    game.save_path = save;
+    if args.record.is_some() {
+        game.recording = Some(Recording::new(game.history.puzzle().clone()));
+    }
+
+    let mut terminal = ratatui::init();
+    let result = game.play(&mut terminal);
+    ratatui::restore();
+
+    if game.finished.is_some() {
+        println!("Solved!  {}  Score {}", game.stats(), game.score());
+    }
+    if let (Some(path), Some(recording)) = (&args.record, &game.recording) {
+        fs::write(path, recording.to_string())?;
+    }
+    result.map(|_| Exit::Success)
+}
+
+/// Play back the game recorded in text at speed times its pace.
+fn replay(text: &str, speed: f64) -> io::Result<Exit> {
+    let recording: Recording = match text.parse() {
+        Ok(recording) => recording,
+        Err(e) => {
+            eprintln!("Invalid recording: {}", e);
+            return Ok(Exit::ParseError);
+        }
+    };
+    let mut game = Game::new(recording.puzzle().clone());
+    game.replaying = true;
+
+    let mut terminal = ratatui::init();
+    let result = game.watch(&recording, speed, &mut terminal);
+    ratatui::restore();
+    result.map(|_| Exit::Success)
+}
+
+/// The state of a game in progress.
+struct Game {
+    history: History,
+    /// The (row, col) of the selected square.
+    cursor: (usize, usize),
+    /// Whether digits toggle pencil marks rather than filling squares.
+    notes: bool,
+    /// Feedback on the last key pressed, such as an attempt to change a given.
+    message: String,
+    /// The squares found to be wrong by the last check, shown until the next
+    /// key is pressed.
+    checked: Vec<Mistake>,
+    /// The solution that entries are checked against, if it is unique.
+    solution: Option<Solution>,
+    started: Instant,
+    /// How long the puzzle took to solve, once it has been solved.
+    finished: Option<Duration>,
+    /// Numbers placed that don't match the solution.
+    mistakes: u32,
+    hints: u32,
+    /// How much of the next hint the ? key has given away, until another
+    /// key is pressed.
+    hint_level: Option<Level>,
+    /// Where the s key saves the game.
+    save_path: PathBuf,
+    /// Every move made, if the game is being recorded.
+    recording: Option<Recording>,
+    /// Whether the game is a recording being played back.
+    replaying: bool,
+}
+
+impl Game {
+    fn new(puzzle: SudokuPuzzle) -> Self {
+        Game::from_history(History::new(puzzle))
+    }
+
+    /// Continue a game from history, with the clock started from zero.
+    fn from_history(history: History) -> Self {
+        let mut givens = history.puzzle().clone();
+        givens.reset();
+        let mut solutions = givens.solutions();
+        let solution = match (solutions.next(), solutions.next()) {
+            (Some(solution), None) => Some(solution),
+            _ => None,
+        };
+
+        Game {
+            history,
+            cursor: (0, 0),
+            notes: false,
+            message: String::new(),
+            checked: Vec::new(),
+            solution,
+            started: Instant::now(),
+            finished: None,
+            mistakes: 0,
+            hints: 0,
+            hint_level: None,
+            save_path: PathBuf::from(DEFAULT_SAVE),
+            recording: None,
+            replaying: false,
+        }
+    }
+
+    /// The game as JSON: the puzzle and its history as saved by
+    /// `History::save`, along with the time spent, mistakes, and hints.
+    fn to_json(&self) -> String {
+        json!({
+            "history": self.history.save(),
+            "elapsed_ms": self.elapsed().as_millis() as u64,
+            "mistakes": self.mistakes,
+            "hints": self.hints,
+            "cursor": [self.cursor.0, self.cursor.1],
+            "notes": self.notes,
+        })
+        .to_string()
+    }
+
+    /// Continue a game saved by `to_json`, with the clock running from the
+    /// time already spent.
+    fn load(saved: &str) -> Result<Self, String> {
+        let saved: serde_json::Value = serde_json::from_str(saved).map_err(|e| e.to_string())?;
+        let history = saved["history"].as_str().ok_or("the history is missing")?;
+        let history = History::restore(history).map_err(|e| e.to_string())?;
+
+        let mut game = Game::from_history(history);
+        let elapsed = Duration::from_millis(saved["elapsed_ms"].as_u64().unwrap_or(0));
+        game.started = Instant::now().checked_sub(elapsed).unwrap_or(game.started);
+        // Counts may be missing, but not out of range
+        let count = |key: &str| match &saved[key] {
+            serde_json::Value::Null => Ok(0),
+            value => value
+                .as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .ok_or_else(|| format!("{} is not a count", key)),
+        };
+        game.mistakes = count("mistakes")?;
+        game.hints = count("hints")?;
+        if let (Some(row), Some(col)) = (saved["cursor"][0].as_u64(), saved["cursor"][1].as_u64()) {
+            game.cursor = (row.min(8) as usize, col.min(8) as usize);
+        }
+        game.notes = saved["notes"].as_bool().unwrap_or(false);
+        Ok(game)
+    }
+
+    /// Write the game to its save file and report where it went.
+    fn save(&mut self) {
+        self.message = match fs::write(&self.save_path, self.to_json()) {
+            Ok(()) => format!("saved to {}", self.save_path.display()),
+            Err(e) => format!("could not save: {}", e),
+        };
+    }
+
+    /// Draw the game and respond to keys until the player quits.
+    fn play(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            // Wake up regularly to keep the timer ticking
+            if !event::poll(Duration::from_millis(250))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Respond to a key, returning `false` once the game should end.
+    fn handle(&mut self, key: KeyCode) -> bool {
+        let (row, col) = self.cursor;
+        self.message.clear();
+        self.checked.clear();
+        if key != KeyCode::Char('?') {
+            self.hint_level = None;
+        }
+
+        if self.history.puzzle().is_solved() {
+            return false;
+        }
+
+        let result = match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.cursor = ((row + 8) % 9, col);
+                Ok(())
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor = ((row + 1) % 9, col);
+                Ok(())
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.cursor = (row, (col + 8) % 9);
+                Ok(())
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.cursor = (row, (col + 1) % 9);
+                Ok(())
+            }
+            KeyCode::Char(c @ '1'..='9') if self.notes => {
+                if self.history.puzzle().get(row, col).is_some() {
+                    self.message = String::from("only empty squares can have notes");
+                    Ok(())
+                } else {
+                    let n = c as u8 - b'0';
+                    self.act(Action::Mark { row, col, n })
+                }
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let n = c as u8 - b'0';
+                self.act(Action::Place { row, col, n })
+            }
+            KeyCode::Char('?') => self.hint(),
+            KeyCode::Char('t') => {
+                self.tip();
+                Ok(())
+            }
+            KeyCode::Char('c') => {
+                self.check();
+                Ok(())
+            }
+            KeyCode::Char('s') => {
+                self.save();
+                Ok(())
+            }
+            KeyCode::Char('n') => {
+                self.notes = !self.notes;
+                Ok(())
+            }
+            KeyCode::Char('a') => self.act(Action::FillMarks),
+            KeyCode::Char('0') | KeyCode::Backspace | KeyCode::Delete => {
+                self.act(Action::Erase { row, col })
+            }
+            KeyCode::Char('u') => self.act(Action::Undo),
+            KeyCode::Char('r') => self.act(Action::Redo),
+            _ => Ok(()),
+        };
+
+        if let Err(e) = result {
+            self.message = e.to_string();
+        }
+        if self.history.puzzle().is_solved() {
+            self.finished = Some(self.started.elapsed());
+        }
+        true
+    }
+
+    /// Give away a little more of the solver's next step: first the
+    /// technique, then where to look, then the square and its number, and
+    /// last fill that square in. Only the square and number count as a hint.
+    fn hint(&mut self) -> Result<(), sudoku::Error> {
+        let puzzle = self.history.puzzle();
+        let wrong = self
+            .solution
+            .is_some_and(|solution| !puzzle.check_against(&solution).is_empty());
+        let hint = match puzzle.hint() {
+            Some(hint) if !wrong => hint,
+            _ => {
+                self.message = String::from("some numbers are wrong: press c to find them");
+                return Ok(());
+            }
+        };
+
+        let level = match self.hint_level {
+            None => Level::Technique,
+            Some(level) => match level.next() {
+                Some(level) => level,
+                None => {
+                    let (row, col, n) = hint.cell();
+                    self.cursor = (row, col);
+                    self.hint_level = None;
+                    return self.act(Action::Place { row, col, n });
+                }
+            },
+        };
+        if level == Level::Cell {
+            let (row, col, n) = hint.cell();
+            self.act(Action::Hint { row, col, n })?;
+        }
+        self.hint_level = Some(level);
+        self.message = hint.reveal(level);
+        Ok(())
+    }
+
+    /// Say which technique gets the player unstuck and where to use it,
+    /// taking their notes into account, without giving away the move.
+    fn tip(&mut self) {
+        let puzzle = self.history.puzzle();
+        let wrong = self
+            .solution
+            .is_some_and(|solution| !puzzle.check_against(&solution).is_empty());
+        self.message = if wrong || puzzle.solve().is_none() {
+            String::from("some numbers are wrong: press c to find them")
+        } else {
+            match stuck::nudge(puzzle, true) {
+                Some(nudge) => nudge.to_string(),
+                None => String::from("nothing simple applies here: press ? for a hint"),
+            }
+        };
+    }
+
+    /// Make a move, counting numbers placed that don't match the solution as
+    /// mistakes and hints that give away a square, and record it if the game
+    /// is being recorded.
+    fn act(&mut self, action: Action) -> Result<(), sudoku::Error> {
+        let before = action
+            .square()
+            .and_then(|(row, col)| self.history.puzzle().get(row, col));
+        action.apply(&mut self.history)?;
+        match action {
+            Action::Place { row, col, n } => {
+                let wrong = self.solution.is_some_and(|s| s.get(row, col) != n);
+                if wrong && before != Some(n) {
+                    self.mistakes += 1;
+                }
+            }
+            Action::Hint { .. } => self.hints += 1,
+            _ => (),
+        }
+
+        let at = self.elapsed();
+        if let Some(recording) = &mut self.recording {
+            let recorded = recording.record(at, action);
+            debug_assert!(recorded.is_ok());
+        }
+        Ok(())
+    }
+
+    /// Play recording back, at speed times the pace it was played at, until
+    /// the player quits. Space pauses and resumes, and once every move has
+    /// been made any key ends the playback. The timer shows the recording's
+    /// clock, stopping at its last move.
+    fn watch(
+        &mut self,
+        recording: &Recording,
+        speed: f64,
+        terminal: &mut DefaultTerminal,
+    ) -> io::Result<()> {
+        let mut events = recording.events().iter().peekable();
+        let mut clock = Duration::ZERO;
+        let mut paused = false;
+        let mut ticked = Instant::now();
+        loop {
+            if !paused {
+                clock += ticked.elapsed().mul_f64(speed);
+            }
+            ticked = Instant::now();
+            while let Some(event) = events.next_if(|event| event.at <= clock) {
+                let result = self.act(event.action);
+                debug_assert!(result.is_ok());
+                self.cursor = event.action.square().unwrap_or(self.cursor);
+                self.message = describe(event.action);
+            }
+            self.finished = Some(clock.min(recording.duration()));
+
+            terminal.draw(|frame| self.draw(frame))?;
+            if !event::poll(Duration::from_millis(50))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char(' ') => paused = !paused,
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    _ if events.peek().is_none() => return Ok(()),
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// Highlight the squares filled with the wrong number, without showing
+    /// what they should be.
+    fn check(&mut self) {
+        let solution = match self.solution {
+            Some(solution) => solution,
+            None => {
+                self.message = String::from("checking needs a puzzle with one solution");
+                return;
+            }
+        };
+
+        self.checked = self.history.puzzle().check_against(&solution);
+        self.message = match self.checked.len() {
+            0 => String::from("no mistakes so far"),
+            1 => String::from("1 square is wrong"),
+            wrong => format!("{} squares are wrong", wrong),
+        };
+    }
+
+    /// The time spent on the puzzle so far, or until it was solved.
+    fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.started.elapsed())
+    }
+
+    /// The score for solving the puzzle: 1000 points, less 25 for each
+    /// mistake, 50 for each hint, and 1 for every 6 seconds taken.
+    fn score(&self) -> u32 {
+        let seconds = u32::try_from(self.elapsed().as_secs()).unwrap_or(u32::MAX);
+        let penalty = self
+            .mistakes
+            .saturating_mul(25)
+            .saturating_add(self.hints.saturating_mul(50))
+            .saturating_add(seconds / 6);
+        1000u32.saturating_sub(penalty)
+    }
+
+    /// The time spent, mistakes made, and hints used.
+    fn stats(&self) -> String {
+        let secs = self.elapsed().as_secs();
+        let mistakes = match self.solution {
+            Some(_) => self.mistakes.to_string(),
+            None => String::from("-"),
+        };
+        format!(
+            "Time {:02}:{:02}  Mistakes {}  Hints {}",
+            secs / 60,
+            secs % 60,
+            mistakes,
+            self.hints
+        )
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let puzzle = self.history.puzzle();
+        let conflicts = puzzle.conflicts();
+
+        let mut lines = vec![rule('━', ['┏', '┯', '┳', '┓'])];
+        for row in 0..9 {
+            // Each square is three lines tall, so that it can hold a 3x3
+            // block of pencil marks
+            for line in 0..3 {
+                let mut spans = vec![Span::raw("┃")];
+                for col in 0..9 {
+                    spans.push(self.square_line(row, col, line, &conflicts));
+                    spans.push(Span::raw(if col % 3 == 2 { "┃" } else { "│" }));
+                }
+                lines.push(Line::from(spans));
+            }
+            lines.push(match row {
+                8 => rule('━', ['┗', '┷', '┻', '┛']),
+                _ if row % 3 == 2 => rule('━', ['┣', '┿', '╋', '┫']),
+                _ => rule('─', ['┠', '┼', '╂', '┨']),
+            });
+        }
+
+        let mode = if self.notes { "notes" } else { "numbers" };
+        lines.push(Line::from(self.stats()));
+        lines.push(Line::from(format!("Mode: {}", mode)));
+        let message = wrap(&self.message, 73);
+        if message.is_empty() {
+            lines.push(Line::from(""));
+        }
+        for line in message {
+            lines.push(Line::from(line).red());
+        }
+        if self.replaying {
+            lines.push(Line::from("space pause  q quit").dim());
+        } else {
+            lines.push(Line::from("arrows/hjkl move  1-9 place  0/del clear  n notes mode").dim());
+            lines.push(
+                Line::from(
+                    "a fill in notes  ? hint  t tip  c check  u undo  r redo  s save  q quit",
+                )
+                .dim(),
+            );
+        }
+
+        let [area] = Layout::horizontal([Constraint::Length(75)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let block = Block::bordered().title(" Sudoku ");
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+
+        if puzzle.is_solved() {
+            let [popup] = Layout::horizontal([Constraint::Length(26)])
+                .flex(Flex::Center)
+                .areas(area);
+            let [popup] = Layout::vertical([Constraint::Length(9)])
+                .flex(Flex::Center)
+                .areas(popup);
+            let secs = self.elapsed().as_secs();
+            let win = Paragraph::new(vec![
+                Line::from("Solved!").bold().green(),
+                Line::from(""),
+                Line::from(format!("Time      {:02}:{:02}", secs / 60, secs % 60)),
+                Line::from(format!("Mistakes  {:>5}", self.mistakes)),
+                Line::from(format!("Hints     {:>5}", self.hints)),
+                Line::from(format!("Score     {:>5}", self.score())).bold(),
+                Line::from("Press any key to exit").dim(),
+            ])
+            .centered()
+            .block(Block::bordered());
+            frame.render_widget(Clear, popup);
+            frame.render_widget(win, popup);
+        }
+    }
+
+    /// One of the three lines of the square at (row, col): its number on the
+    /// middle line, or else its pencil marks, three to a line.
+    fn square_line(
+        &self,
+        row: usize,
+        col: usize,
+        line: usize,
+        conflicts: &[(usize, usize)],
+    ) -> Span<'static> {
+        let puzzle = self.history.puzzle();
+        let mut style = Style::default();
+
+        let text = match puzzle.get(row, col) {
+            Some(n) => {
+                if puzzle.is_given(row, col) {
+                    style = style.add_modifier(Modifier::BOLD);
+                } else {
+                    style = style.cyan();
+                }
+                let wrong = self.checked.iter().any(|m| (m.row, m.col) == (row, col));
+                if wrong || conflicts.contains(&(row, col)) {
+                    style = style.red();
+                }
+                match line {
+                    1 => format!("   {}   ", n),
+                    _ => String::from("       "),
+                }
+            }
+            None => {
+                style = style.dim();
+                let marks = puzzle.marks(row, col);
+                let mut text = String::from(" ");
+                for n in (line as u8 * 3 + 1)..=(line as u8 * 3 + 3) {
+                    text.push(if marks.contains(n) {
+                        char::from(b'0' + n)
+                    } else {
+                        ' '
+                    });
+                    text.push(' ');
+                }
+                text
+            }
+        };
+
+        if self.cursor == (row, col) {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        Span::styled(text, style)
+    }
+}
+
+/// A move being played back, described for the player.
+fn describe(action: Action) -> String {
+    match action {
+        Action::Place { row, col, n } => format!("placed {} in r{}c{}", n, row + 1, col + 1),
+        Action::Erase { row, col } => format!("cleared r{}c{}", row + 1, col + 1),
+        Action::Mark { row, col, n } => format!("toggled note {} in r{}c{}", n, row + 1, col + 1),
+        Action::FillMarks => String::from("filled in notes"),
+        Action::Undo => String::from("undid a move"),
+        Action::Redo => String::from("redid a move"),
+        Action::Hint { row, col, n } => {
+            format!("hint: {} goes in r{}c{}", n, row + 1, col + 1)
+        }
+    }
+}
+
+/// A horizontal line across the grid drawn with line, with the joints
+/// given as [left end, between squares, between 3x3 squares, right end].
+fn rule(line: char, [left, between, between_boxes, right]: [char; 4]) -> Line<'static> {
+    let mut rule = String::from(left);
+    for col in 0..9 {
+        rule.extend([line; 7]);
+        rule.push(match col {
+            8 => right,
+            _ if col % 3 == 2 => between_boxes,
+            _ => between,
+        });
+    }
+    Line::from(rule)
+}
+
+/// Break text into lines of at most width characters, between words.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(String::from(word)),
+        }
+    }
+    lines
+}
@@ -0,0 +1,126 @@
+//! `sudoku render`: draw a puzzle as an SVG, PNG, HTML, or text file.
+
+use crate::cli::{exit::Exit, input::Source};
+use clap::ValueEnum;
+use std::{
+    fs::File,
+    io::{self, BufWriter, ErrorKind, Write},
+    path::PathBuf,
+};
+use sudoku::{
+    puzzle::SudokuPuzzle,
+    render::{self, RenderOptions},
+};
+
+/// The kinds of file a puzzle can be drawn as.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum ImageType {
+    Svg,
+    Png,
+    Html,
+    #[value(alias = "txt")]
+    Text,
+}
+
+#[derive(clap::Args)]
+pub struct RenderArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// The file to write, or stdout if not given
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// The kind of file to write, by default the one that matches the
+    /// output's extension, or SVG
+    #[arg(long = "type", value_enum)]
+    pub image_type: Option<ImageType>,
+
+    /// Fill the empty squares from the solution, in grey
+    #[arg(long)]
+    pub solution: bool,
+
+    /// Write the candidates of each empty square in small digits
+    #[arg(long, conflicts_with = "solution")]
+    pub candidates: bool,
+
+    /// Squares to shade, such as r4c7, separated by commas
+    #[arg(long, value_name = "SQUARES", value_delimiter = ',', value_parser = parse_square)]
+    pub highlight: Vec<(usize, usize)>,
+}
+
+/// Read a square written as "r4c7", with 1-based row and column numbers.
+fn parse_square(s: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("'{}' is not a square such as r4c7", s);
+    let lower = s.trim().to_ascii_lowercase();
+    let (row, col) = lower
+        .strip_prefix('r')
+        .and_then(|rest| rest.split_once('c'))
+        .ok_or_else(invalid)?;
+    let row: usize = row.parse().map_err(|_| invalid())?;
+    let col: usize = col.parse().map_err(|_| invalid())?;
+    if !(1..=9).contains(&row) || !(1..=9).contains(&col) {
+        return Err(invalid());
+    }
+    Ok((row - 1, col - 1))
+}
+
+/// Draw the first puzzle from the source.
+pub fn run(args: &RenderArgs) -> io::Result<Exit> {
+    let puzzle_source = match args.source.puzzles()?.next() {
+        Some(puzzle_source) => puzzle_source?,
+        None => {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "no puzzle was given",
+            ))
+        }
+    };
+    let puzzle = match SudokuPuzzle::from_string(&puzzle_source) {
+        Ok(puzzle) => puzzle,
+        Err(e) => {
+            eprintln!("Invalid puzzle: {}", e);
+            return Ok(Exit::ParseError);
+        }
+    };
+
+    let options = RenderOptions {
+        solution: args.solution,
+        candidates: args.candidates,
+        highlight: args.highlight.clone(),
+    };
+    let extension = args
+        .output
+        .as_ref()
+        .and_then(|path| path.extension())
+        .and_then(|extension| ImageType::from_str(&extension.to_string_lossy(), true).ok());
+    let image_type = args.image_type.or(extension).unwrap_or(ImageType::Svg);
+
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut out = BufWriter::new(output);
+    match image_type {
+        ImageType::Svg => out.write_all(render::svg(&puzzle, &options).as_bytes())?,
+        ImageType::Html => out.write_all(render::html(&puzzle, &options).as_bytes())?,
+        ImageType::Text => out.write_all(render::text(&puzzle, &options).as_bytes())?,
+        ImageType::Png => {
+            let image = render::raster(&puzzle, &options);
+            let mut encoder = png::Encoder::new(&mut out, image.width as u32, image.height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().map_err(io::Error::other)?;
+            writer
+                .write_image_data(&image.pixels)
+                .map_err(io::Error::other)?;
+        }
+    }
+    out.flush()?;
+
+    if args.solution && puzzle.solve().is_none() {
+        eprintln!("No solution could be found");
+        return Ok(Exit::Unsolvable);
+    }
+    Ok(Exit::Success)
+}
@@ -0,0 +1,19 @@
+//! `sudoku serve`: run the HTTP solver service.
+
+use crate::cli::exit::Exit;
+use std::io;
+
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    /// The address and port to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub addr: String,
+}
+
+/// Serve until the process is stopped.
+pub fn run(args: &ServeArgs) -> io::Result<Exit> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    eprintln!("Listening on http://{}", args.addr);
+    runtime.block_on(sudoku::server::serve(args.addr.as_str()))?;
+    Ok(Exit::Success)
+}
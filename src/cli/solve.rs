@@ -0,0 +1,497 @@
+//! `sudoku solve`: print the solution of each puzzle.
+
+use crate::cli::{
+    exit::Exit,
+    input::{BoxShape, Source},
+    output::{csv_field, digits, grid, puzzle_grid, solution_count, step_text, Format, Highlight},
+    watch,
+};
+use serde_json::json;
+use std::{
+    fmt,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+use sudoku::{
+    error::ParseError,
+    grid::Grid,
+    limits::SolverLimits,
+    puzzle::SudokuPuzzle,
+    sukaku::Sukaku,
+    teaching::{self, Annotation},
+    trace::Trace,
+};
+
+/// The options that the solver limits cannot be used with.
+const LIMIT_CONFLICTS: [&str; 6] = ["explain", "teach", "trace", "watch", "box_shape", "sukaku"];
+
+#[derive(clap::Args, Default)]
+pub struct SolveArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Print each step the solver took before the solution, or include them
+    /// as "steps" in JSON output
+    #[arg(long, conflicts_with_all = ["watch", "count_solutions"])]
+    pub explain: bool,
+
+    /// Print each step with the numbers on the board that force it, such as
+    /// "box 7 already contains 2, 4, 8; row 9 contains 5, 9 ⇒ r9c2 = 7", or
+    /// include them as "annotations" in JSON output
+    #[arg(long, conflicts_with_all = ["explain", "watch", "count_solutions"])]
+    pub teach: bool,
+
+    /// Print the full trace of each solve as a line of JSON instead, with
+    /// the candidates of every square after each step
+    #[arg(long, conflicts_with_all = ["explain", "teach", "watch", "count_solutions"])]
+    pub trace: bool,
+
+    /// Show the solver working, one step at a time
+    #[arg(long)]
+    pub watch: bool,
+
+    /// With --watch, the pause between steps in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 200, requires = "watch")]
+    pub delay: u64,
+
+    /// Count solutions, up to CAP (2 by default), instead of printing them
+    #[arg(long, conflicts_with = "watch", value_name = "CAP", num_args = 0..=1, require_equals = true, default_missing_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+    pub count_solutions: Option<u64>,
+
+    /// Solve puzzles with boxes of R rows and C columns instead of 9x9
+    /// puzzles, such as 2x3 for 6x6 puzzles or 3x4 for 12x12 ones
+    #[arg(long = "box", value_name = "RxC", conflicts_with_all = ["explain", "teach", "trace", "watch"])]
+    pub box_shape: Option<BoxShape>,
+
+    /// Give up on a puzzle once the search has visited N points, the puzzle
+    /// and each guess made from it
+    #[arg(long, value_name = "N", conflicts_with_all = LIMIT_CONFLICTS)]
+    pub max_nodes: Option<u64>,
+
+    /// Give up on a puzzle once the search would make more than N guesses on
+    /// top of each other
+    #[arg(long, value_name = "N", conflicts_with_all = LIMIT_CONFLICTS)]
+    pub max_depth: Option<usize>,
+
+    /// Give up on a puzzle once the search has taken MS milliseconds
+    #[arg(long, value_name = "MS", conflicts_with_all = LIMIT_CONFLICTS)]
+    pub max_time: Option<u64>,
+
+    /// Solve sukaku puzzles, given as 729 candidates with nine for each
+    /// square, instead of placed numbers
+    #[arg(long, conflicts_with_all = ["explain", "teach", "trace", "watch", "box_shape"])]
+    pub sukaku: bool,
+}
+
+impl SolveArgs {
+    /// The limits given on the command line.
+    fn limits(&self) -> SolverLimits {
+        SolverLimits {
+            max_nodes: self.max_nodes,
+            max_guess_depth: self.max_depth,
+            max_time: self.max_time.map(Duration::from_millis),
+        }
+    }
+}
+
+pub fn run(args: &SolveArgs, format: Format, color: bool) -> io::Result<Exit> {
+    if let Some(shape) = args.box_shape {
+        return run_others(args, format, color, shape.squares(), |source| {
+            shape.grid(source)
+        });
+    }
+    if args.sukaku {
+        return run_others(args, format, color, 729, str::parse::<Sukaku>);
+    }
+
+    let mut out = io::stdout().lock();
+    let mut exit = Exit::Success;
+    if format == Format::Csv && !args.trace {
+        let column = match args.count_solutions {
+            Some(_) => "solutions",
+            None => "solution",
+        };
+        writeln!(out, "puzzle,{},error", column)?;
+    }
+
+    for (i, puzzle_source) in args.source.puzzles()?.enumerate() {
+        let puzzle_source = puzzle_source?;
+        if i > 0
+            && matches!(format, Format::Grid | Format::Spoken)
+            && args.count_solutions.is_none()
+            && !args.trace
+        {
+            writeln!(out, "\n")?;
+        }
+
+        let puzzle = match SudokuPuzzle::from_string(&puzzle_source) {
+            Ok(puzzle) => puzzle,
+            Err(e) => {
+                exit = exit.max(Exit::ParseError);
+                write_error(&mut out, format, "Invalid puzzle", &puzzle_source, &e)?;
+                continue;
+            }
+        };
+        if args.watch {
+            let delay = Duration::from_millis(args.delay);
+            exit = exit.max(watch::replay(&puzzle, delay, format, color, &mut out)?);
+            continue;
+        }
+
+        if args.trace {
+            let trace = Trace::of(&puzzle);
+            if trace.solution().is_none() {
+                exit = exit.max(Exit::Unsolvable);
+            }
+            writeln!(out, "{}", trace.to_json())?;
+            continue;
+        }
+
+        let limits = args.limits();
+        if let Some(cap) = args.count_solutions.map(|cap| cap as usize) {
+            let line = digits(&puzzle.to_vec());
+            match puzzle.count_solutions_within(cap, &limits) {
+                Ok(count) => exit = exit.max(write_count(&mut out, format, &line, count, cap)?),
+                Err(e) => {
+                    exit = exit.max(Exit::LimitExceeded);
+                    write_error(&mut out, format, "Gave up", &line, &e)?;
+                }
+            }
+            continue;
+        }
+
+        let start = Instant::now();
+        let (solution, steps) = if args.explain || args.teach {
+            puzzle.solve_with_steps()
+        } else {
+            match puzzle.solve_within(&limits) {
+                Ok(solution) => (solution, Vec::new()),
+                Err(e) => {
+                    exit = exit.max(Exit::LimitExceeded);
+                    let line = digits(&puzzle.to_vec());
+                    write_error(&mut out, format, "Gave up", &line, &e)?;
+                    continue;
+                }
+            }
+        };
+        if solution.is_none() {
+            exit = exit.max(Exit::Unsolvable);
+        }
+        let elapsed = start.elapsed();
+        let annotations = if args.teach {
+            teaching::annotate(&puzzle, &steps)
+        } else {
+            Vec::new()
+        };
+
+        match format {
+            Format::Grid | Format::Spoken => {
+                writeln!(out, "Input:\n{}\n\n", puzzle_grid(&puzzle, format, color))?;
+                if args.explain {
+                    writeln!(out, "Steps:")?;
+                    for step in &steps {
+                        writeln!(out, "{}", step_text(step, format))?;
+                    }
+                    writeln!(out, "\n")?;
+                }
+                if args.teach {
+                    writeln!(out, "Steps:")?;
+                    for annotation in &annotations {
+                        writeln!(out, "{}", annotation_text(annotation, format))?;
+                    }
+                    writeln!(out, "\n")?;
+                }
+
+                match solution {
+                    Some(solution) => {
+                        let solution = grid(
+                            |row, col| {
+                                let highlight = match puzzle.get(row, col) {
+                                    Some(_) => Highlight::Given,
+                                    None => Highlight::Filled,
+                                };
+                                Some((solution.get(row, col), highlight))
+                            },
+                            format,
+                            color,
+                        );
+                        writeln!(out, "Solution:\n{}", solution)?
+                    }
+                    None => writeln!(out, "No solution could be found")?,
+                }
+            }
+            Format::Line => {
+                // Without --explain or --teach there are no steps
+                if args.teach {
+                    for annotation in &annotations {
+                        writeln!(out, "{}", annotation)?;
+                    }
+                } else {
+                    for step in &steps {
+                        writeln!(out, "{}", step)?;
+                    }
+                }
+                match solution {
+                    Some(solution) => writeln!(out, "{}", digits(&solution.to_vec()))?,
+                    None => writeln!(out, "No solution could be found")?,
+                }
+            }
+            Format::Json => {
+                let solution = solution.map(|s| digits(&s.to_vec()));
+                let givens = puzzle.to_vec().iter().filter(|n| **n != 0).count();
+                let mut record = json!({
+                    "puzzle": digits(&puzzle.to_vec()),
+                    "solution": solution,
+                    "stats": { "givens": givens, "elapsed_us": elapsed.as_micros() as u64 },
+                });
+                if args.explain {
+                    let steps: Vec<_> = steps.iter().map(|step| step.to_string()).collect();
+                    record["steps"] = json!(steps);
+                }
+                if args.teach {
+                    let annotations: Vec<_> = annotations.iter().map(annotation_json).collect();
+                    record["annotations"] = json!(annotations);
+                }
+                writeln!(out, "{}", record)?;
+            }
+            Format::Csv => {
+                let solution = solution.map(|s| digits(&s.to_vec())).unwrap_or_default();
+                writeln!(out, "{},{},", digits(&puzzle.to_vec()), solution)?;
+            }
+        }
+    }
+
+    Ok(exit)
+}
+
+/// An annotated step as a line of text. The spoken format says "so" rather
+/// than "⇒", which screen readers read out as an arrow.
+fn annotation_text(annotation: &Annotation, format: Format) -> String {
+    let text = annotation.to_string();
+    match format {
+        Format::Spoken => text.replace(" ⇒ ", ", so "),
+        _ => text,
+    }
+}
+
+/// An annotated step as JSON: the step, the sentence, and the facts behind
+/// it with their squares numbered from 0, for a UI to highlight.
+fn annotation_json(annotation: &Annotation) -> serde_json::Value {
+    let evidence: Vec<_> = annotation
+        .evidence
+        .iter()
+        .map(|fact| {
+            json!({
+                "unit": fact.unit.to_string(),
+                "numbers": fact.numbers.to_vec(),
+                "squares": fact.squares,
+            })
+        })
+        .collect();
+    json!({
+        "step": annotation.step.to_string(),
+        "text": annotation.to_string(),
+        "evidence": evidence,
+    })
+}
+
+/// A puzzle other than a 9x9 one that `run_others` can solve.
+trait OtherPuzzle {
+    /// The puzzle on one line, in the form it was read from.
+    fn line(&self) -> String;
+
+    /// The puzzle as a grid to print before its solution, if it can be
+    /// shown as one.
+    fn input(&self) -> Option<String>;
+
+    fn count_solutions(&self, cap: usize) -> usize;
+
+    /// The solution, as a grid to print and on one line.
+    fn solution(&self, format: Format, color: bool) -> Option<(String, String)>;
+}
+
+impl OtherPuzzle for Grid {
+    fn line(&self) -> String {
+        self.to_line()
+    }
+
+    fn input(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+
+    fn count_solutions(&self, cap: usize) -> usize {
+        Grid::count_solutions(self, cap)
+    }
+
+    fn solution(&self, _: Format, _: bool) -> Option<(String, String)> {
+        let solution = self.solve()?;
+        Some((solution.to_string(), solution.to_line()))
+    }
+}
+
+impl OtherPuzzle for Sukaku {
+    fn line(&self) -> String {
+        self.to_string()
+    }
+
+    fn input(&self) -> Option<String> {
+        None
+    }
+
+    fn count_solutions(&self, cap: usize) -> usize {
+        Sukaku::count_solutions(self, cap)
+    }
+
+    /// Squares that had only one candidate are shown as givens.
+    fn solution(&self, format: Format, color: bool) -> Option<(String, String)> {
+        let solution = self.solve()?;
+        let grid = grid(
+            |row, col| {
+                let highlight = match self.candidates(row, col).size() {
+                    1 => Highlight::Given,
+                    _ => Highlight::Filled,
+                };
+                Some((solution.get(row, col), highlight))
+            },
+            format,
+            color,
+        );
+        Some((grid, digits(&solution.to_vec())))
+    }
+}
+
+/// Solve puzzles of the given number of squares, read by parse, printing
+/// them the same way as 9x9 puzzles.
+fn run_others<P, F>(
+    args: &SolveArgs,
+    format: Format,
+    color: bool,
+    squares: usize,
+    parse: F,
+) -> io::Result<Exit>
+where
+    P: OtherPuzzle,
+    F: Fn(&str) -> Result<P, ParseError>,
+{
+    let mut out = io::stdout().lock();
+    let mut exit = Exit::Success;
+    if format == Format::Csv {
+        let column = match args.count_solutions {
+            Some(_) => "solutions",
+            None => "solution",
+        };
+        writeln!(out, "puzzle,{},error", column)?;
+    }
+
+    for (i, puzzle_source) in args.source.puzzles_of(squares)?.enumerate() {
+        let puzzle_source = puzzle_source?;
+        if i > 0
+            && matches!(format, Format::Grid | Format::Spoken)
+            && args.count_solutions.is_none()
+        {
+            writeln!(out, "\n")?;
+        }
+
+        let puzzle = match parse(&puzzle_source) {
+            Ok(puzzle) => puzzle,
+            Err(e) => {
+                exit = exit.max(Exit::ParseError);
+                write_error(&mut out, format, "Invalid puzzle", &puzzle_source, &e)?;
+                continue;
+            }
+        };
+
+        if let Some(cap) = args.count_solutions.map(|cap| cap as usize) {
+            let count = puzzle.count_solutions(cap);
+            exit = exit.max(write_count(&mut out, format, &puzzle.line(), count, cap)?);
+            continue;
+        }
+
+        let solution = puzzle.solution(format, color);
+        if solution.is_none() {
+            exit = exit.max(Exit::Unsolvable);
+        }
+        match format {
+            Format::Grid | Format::Spoken => {
+                if let Some(input) = puzzle.input() {
+                    writeln!(out, "Input:\n{}\n\n", input)?;
+                }
+                match solution {
+                    Some((solution, _)) => writeln!(out, "Solution:\n{}", solution)?,
+                    None => writeln!(out, "No solution could be found")?,
+                }
+            }
+            Format::Line => match solution {
+                Some((_, line)) => writeln!(out, "{}", line)?,
+                None => writeln!(out, "No solution could be found")?,
+            },
+            Format::Json => writeln!(
+                out,
+                "{}",
+                json!({
+                    "puzzle": puzzle.line(),
+                    "solution": solution.map(|(_, line)| line),
+                })
+            )?,
+            Format::Csv => {
+                let solution = solution.map(|(_, line)| line).unwrap_or_default();
+                writeln!(out, "{},{},", puzzle.line(), solution)?;
+            }
+        }
+    }
+
+    Ok(exit)
+}
+
+/// Report a puzzle that could not be read or solved, with what as the
+/// start of the message in text formats.
+fn write_error(
+    out: &mut impl Write,
+    format: Format,
+    what: &str,
+    puzzle_source: &str,
+    e: &dyn fmt::Display,
+) -> io::Result<()> {
+    match format {
+        Format::Grid | Format::Line | Format::Spoken => writeln!(out, "{}: {}", what, e),
+        Format::Json => writeln!(
+            out,
+            "{}",
+            json!({ "puzzle": puzzle_source, "error": e.to_string() })
+        ),
+        Format::Csv => writeln!(
+            out,
+            "{},,{}",
+            csv_field(puzzle_source),
+            csv_field(&e.to_string())
+        ),
+    }
+}
+
+/// Report the number of solutions of a puzzle, counted up to cap, and
+/// return the exit code for it.
+fn write_count(
+    out: &mut impl Write,
+    format: Format,
+    puzzle: &str,
+    count: usize,
+    cap: usize,
+) -> io::Result<Exit> {
+    let label = solution_count(count, cap);
+    match format {
+        Format::Grid | Format::Spoken if count == 1 => writeln!(out, "{}: 1 solution", puzzle)?,
+        Format::Grid | Format::Spoken => writeln!(out, "{}: {} solutions", puzzle, label)?,
+        Format::Line => writeln!(out, "{}", label)?,
+        Format::Json => writeln!(
+            out,
+            "{}",
+            json!({ "puzzle": puzzle, "solutions": count, "capped": count >= cap })
+        )?,
+        Format::Csv => writeln!(out, "{},{},", puzzle, label)?,
+    }
+    Ok(match count {
+        0 => Exit::Unsolvable,
+        1 => Exit::Success,
+        _ => Exit::Multiple,
+    })
+}
@@ -0,0 +1,270 @@
+//! `sudoku train`: practise a solving technique on positions that need it.
+
+use crate::cli::{
+    bench,
+    exit::Exit,
+    output::{digits, grid, Format, Highlight, Layout},
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+use sudoku::{
+    deterministic,
+    generate::generate,
+    rating::Difficulty,
+    technique::{Deduction, Finding, Position, Technique},
+};
+
+/// How many puzzles to generate, looking for a position that needs the
+/// technique, before giving up.
+const ATTEMPTS: usize = 1000;
+
+#[derive(clap::Args)]
+pub struct TrainArgs {
+    /// The technique to practise: naked-single, hidden-single, naked-pair,
+    /// pointing-pair, or x-wing
+    #[arg(long, value_name = "NAME")]
+    pub technique: Technique,
+
+    /// A file of puzzles to take positions from, read the same way as for
+    /// solve. Puzzles are generated if no file is given.
+    pub file: Option<PathBuf>,
+
+    /// How many positions to practise on
+    #[arg(short = 'n', long, default_value_t = 1)]
+    pub count: usize,
+}
+
+/// An answer typed by the player.
+enum Answer {
+    /// r3c2=5
+    Place { row: usize, col: usize, n: u8 },
+    /// r3c2-5
+    Eliminate { row: usize, col: usize, n: u8 },
+}
+
+impl Answer {
+    /// Read an answer such as "r3c2=5" or "r3c2-5", with rows and columns
+    /// numbered from 1.
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_ascii_lowercase();
+        let digit = |c: char| c.to_digit(10).filter(|d| (1..=9).contains(d));
+        let chars: Vec<char> = text.chars().collect();
+        match chars[..] {
+            ['r', row, 'c', col, op, n] => {
+                let (row, col, n) = (
+                    digit(row)? as usize - 1,
+                    digit(col)? as usize - 1,
+                    digit(n)? as u8,
+                );
+                match op {
+                    '=' => Some(Answer::Place { row, col, n }),
+                    '-' => Some(Answer::Eliminate { row, col, n }),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn is_right(&self, deduction: &Deduction) -> bool {
+        match *self {
+            Answer::Place { row, col, n } => deduction.places(row, col, n),
+            Answer::Eliminate { row, col, n } => deduction.eliminates(row, col, n),
+        }
+    }
+}
+
+/// Show positions where the technique is the simplest way forward, and check
+/// the player's answer for each.
+pub fn run(args: &TrainArgs, seed: Option<u64>) -> io::Result<Exit> {
+    let positions = match &args.file {
+        Some(file) => {
+            let (puzzles, exit) = bench::load(Some(file))?;
+            let positions: Vec<Position> = puzzles
+                .iter()
+                .filter_map(|puzzle| Position::practice(puzzle, args.technique))
+                .take(args.count)
+                .collect();
+            if positions.is_empty() {
+                eprintln!("None of the puzzles need {}", args.technique);
+                return Ok(exit.max(Exit::Unsolvable));
+            }
+            positions
+        }
+        None => {
+            let mut rng = match seed {
+                Some(seed) => deterministic::rng(seed),
+                None => ChaCha8Rng::from_os_rng(),
+            };
+            let positions: Vec<Position> = (0..ATTEMPTS)
+                .map(|_| generate(&mut rng, difficulty(args.technique)))
+                .filter_map(|puzzle| Position::practice(&puzzle, args.technique))
+                .take(args.count)
+                .collect();
+            if positions.is_empty() {
+                eprintln!("Could not generate a puzzle that needs {}", args.technique);
+                return Ok(Exit::Unsolvable);
+            }
+            positions
+        }
+    };
+
+    let mut out = io::stdout().lock();
+    let mut answers = io::stdin().lock().lines();
+    let mut right = 0;
+    for (i, position) in positions.iter().enumerate() {
+        let finding = position
+            .next()
+            .expect("practice positions have a next step");
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "Use {} to find the next step:", args.technique)?;
+        writeln!(out, "{}", candidate_grid(position))?;
+        writeln!(out, "{}", digits(&position.puzzle().to_vec()))?;
+
+        let answer = loop {
+            write!(
+                out,
+                "Answer as r3c2=5 to place a number or r3c2-5 to rule one out: "
+            )?;
+            out.flush()?;
+            match answers.next().transpose()? {
+                Some(line) if line.trim().is_empty() => break None,
+                Some(line) => match Answer::parse(&line) {
+                    Some(answer) => break Some(answer),
+                    None => writeln!(out, "Could not read \"{}\"", line.trim())?,
+                },
+                None => break None,
+            }
+        };
+        match answer {
+            Some(answer) if answer.is_right(&finding.deduction) => {
+                right += 1;
+                writeln!(out, "Right! {}", finding.explain())?;
+            }
+            Some(_) => writeln!(out, "Not quite. {}", finding.explain())?,
+            None => writeln!(out, "{}", finding.explain())?,
+        }
+        writeln!(out, "{}", deduction_text(&finding))?;
+    }
+
+    writeln!(out, "\n{} of {} right", right, positions.len())?;
+    Ok(Exit::Success)
+}
+
+/// The difficulty of puzzles most likely to need technique.
+fn difficulty(technique: Technique) -> Difficulty {
+    match technique {
+        Technique::NakedSingle => Difficulty::Easy,
+        Technique::HiddenSingle => Difficulty::Medium,
+        _ => Difficulty::Hard,
+    }
+}
+
+/// Every answer that finding would accept.
+fn deduction_text(finding: &Finding) -> String {
+    let answers: Vec<String> = match &finding.deduction {
+        Deduction::Place { row, col, n } => vec![format!("r{}c{}={}", row + 1, col + 1, n)],
+        Deduction::Eliminate(eliminations) => eliminations
+            .iter()
+            .map(|(row, col, n)| format!("r{}c{}-{}", row + 1, col + 1, n))
+            .collect(),
+    };
+    format!("Accepted: {}", answers.join(", "))
+}
+
+/// The width of a grid of candidates drawn in full.
+const CANDIDATE_GRID_WIDTH: usize = 43;
+
+/// The position as a grid with each empty square's candidates in a 3x3
+/// block, where . is a candidate that has been ruled out. The lines are
+/// Unicode on a terminal with room for them; a narrower terminal gets the
+/// grid of numbers instead, followed by the candidates of each empty square.
+fn candidate_grid(position: &Position) -> String {
+    let layout = Layout::for_width(CANDIDATE_GRID_WIDTH);
+    if layout == Layout::Compact {
+        let numbers = |row, col| position.get(row, col).map(|n| (n, Highlight::Given));
+        let mut lines = vec![grid(numbers, Format::Grid, false)];
+        for row in 0..9 {
+            for col in (0..9).filter(|col| position.get(row, *col).is_none()) {
+                let candidates = digits(&position.candidates(row, col).to_vec());
+                lines.push(format!("r{}c{}: {}", row + 1, col + 1, candidates));
+            }
+        }
+        return lines.join("\n");
+    }
+
+    let (side, [top, middle, bottom, blank]) = match layout {
+        Layout::Unicode => (
+            "┃",
+            [
+                box_rule('━', ['┏', '┳', '┓']),
+                box_rule('━', ['┣', '╋', '┫']),
+                box_rule('━', ['┗', '┻', '┛']),
+                box_rule(' ', ['┃'; 3]),
+            ],
+        ),
+        _ => (
+            "|",
+            [
+                box_rule('-', ['+'; 3]),
+                box_rule('-', ['+'; 3]),
+                box_rule('-', ['+'; 3]),
+                box_rule(' ', ['|'; 3]),
+            ],
+        ),
+    };
+    let mut lines = vec![top];
+    for row in 0..9 {
+        for line in 0..3 {
+            let mut text = String::new();
+            for col in 0..9 {
+                if col % 3 == 0 {
+                    text.push_str(side);
+                }
+                text.push(' ');
+                match position.get(row, col) {
+                    Some(n) if line == 1 => text.push_str(&format!(" {} ", n)),
+                    Some(_) => text.push_str("   "),
+                    None => {
+                        let candidates = position.candidates(row, col);
+                        for n in line as u8 * 3 + 1..=line as u8 * 3 + 3 {
+                            text.push(if candidates.contains(n) {
+                                char::from(b'0' + n)
+                            } else {
+                                '.'
+                            });
+                        }
+                    }
+                }
+                if col % 3 == 2 {
+                    text.push(' ');
+                }
+            }
+            text.push_str(side);
+            lines.push(text);
+        }
+        lines.push(match row {
+            8 => bottom.clone(),
+            _ if row % 3 == 2 => middle.clone(),
+            _ => blank.clone(),
+        });
+    }
+    lines.join("\n")
+}
+
+/// A line across a grid of candidates drawn with line, with the joints
+/// given as [left end, between 3x3 boxes, right end].
+fn box_rule(line: char, [left, between, right]: [char; 3]) -> String {
+    let mut rule = String::from(left);
+    for bx in 0..3 {
+        rule.extend([line; 13]);
+        rule.push(if bx == 2 { right } else { between });
+    }
+    rule
+}
@@ -0,0 +1,104 @@
+//! `sudoku solve --watch`: replay the solver's steps in the terminal.
+
+use crate::cli::{
+    exit::Exit,
+    output::{grid, step_text, Format, Highlight},
+};
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+use sudoku::puzzle::SudokuPuzzle;
+
+/// Solve puzzle, redrawing it after every step with a pause of delay
+/// between steps. The spoken format announces each step instead, since a
+/// redrawn screen can't be read out. Returns whether a solution was found,
+/// as an exit code.
+pub fn replay<W: Write>(
+    puzzle: &SudokuPuzzle,
+    delay: Duration,
+    format: Format,
+    color: bool,
+    out: &mut W,
+) -> io::Result<Exit> {
+    let (solution, steps) = puzzle.solve_with_steps();
+    let mut nums: Vec<Option<u8>> = puzzle
+        .to_vec()
+        .iter()
+        .map(|n| Some(*n).filter(|n| *n != 0))
+        .collect();
+    // The squares filled so far, so that they can be emptied on backtracking
+    let mut filled = Vec::new();
+
+    for (i, step) in steps.iter().enumerate() {
+        let (row, col) = step.square();
+        let current = match step.number() {
+            Some(n) => {
+                nums[row * 9 + col] = Some(n);
+                filled.push(row * 9 + col);
+                Some(row * 9 + col)
+            }
+            None => {
+                while let Some(square) = filled.pop() {
+                    nums[square] = None;
+                    if square == row * 9 + col {
+                        break;
+                    }
+                }
+                None
+            }
+        };
+
+        if format == Format::Spoken {
+            writeln!(
+                out,
+                "Step {} of {}: {}",
+                i + 1,
+                steps.len(),
+                step_text(step, format)
+            )?;
+            out.flush()?;
+            thread::sleep(delay);
+            continue;
+        }
+
+        let board = grid(
+            |row, col| {
+                let highlight = if current == Some(row * 9 + col) {
+                    Highlight::Current
+                } else if puzzle.get(row, col).is_some() {
+                    Highlight::Given
+                } else {
+                    Highlight::Filled
+                };
+                nums[row * 9 + col].map(|n| (n, highlight))
+            },
+            format,
+            color,
+        );
+        // Clear the screen and draw from the top left
+        write!(out, "\x1b[2J\x1b[H")?;
+        writeln!(
+            out,
+            "{}\nStep {} of {}: {}",
+            board,
+            i + 1,
+            steps.len(),
+            step
+        )?;
+        out.flush()?;
+        thread::sleep(delay);
+    }
+
+    match solution {
+        Some(_) => {
+            writeln!(out, "Solved in {} steps", steps.len())?;
+            Ok(Exit::Success)
+        }
+        None => {
+            writeln!(out, "No solution could be found")?;
+            Ok(Exit::Unsolvable)
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! `sudoku worksheet`: lay out generated puzzles on printable PDF pages.
+
+use crate::cli::exit::Exit;
+use clap::ValueEnum;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+};
+use sudoku::{
+    deterministic,
+    generate::generate,
+    rating::Difficulty,
+    worksheet::{self, Paper, WorksheetOptions},
+};
+
+/// The sizes of paper a worksheet can be printed on.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum PaperArg {
+    Letter,
+    A4,
+}
+
+#[derive(clap::Args)]
+pub struct WorksheetArgs {
+    /// How many puzzles to generate
+    #[arg(short = 'n', long, default_value_t = 12)]
+    pub count: usize,
+
+    /// How hard the puzzles are: easy, medium, hard, or expert [default: medium]
+    #[arg(long, value_name = "LEVEL")]
+    pub difficulty: Option<Difficulty>,
+
+    /// How many puzzles to put on each page
+    #[arg(long, value_name = "N", default_value_t = 4, value_parser = clap::value_parser!(u64).range(1..=12))]
+    pub per_page: u64,
+
+    /// The size of the pages
+    #[arg(long, value_enum, default_value_t = PaperArg::Letter)]
+    pub paper: PaperArg,
+
+    /// A title for the top of each page of puzzles
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Leave out the answer key at the end
+    #[arg(long)]
+    pub no_answers: bool,
+
+    /// The PDF file to write, or stdout if not given
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Generate puzzles and write them as a PDF worksheet. The same seed always
+/// gives the same puzzles.
+pub fn run(args: &WorksheetArgs, seed: Option<u64>) -> io::Result<Exit> {
+    let mut rng = match seed {
+        Some(seed) => deterministic::rng(seed),
+        None => ChaCha8Rng::from_os_rng(),
+    };
+    let difficulty = args.difficulty.unwrap_or(Difficulty::Medium);
+    let puzzles: Vec<_> = (0..args.count)
+        .map(|_| generate(&mut rng, difficulty))
+        .collect();
+
+    let options = WorksheetOptions {
+        per_page: args.per_page as usize,
+        paper: match args.paper {
+            PaperArg::Letter => Paper::Letter,
+            PaperArg::A4 => Paper::A4,
+        },
+        title: args.title.clone(),
+        answers: !args.no_answers,
+    };
+    let pdf = worksheet::pdf(&puzzles, &options);
+    match &args.output {
+        Some(path) => File::create(path)?.write_all(&pdf)?,
+        None => io::stdout().lock().write_all(&pdf)?,
+    }
+    Ok(Exit::Success)
+}
@@ -0,0 +1,197 @@
+//! Solving many puzzles in a row without allocating for each one.
+//!
+//! A [`SolverContext`] owns the scratch space the solver works in: the grid
+//! of candidates being filled and the stack of guesses used to count
+//! solutions. Once it has solved its first puzzle, solving or counting the
+//! next one makes no heap allocations. A [`SolverPool`] hands contexts out to
+//! the threads of a server, so that each request borrows one rather than
+//! making its own.
+//!
+//! ```
+//! # use sudoku::{context::SolverContext, puzzle::SudokuPuzzle};
+//! let mut context = SolverContext::new();
+//! for text in &[
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//!     "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+//! ] {
+//!     let puzzle = SudokuPuzzle::from_string(text).unwrap();
+//!     assert_eq!(puzzle.solve(), context.solve(&puzzle));
+//!     assert_eq!(1, context.count_solutions(&puzzle, 2));
+//! }
+//! ```
+
+use crate::{
+    error::LimitExceeded,
+    limits::SolverLimits,
+    puzzle::SudokuPuzzle,
+    search::{Frame, Solutions},
+    solution::Solution,
+};
+use alloc::vec::Vec;
+use core::mem;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+/// The scratch space of the solver, reused from one puzzle to the next.
+pub struct SolverContext {
+    /// The puzzle being solved.
+    working: SudokuPuzzle,
+    /// The guesses of a search for solutions, always left empty. A guess
+    /// fills a square, so there are never more than 81.
+    stack: Vec<Frame>,
+}
+
+impl SolverContext {
+    /// A context with room for the deepest search.
+    pub fn new() -> Self {
+        SolverContext {
+            working: SudokuPuzzle::empty(),
+            stack: Vec::with_capacity(81),
+        }
+    }
+
+    /// Solve puzzle, as with [`SudokuPuzzle::solve`].
+    pub fn solve(&mut self, puzzle: &SudokuPuzzle) -> Option<Solution> {
+        if puzzle.search_into(&mut self.working) {
+            Some(Solution::new(&self.working))
+        } else {
+            None
+        }
+    }
+
+    /// Count the solutions of puzzle, stopping once limit have been found,
+    /// as with [`SudokuPuzzle::count_solutions`].
+    pub fn count_solutions(&mut self, puzzle: &SudokuPuzzle, limit: usize) -> usize {
+        let mut solutions = Solutions::with_stack(puzzle, mem::take(&mut self.stack));
+        let count = solutions.by_ref().take(limit).count();
+        self.stack = solutions.into_stack();
+        count
+    }
+
+    /// Solve puzzle, as with [`SudokuPuzzle::solve_within`].
+    pub fn solve_within(
+        &mut self,
+        puzzle: &SudokuPuzzle,
+        limits: &SolverLimits,
+    ) -> Result<Option<Solution>, LimitExceeded> {
+        self.working.clone_from(puzzle);
+        self.working.solve_in_place_within(limits)
+    }
+
+    /// Count the solutions of puzzle, as with
+    /// [`SudokuPuzzle::count_solutions_within`].
+    pub fn count_solutions_within(
+        &mut self,
+        puzzle: &SudokuPuzzle,
+        limit: usize,
+        limits: &SolverLimits,
+    ) -> Result<usize, LimitExceeded> {
+        self.working.clone_from(puzzle);
+        self.working.count_in_place_within(limit, limits)
+    }
+}
+
+impl Default for SolverContext {
+    fn default() -> Self {
+        SolverContext::new()
+    }
+}
+
+/// Contexts shared by the threads of a server. A context is made the first
+/// time no idle one is left, and goes back to the pool when the borrow of
+/// it is dropped, so a pool holds as many contexts as were ever in use at
+/// once.
+///
+/// ```
+/// # use sudoku::{context::SolverPool, puzzle::SudokuPuzzle};
+/// static POOL: SolverPool = SolverPool::new();
+///
+/// let puzzle = SudokuPuzzle::from_string(
+///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+/// )
+/// .unwrap();
+/// let solution = POOL.get().solve(&puzzle);
+/// assert_eq!(puzzle.solve(), solution);
+/// assert_eq!(1, POOL.idle());
+/// ```
+#[cfg(feature = "std")]
+pub struct SolverPool {
+    idle: Mutex<Vec<SolverContext>>,
+}
+
+#[cfg(feature = "std")]
+impl SolverPool {
+    /// An empty pool, which can be a `static`.
+    pub const fn new() -> Self {
+        SolverPool {
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Borrow an idle context, or a new one if there are none.
+    pub fn get(&self) -> PooledContext<'_> {
+        let context = self.lock().pop().unwrap_or_default();
+        PooledContext {
+            pool: self,
+            context: Some(context),
+        }
+    }
+
+    /// The number of contexts waiting to be borrowed.
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// The idle contexts. A thread that panicked while holding the lock
+    /// cannot have left them half changed, so a poisoned lock is used as is.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<SolverContext>> {
+        self.idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for SolverPool {
+    fn default() -> Self {
+        SolverPool::new()
+    }
+}
+
+/// A context borrowed from a [`SolverPool`], which goes back to the pool
+/// when dropped.
+#[cfg(feature = "std")]
+pub struct PooledContext<'a> {
+    pool: &'a SolverPool,
+    /// Only taken when the borrow is dropped.
+    context: Option<SolverContext>,
+}
+
+#[cfg(feature = "std")]
+impl core::ops::Deref for PooledContext<'_> {
+    type Target = SolverContext;
+
+    fn deref(&self) -> &SolverContext {
+        self.context
+            .as_ref()
+            .expect("the context is only taken on drop")
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::ops::DerefMut for PooledContext<'_> {
+    fn deref_mut(&mut self) -> &mut SolverContext {
+        self.context
+            .as_mut()
+            .expect("the context is only taken on drop")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for PooledContext<'_> {
+    fn drop(&mut self) {
+        if let Some(context) = self.context.take() {
+            self.pool.lock().push(context);
+        }
+    }
+}
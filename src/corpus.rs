@@ -0,0 +1,276 @@
+//! A collection of puzzles for tests, benchmarks, and examples, sorted by
+//! what makes each one interesting.
+//!
+//! ```
+//! # use sudoku::corpus::{self, Category};
+//! let inkala = corpus::get("inkala-2012").unwrap();
+//! assert_eq!(Category::Backtracking, inkala.category);
+//! assert_eq!(1, inkala.puzzle().unwrap().count_solutions(2));
+//!
+//! for entry in corpus::category(Category::MultipleSolutions) {
+//!     assert_eq!(2, entry.puzzle().unwrap().count_solutions(2));
+//! }
+//! ```
+
+use crate::{puzzle::SudokuPuzzle, rating::Difficulty, Error};
+
+/// What a puzzle in the corpus is an example of. Each puzzle is in only one
+/// category, so the 17-clue puzzles are not also sorted by technique.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Category {
+    /// Solved grids with a few squares emptied.
+    Trivial,
+    /// Puzzles that naked singles alone solve, which are rated easy.
+    NakedSingles,
+    /// Puzzles that also need hidden singles, which are rated medium.
+    HiddenSingles,
+    /// Puzzles where the solver has to guess but never takes a guess back,
+    /// which are rated hard.
+    Guessing,
+    /// Puzzles where the solver takes guesses back, which are rated expert.
+    Backtracking,
+    /// Puzzles with one solution and only 17 givens, the fewest possible.
+    SeventeenClue,
+    /// Text that is not a puzzle with a solution: it has conflicting givens,
+    /// has no solution, or cannot be read at all.
+    Invalid,
+    /// Puzzles with more than one solution.
+    MultipleSolutions,
+}
+
+impl Category {
+    /// Every category, in the order of `all`.
+    pub const ALL: [Category; 8] = [
+        Category::Trivial,
+        Category::NakedSingles,
+        Category::HiddenSingles,
+        Category::Guessing,
+        Category::Backtracking,
+        Category::SeventeenClue,
+        Category::Invalid,
+        Category::MultipleSolutions,
+    ];
+
+    /// The difficulty of the puzzles in this category, for the categories
+    /// named after a technique.
+    ///
+    /// ```
+    /// # use sudoku::{corpus::Category, rating::Difficulty};
+    /// assert_eq!(Some(Difficulty::Hard), Category::Guessing.difficulty());
+    /// assert_eq!(None, Category::SeventeenClue.difficulty());
+    /// ```
+    pub fn difficulty(self) -> Option<Difficulty> {
+        match self {
+            Category::NakedSingles => Some(Difficulty::Easy),
+            Category::HiddenSingles => Some(Difficulty::Medium),
+            Category::Guessing => Some(Difficulty::Hard),
+            Category::Backtracking => Some(Difficulty::Expert),
+            _ => None,
+        }
+    }
+}
+
+/// A puzzle in the corpus.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Entry {
+    /// A short name that identifies the puzzle, such as "seventeen-clue-1".
+    pub name: &'static str,
+    pub category: Category,
+    /// The puzzle as 81 digits, where 0 is an empty square. Some of the
+    /// invalid puzzles are not.
+    pub text: &'static str,
+}
+
+impl Entry {
+    /// Read the puzzle, which fails only for some invalid puzzles.
+    pub fn puzzle(&self) -> Result<SudokuPuzzle, Error> {
+        SudokuPuzzle::from_string(self.text)
+    }
+}
+
+const fn entry(name: &'static str, category: Category, text: &'static str) -> Entry {
+    Entry {
+        name,
+        category,
+        text,
+    }
+}
+
+const ENTRIES: [Entry; 30] = [
+    entry(
+        "one-empty",
+        Category::Trivial,
+        "083921657967345821251876493548132976729564138136798245372689514814253769695417382",
+    ),
+    entry(
+        "row-empty",
+        Category::Trivial,
+        "000000000967345821251876493548132976729564138136798245372689514814253769695417382",
+    ),
+    // Grid 01 of Project Euler problem 96
+    entry(
+        "euler-96-1",
+        Category::NakedSingles,
+        "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+    ),
+    entry(
+        "naked-singles-1",
+        Category::NakedSingles,
+        "081009700000700000050068030700000059010040000008053040260800000000002070000630900",
+    ),
+    entry(
+        "naked-singles-2",
+        Category::NakedSingles,
+        "009000000008021690400005700200050000000900015900708030500800020083000059000000060",
+    ),
+    entry(
+        "hidden-singles-1",
+        Category::HiddenSingles,
+        "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    ),
+    entry(
+        "hidden-singles-2",
+        Category::HiddenSingles,
+        "001009700000700000050060030700000059010040000008053040260800000000502070000600900",
+    ),
+    entry(
+        "hidden-singles-3",
+        Category::HiddenSingles,
+        "309007000000021600400005780200050000007900005010708000500000020080000059000000060",
+    ),
+    entry(
+        "guessing-1",
+        Category::Guessing,
+        "010000020070008000030400089420000000001009300000065002000090008300000076008003910",
+    ),
+    entry(
+        "guessing-2",
+        Category::Guessing,
+        "000000030008000100090000000000030001026700403040900500700104008001006007935070000",
+    ),
+    entry(
+        "guessing-3",
+        Category::Guessing,
+        "000700502620010400007009300200400070950030001000060850700003000000000004105000000",
+    ),
+    // Published by Arto Inkala in 2012 as the world's hardest Sudoku
+    entry(
+        "inkala-2012",
+        Category::Backtracking,
+        "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    ),
+    entry(
+        "backtracking-1",
+        Category::Backtracking,
+        "400000805030000000000700000020000060000080400000010000000603070500200000104000000",
+    ),
+    entry(
+        "backtracking-2",
+        Category::Backtracking,
+        "520006000000000701300000000000400800600000050000000000041800000000030020008700000",
+    ),
+    entry(
+        "backtracking-3",
+        Category::Backtracking,
+        "900200403000403070500900002700000000003000600010600009009070010002009080400800000",
+    ),
+    entry(
+        "seventeen-clue-1",
+        Category::SeventeenClue,
+        "000000010400000000020000000000050407008000300001090000300400200050100000000806000",
+    ),
+    entry(
+        "seventeen-clue-2",
+        Category::SeventeenClue,
+        "000000010400000000020000000000050604008000300001090000300400200050100000000807000",
+    ),
+    entry(
+        "seventeen-clue-3",
+        Category::SeventeenClue,
+        "000000012000035000000600070700000300000400800100000000000120000080000040050000600",
+    ),
+    entry(
+        "seventeen-clue-4",
+        Category::SeventeenClue,
+        "000000012003600000000007000410020000000500300700000600280000040000300500000000000",
+    ),
+    entry(
+        "seventeen-clue-5",
+        Category::SeventeenClue,
+        "000000012008030000000000040120500000000004700060000000507000300000620000000100000",
+    ),
+    entry(
+        "seventeen-clue-6",
+        Category::SeventeenClue,
+        "000000012040050000000009000070600400000100000000000050000087500601000300200000000",
+    ),
+    entry(
+        "seventeen-clue-7",
+        Category::SeventeenClue,
+        "000000013000030080070000000000206000030000900000010000600500204000400700100000000",
+    ),
+    // Two 1s in the first row
+    entry(
+        "repeated-digit",
+        Category::Invalid,
+        "110000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    ),
+    // No givens conflict, but the last square of the first row can only
+    // be 9, which is already in its column
+    entry(
+        "no-solution",
+        Category::Invalid,
+        "123456780000000009000000000000000000000000000000000000000000000000000000000000000",
+    ),
+    entry(
+        "too-short",
+        Category::Invalid,
+        "00302060090030500100180640000810290070000000800670820000260950080020300900501030",
+    ),
+    entry(
+        "not-a-digit",
+        Category::Invalid,
+        "003020600900305001001806400008102900700000008006708200002609500800203009005010x00",
+    ),
+    entry(
+        "empty",
+        Category::MultipleSolutions,
+        "000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    ),
+    // The solution of euler-96-1 with the corners of a rectangle emptied,
+    // which can be filled with 6 and 8 either way round
+    entry(
+        "deadly-pattern",
+        Category::MultipleSolutions,
+        "403921057907345021251876493548132976729564138136798245372689514814253769695417382",
+    ),
+    entry(
+        "one-given",
+        Category::MultipleSolutions,
+        "000000000000000000000000000000050000000000000000000000000000000000000000000000000",
+    ),
+    // seventeen-clue-1 without its last given
+    entry(
+        "sixteen-clue",
+        Category::MultipleSolutions,
+        "000000010400000000020000000000050407008000300001090000300400200050100000000800000",
+    ),
+];
+
+/// Every puzzle in the corpus, grouped by category in the order of
+/// `Category::ALL`.
+pub fn all() -> &'static [Entry] {
+    &ENTRIES
+}
+
+/// The puzzles in a category.
+pub fn category(category: Category) -> impl Iterator<Item = &'static Entry> {
+    ENTRIES
+        .iter()
+        .filter(move |entry| entry.category == category)
+}
+
+/// The puzzle with the given name, if there is one.
+pub fn get(name: &str) -> Option<&'static Entry> {
+    ENTRIES.iter().find(|entry| entry.name == name)
+}
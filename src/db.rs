@@ -0,0 +1,310 @@
+//! A collection of puzzles stored with their ratings, clue counts, and
+//! canonical forms, so that they can be looked up and filtered without
+//! rating them again. A database is saved as bytes with `to_bytes`, which
+//! can be written to a file and read back with `from_bytes`.
+//!
+//! ```
+//! # use sudoku::{db::{Database, Query}, puzzle::SudokuPuzzle, rating::Difficulty};
+//! let mut db = Database::new();
+//! for source in &[
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//!     "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+//! ] {
+//!     assert!(db.insert(SudokuPuzzle::from_string(source).unwrap(), "examples"));
+//! }
+//!
+//! let query = Query {
+//!     difficulty: Some(Difficulty::Easy),
+//!     clues: Some(30..=35),
+//!     ..Query::default()
+//! };
+//! let found: Vec<_> = db.query(&query).collect();
+//! assert_eq!(1, found.len());
+//! assert_eq!(32, found[0].clues);
+//!
+//! let db = Database::from_bytes(&db.to_bytes()).unwrap();
+//! assert_eq!(2, db.len());
+//! ```
+
+use crate::{
+    bytes::{push_squares, Reader},
+    error::ParseError,
+    puzzle::SudokuPuzzle,
+    rating::{Difficulty, Rating},
+};
+use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use core::{convert::TryFrom, ops::RangeInclusive};
+
+/// The first bytes of a saved database, followed by the version of the
+/// format.
+const MAGIC: &[u8; 4] = b"SUDB";
+const VERSION: u8 = 1;
+
+/// A puzzle in a database, with what is known about it.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub puzzle: SudokuPuzzle,
+    /// How hard the puzzle is, or `None` if it has no solution.
+    pub rating: Option<Rating>,
+    /// How many squares of the puzzle are filled.
+    pub clues: usize,
+    /// A hash of the puzzle's canonical form, which is the same for every
+    /// puzzle equivalent to it.
+    pub canonical_hash: u64,
+    /// Where the puzzle came from, such as the file it was read from.
+    pub source: Arc<str>,
+}
+
+/// Which records of a database to find. Each field that is set narrows the
+/// search, so the default query matches every record.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub difficulty: Option<Difficulty>,
+    pub clues: Option<RangeInclusive<usize>>,
+    pub source: Option<String>,
+}
+
+/// Puzzles indexed by their canonical forms and by their difficulties and
+/// clue counts. Equivalent puzzles are only stored once.
+#[derive(Debug, Clone, Default)]
+pub struct Database {
+    records: Vec<Record>,
+    /// The distinct sources of the records, in the order they were added.
+    sources: Vec<Arc<str>>,
+    /// The records with each canonical hash. Different canonical forms can
+    /// share a hash, so the forms themselves are compared too.
+    by_canonical: BTreeMap<u64, Vec<usize>>,
+    /// The records with each difficulty and clue count, where a difficulty
+    /// of `None` means the puzzle has no solution.
+    by_rating: BTreeMap<(Option<Difficulty>, usize), Vec<usize>>,
+}
+
+impl Database {
+    /// Create a database with no puzzles.
+    pub fn new() -> Self {
+        Database::default()
+    }
+
+    /// How many puzzles are in the database.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Indicates whether the database has no puzzles.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Every record, in the order they were added.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Rate puzzle and add it, noting that it came from source. Returns
+    /// `false`, leaving the database unchanged, if it already has an
+    /// equivalent puzzle.
+    pub fn insert(&mut self, puzzle: SudokuPuzzle, source: &str) -> bool {
+        let canonical = puzzle.canonical();
+        if self.find_canonical(&canonical).is_some() {
+            return false;
+        }
+
+        let rating = puzzle.rating();
+        let source = self.source(source);
+        self.add(Record {
+            clues: count_clues(&puzzle),
+            puzzle,
+            rating,
+            canonical_hash: hash(&canonical),
+            source,
+        });
+        true
+    }
+
+    /// The record of puzzle or of a puzzle equivalent to it, if there is
+    /// one.
+    ///
+    /// ```
+    /// # use sudoku::{db::Database, puzzle::SudokuPuzzle, transform::Transform};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let mut db = Database::new();
+    /// db.insert(puzzle.clone(), "examples");
+    ///
+    /// let equivalent = puzzle.transform(Transform::Transpose).unwrap();
+    /// assert!(!db.insert(equivalent.clone(), "examples"));
+    /// assert_eq!(puzzle.to_vec(), db.find(&equivalent).unwrap().puzzle.to_vec());
+    /// assert!(db.find(&SudokuPuzzle::empty()).is_none());
+    /// ```
+    pub fn find(&self, puzzle: &SudokuPuzzle) -> Option<&Record> {
+        self.find_canonical(&puzzle.canonical())
+    }
+
+    /// The records that match query, ordered by difficulty, then by clue
+    /// count, then by when they were added. Puzzles with no solution come
+    /// first, and only match queries without a difficulty.
+    pub fn query<'a>(&'a self, query: &'a Query) -> impl Iterator<Item = &'a Record> + 'a {
+        let clues = query.clues.clone().unwrap_or(0..=81);
+        self.by_rating
+            .iter()
+            .filter(move |((difficulty, count), _)| {
+                (query.difficulty.is_none() || *difficulty == query.difficulty)
+                    && clues.contains(count)
+            })
+            .flat_map(|(_, records)| records.iter())
+            .map(move |i| &self.records[*i])
+            .filter(move |record| {
+                query
+                    .source
+                    .as_ref()
+                    .is_none_or(|source| **source == *record.source)
+            })
+    }
+
+    /// The database as bytes, to be written to a file.
+    ///
+    /// Every number is little-endian. After the magic bytes "SUDB" and a
+    /// version byte come the number of sources as a u32, each source as its
+    /// length as a u16 and its UTF-8 bytes, and the number of records as a
+    /// u32. Each record is 58 bytes: its squares packed two to a byte, first
+    /// in the high half, its difficulty as a byte (0 for no solution, or 1-4
+    /// from easy to expert), its score as a u32, its canonical hash as a u64,
+    /// and the index of its source as a u32.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::from(&MAGIC[..]);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.sources.len() as u32).to_le_bytes());
+        for source in &self.sources {
+            bytes.extend_from_slice(&(source.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(source.as_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.records.len() as u32).to_le_bytes());
+        for record in &self.records {
+            push_squares(&mut bytes, &record.puzzle.to_vec());
+            let (difficulty, score) = match record.rating {
+                Some(rating) => (difficulty_byte(rating.difficulty), rating.score),
+                None => (0, 0),
+            };
+            bytes.push(difficulty);
+            bytes.extend_from_slice(&score.to_le_bytes());
+            bytes.extend_from_slice(&record.canonical_hash.to_le_bytes());
+            let source = self
+                .sources
+                .iter()
+                .position(|source| Arc::ptr_eq(source, &record.source))
+                .unwrap_or(0);
+            bytes.extend_from_slice(&(source as u32).to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Read a database saved with `to_bytes`, rebuilding its indexes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(bytes, |offset| ParseError::InvalidDatabase { offset });
+        if reader.take(4)? != MAGIC || reader.take(1)? != [VERSION] {
+            return Err(reader.invalid(0));
+        }
+
+        let mut db = Database::new();
+        for _ in 0..reader.u32()? {
+            let start = reader.offset;
+            let len = reader.u16()?;
+            let source = core::str::from_utf8(reader.take(len as usize)?)
+                .map_err(|_| reader.invalid(start))?;
+            db.sources.push(Arc::from(source));
+        }
+
+        for _ in 0..reader.u32()? {
+            let invalid = reader.invalid(reader.offset);
+            let puzzle = SudokuPuzzle::try_from(&reader.squares()?[..]).map_err(|_| invalid)?;
+            let difficulty = match reader.byte()? {
+                0 => None,
+                n => Some(*Difficulty::ALL.get(n as usize - 1).ok_or(invalid)?),
+            };
+            let score = reader.u32()?;
+            let canonical_hash = reader.u64()?;
+            let source = db.sources.get(reader.u32()? as usize).ok_or(invalid)?;
+
+            db.add(Record {
+                rating: difficulty.map(|difficulty| Rating { difficulty, score }),
+                clues: count_clues(&puzzle),
+                puzzle,
+                canonical_hash,
+                source: source.clone(),
+            });
+        }
+
+        reader.finish()?;
+        Ok(db)
+    }
+
+    /// Add record and index it.
+    fn add(&mut self, record: Record) {
+        let i = self.records.len();
+        self.by_canonical
+            .entry(record.canonical_hash)
+            .or_default()
+            .push(i);
+        let difficulty = record.rating.map(|rating| rating.difficulty);
+        self.by_rating
+            .entry((difficulty, record.clues))
+            .or_default()
+            .push(i);
+        self.records.push(record);
+    }
+
+    /// The record of a puzzle whose canonical form is canonical.
+    fn find_canonical(&self, canonical: &SudokuPuzzle) -> Option<&Record> {
+        let nums = canonical.to_vec();
+        self.by_canonical
+            .get(&hash(canonical))?
+            .iter()
+            .map(|i| &self.records[*i])
+            .find(|record| record.puzzle.canonical().to_vec() == nums)
+    }
+
+    /// The shared copy of source, added to the sources if it is new. Sources
+    /// are cut short to fit their length in a u16.
+    fn source(&mut self, source: &str) -> Arc<str> {
+        let mut len = source.len().min(u16::MAX as usize);
+        while !source.is_char_boundary(len) {
+            len -= 1;
+        }
+        let source = &source[..len];
+        match self.sources.iter().find(|known| ***known == *source) {
+            Some(known) => known.clone(),
+            None => {
+                let source: Arc<str> = Arc::from(source);
+                self.sources.push(source.clone());
+                source
+            }
+        }
+    }
+}
+
+/// The byte that stands for difficulty in a saved database.
+fn difficulty_byte(difficulty: Difficulty) -> u8 {
+    Difficulty::ALL
+        .iter()
+        .position(|d| *d == difficulty)
+        .unwrap() as u8
+        + 1
+}
+
+fn count_clues(puzzle: &SudokuPuzzle) -> usize {
+    puzzle.to_vec().iter().filter(|n| **n != 0).count()
+}
+
+/// An FNV-1a hash of a puzzle's squares, which unlike the standard
+/// library's hashers is the same on every platform and in every release.
+fn hash(puzzle: &SudokuPuzzle) -> u64 {
+    puzzle
+        .to_vec()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, n| {
+            (hash ^ u64::from(*n)).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+}
@@ -0,0 +1,59 @@
+//! Deterministic mode: everything that depends on a seed, seeded through
+//! this module, comes out the same on every platform and every run.
+//!
+//! Given the same seed and the same version of this crate:
+//!
+//! - [`generate`] and [`random_solution`] produce the same puzzles and
+//!   grids, on 32- and 64-bit targets, with or without `std`, `simd`, or
+//!   WebAssembly. [`rng`] is ChaCha8, whose output is specified to the bit,
+//!   and the random choices made with it only sample integer ranges that
+//!   rand draws the same way regardless of the width of `usize`.
+//! - Solving a puzzle tries squares and guesses in the same order, so
+//!   [`SudokuPuzzle::solve_with_steps`](crate::puzzle::SudokuPuzzle::solve_with_steps),
+//!   the hints, traces, and ratings built on it are the same step for step.
+//! - Nothing in the library iterates over a `HashMap` or `HashSet`, whose
+//!   order changes from run to run; the collections it keeps are ordered
+//!   (`Vec` and `BTreeMap`), and the hashes it stores, such as the canonical
+//!   hashes of `db::Database`, are FNV-1a over the digits.
+//! - The `batch` functions return their results in the order of the puzzles
+//!   given, whether or not the `parallel` feature spreads them across
+//!   threads, and each result depends only on its own puzzle.
+//!
+//! A new version of the crate may change what a seed produces, for example
+//! when the generator or rating changes, so store the puzzle itself where it
+//! must last. Seeds are enough for daily puzzles and puzzle IDs shared
+//! between users of the same version.
+//!
+//! ```
+//! # use sudoku::{deterministic, rating::Difficulty};
+//! let puzzle = deterministic::generate(7, Difficulty::Easy);
+//! assert_eq!(puzzle.to_vec(), deterministic::generate(7, Difficulty::Easy).to_vec());
+//! assert_eq!(
+//!     deterministic::random_solution(7),
+//!     deterministic::random_solution(7)
+//! );
+//! ```
+
+use crate::{generate as generator, puzzle::SudokuPuzzle, rating::Difficulty, solution::Solution};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// The random number generator used for seeded generation.
+pub type SeededRng = ChaCha8Rng;
+
+/// The generator for seed, which produces the same numbers everywhere.
+pub fn rng(seed: u64) -> SeededRng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// The puzzle that seed generates at difficulty, as with
+/// [`generate::generate`](crate::generate::generate).
+pub fn generate(seed: u64, difficulty: Difficulty) -> SudokuPuzzle {
+    generator::generate(&mut rng(seed), difficulty)
+}
+
+/// The completely filled grid that seed generates, as with
+/// [`generate::random_solution`](crate::generate::random_solution).
+pub fn random_solution(seed: u64) -> Solution {
+    generator::random_solution(&mut rng(seed))
+}
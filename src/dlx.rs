@@ -0,0 +1,204 @@
+//! Knuth's Algorithm X with dancing links, solving a puzzle as an exact
+//! cover problem: choose one (square, number) row for each of the 324
+//! constraints that every square is filled and every row, column, and box
+//! holds every number once.
+
+use crate::nine_set::NineSet;
+use alloc::vec::Vec;
+
+/// The constraints, each a column of the cover matrix.
+const COLUMNS: usize = 324;
+
+/// The cover matrix as circular, doubly linked lists of nodes. Node 0 is the
+/// root, nodes 1 to 324 are the column headers, and the rest are the 1s of
+/// the matrix, four to a row.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The header of each node's column.
+    column: Vec<usize>,
+    /// The (square, number) of each node's row.
+    choice: Vec<(usize, u8)>,
+    /// How many nodes are in each column, indexed by header.
+    size: Vec<usize>,
+}
+
+impl Links {
+    /// A matrix with a row for each candidate of each square.
+    fn new(candidates: &[NineSet; 81]) -> Self {
+        let headers = COLUMNS + 1;
+        let mut links = Links {
+            left: (0..headers).map(|i| (i + headers - 1) % headers).collect(),
+            right: (0..headers).map(|i| (i + 1) % headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            choice: alloc::vec![(0, 0); headers],
+            size: alloc::vec![0; headers],
+        };
+
+        for (square, candidates) in candidates.iter().enumerate() {
+            for n in candidates.iter() {
+                links.add_row(square, n);
+            }
+        }
+        links
+    }
+
+    /// Add the row for filling square with n.
+    fn add_row(&mut self, square: usize, n: u8) {
+        let (row, col) = (square / 9, square % 9);
+        let digit = n as usize - 1;
+        let columns = [
+            square,
+            81 + row * 9 + digit,
+            162 + col * 9 + digit,
+            243 + (row / 3 * 3 + col / 3) * 9 + digit,
+        ];
+
+        let first = self.left.len();
+        for (i, column) in columns.iter().enumerate() {
+            let header = column + 1;
+            let node = first + i;
+            self.left.push(if i == 0 { first + 3 } else { node - 1 });
+            self.right.push(if i == 3 { first } else { node + 1 });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let above = self.up[header];
+            self.down[above] = node;
+            self.up[header] = node;
+            self.column.push(header);
+            self.choice.push((square, n));
+            self.size[header] += 1;
+        }
+    }
+
+    /// Remove a column, and every row with a node in it, from the matrix.
+    fn cover(&mut self, header: usize) {
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+        let mut row = self.down[header];
+        while row != header {
+            let mut node = self.right[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    /// Put back a column removed by `cover`, in the reverse order.
+    fn uncover(&mut self, header: usize) {
+        let mut row = self.up[header];
+        while row != header {
+            let mut node = self.left[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = node;
+                self.up[down] = node;
+                self.size[self.column[node]] += 1;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+    }
+
+    /// Choose rows until every column is covered, always covering the
+    /// column with the fewest rows next, and count each complete cover.
+    /// Returns `true` once limit covers have been found, with the rows of
+    /// the last one chosen in nums.
+    fn search(
+        &mut self,
+        nums: &mut [u8; 81],
+        found: &mut usize,
+        limit: usize,
+        guesses: &mut u64,
+    ) -> bool {
+        if self.right[0] == 0 {
+            *found += 1;
+            return *found >= limit;
+        }
+
+        let mut header = self.right[0];
+        let mut best = header;
+        while header != 0 {
+            if self.size[header] < self.size[best] {
+                best = header;
+            }
+            header = self.right[header];
+        }
+        if self.size[best] == 0 {
+            return false;
+        }
+
+        let choices = self.size[best];
+        self.cover(best);
+        let mut row = self.down[best];
+        while row != best {
+            if choices > 1 {
+                *guesses += 1;
+            }
+            let mut node = self.right[row];
+            while node != row {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+            if self.search(nums, found, limit, guesses) {
+                let (square, n) = self.choice[row];
+                nums[square] = n;
+                return true;
+            }
+            let mut node = self.left[row];
+            while node != row {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+            row = self.down[row];
+        }
+        self.uncover(best);
+        false
+    }
+}
+
+/// Solve a grid, returning the filled grid if there is a solution and how
+/// many guesses were made at constraints with more than one choice.
+pub(crate) fn solve(nums: &[u8; 81]) -> (Option<[u8; 81]>, u64) {
+    let mut candidates = [NineSet::full(); 81];
+    for (square, n) in nums.iter().enumerate().filter(|(_, n)| **n != 0) {
+        candidates[square] = NineSet::from(alloc::vec![*n]);
+    }
+    solve_candidates(&candidates)
+}
+
+/// Solve a grid whose squares can only be filled with their candidates,
+/// like `solve`.
+pub(crate) fn solve_candidates(candidates: &[NineSet; 81]) -> (Option<[u8; 81]>, u64) {
+    let mut links = Links::new(candidates);
+    let mut solved = [0; 81];
+    let mut guesses = 0;
+    if links.search(&mut solved, &mut 0, 1, &mut guesses) {
+        (Some(solved), guesses)
+    } else {
+        (None, guesses)
+    }
+}
+
+/// Count the ways to fill a grid whose squares can only be filled with
+/// their candidates, stopping once limit have been found.
+pub(crate) fn count_candidates(candidates: &[NineSet; 81], limit: usize) -> usize {
+    let mut found = 0;
+    if limit > 0 {
+        Links::new(candidates).search(&mut [0; 81], &mut found, limit, &mut 0);
+    }
+    found
+}
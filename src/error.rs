@@ -0,0 +1,248 @@
+use crate::units::Unit;
+#[cfg(feature = "std")]
+use core::time::Duration;
+use thiserror::Error;
+
+/// The errors that can be produced by this crate.
+///
+/// Functions that can fail in more than one way, like reading a puzzle and
+/// placing its clues, return an `Error`. Those that can only fail in one way
+/// return the narrower error instead, so that matching on it covers exactly
+/// the failures that can happen:
+///
+/// - the searches within `limits::SolverLimits`, such as
+///   `SudokuPuzzle::solve_within`, only give up on a limit, as a
+///   [`LimitExceeded`];
+/// - the parsers of `grid::Grid`, `sukaku::Sukaku`, `db::Database`,
+///   `cache::SolveCache`, and `replay::Recording`, and the `FromStr` impls of
+///   `rating::Difficulty`, `generate::Symmetry`, `strategy::Strategy`, and
+///   `technique::Technique`, only read text or bytes, and fail with a
+///   [`ParseError`];
+/// - `verify::verify_solution` only rejects the proposal, with a
+///   [`VerificationError`];
+/// - `generate::generate_within` only runs out of attempts, with a
+///   [`GenerationError`].
+///
+/// Each of them converts into an `Error` with `?`.
+///
+/// ```
+/// # use sudoku::{limits::SolverLimits, puzzle::SudokuPuzzle, Error};
+/// fn solve(source: &str) -> Result<bool, Error> {
+///     let puzzle = SudokuPuzzle::from_string(source)?;
+///     let limits = SolverLimits { max_nodes: Some(10), ..SolverLimits::default() };
+///     Ok(puzzle.solve_within(&limits)?.is_some())
+/// }
+/// assert!(matches!(solve("123"), Err(Error::Parse(_))));
+/// assert!(matches!(solve(&"0".repeat(81)), Err(Error::Limit(_))));
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum Error {
+    /// A puzzle could not be read from its text or bytes.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// A number or pencil mark could not be placed.
+    #[error(transparent)]
+    Placement(#[from] PlacementError),
+    /// A transformation of a puzzle was not valid.
+    #[error(transparent)]
+    Transform(#[from] TransformError),
+    /// A search gave up on one of its limits.
+    #[error(transparent)]
+    Limit(#[from] LimitExceeded),
+    /// A proposed solution was rejected.
+    #[error(transparent)]
+    Verification(#[from] VerificationError),
+    /// No puzzle could be generated.
+    #[error(transparent)]
+    Generation(#[from] GenerationError),
+}
+
+/// The reasons that a puzzle, search checkpoint, saved game, or difficulty
+/// can fail to be read from text or bytes.
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum ParseError {
+    /// The source did not contain exactly 81 squares.
+    #[error("expected 81 squares but found {len}")]
+    WrongLength { len: usize },
+    /// A square in a string was not a digit.
+    #[error("square {index} is '{c}', which is not a digit")]
+    InvalidCharacter { index: usize, c: char },
+    /// A `grid::Grid` did not have as many squares as its size needs.
+    #[error("expected {expected} squares but found {len}")]
+    WrongGridLength { expected: usize, len: usize },
+    /// The boxes of a `grid::Grid` would have no squares, or more than 32.
+    #[error("boxes of {box_rows}x{box_cols} would not have from 1 to 32 squares")]
+    InvalidBoxShape { box_rows: usize, box_cols: usize },
+    /// A square of a `grid::Grid` was not one of its symbols.
+    #[error("square {index} is '{c}', which is not a symbol of the grid")]
+    InvalidSymbol { index: usize, c: char },
+    /// A `sukaku::Sukaku` did not have 729 candidates or 81 candidate lists.
+    #[error("expected 729 candidates or 81 candidate lists but found {len}")]
+    WrongSukakuLength { len: usize },
+    /// A candidate of a `sukaku::Sukaku` was not a digit or '.'.
+    #[error("candidate {index} is '{c}', which is not a digit")]
+    InvalidCandidate { index: usize, c: char },
+    /// A square in a byte sequence was not in the range [0,9].
+    #[error("square {index} is {n}, which is not in the range [0,9]")]
+    InvalidNumber { index: usize, n: u8 },
+    /// A line of a search checkpoint was malformed or does not describe a
+    /// point in the search of its puzzle. Lines are numbered from 1.
+    #[error("line {line} of the checkpoint is not valid")]
+    InvalidCheckpoint { line: usize },
+    /// A line of a saved game was malformed or does not follow from the
+    /// lines before it. Lines are numbered from 1.
+    #[error("line {line} of the saved game is not valid")]
+    InvalidSavedGame { line: usize },
+    /// A line of a `replay::Recording` was malformed, went back in time, or
+    /// records an action that could not have been made. Lines are numbered
+    /// from 1.
+    #[error("line {line} of the recording is not valid")]
+    InvalidReplay { line: usize },
+    /// The bytes of a saved `db::Database` were malformed, starting with the
+    /// record or source at offset.
+    #[error("byte {offset} of the database is not valid")]
+    InvalidDatabase { offset: usize },
+    /// The bytes of a saved `cache::SolveCache` were malformed, starting with
+    /// the entry at offset.
+    #[error("byte {offset} of the cache is not valid")]
+    InvalidCache { offset: usize },
+    /// A difficulty was not one of the names of `rating::Difficulty`.
+    #[error("expected a difficulty of easy, medium, hard, or expert")]
+    InvalidDifficulty,
+    /// A strategy was not one of the names of `strategy::Strategy`.
+    #[error(
+        "expected a strategy of deduction, fewest-candidates, naked-singles, backtracking, or dlx"
+    )]
+    InvalidStrategy,
+    /// A symmetry was not one of the names of `generate::Symmetry`.
+    #[error("expected a symmetry of none, rotational, mirror, or diagonal")]
+    InvalidSymmetry,
+    /// A technique was not one of the names of `technique::Technique`.
+    #[error(
+        "expected a technique of naked-single, hidden-single, naked-pair, pointing-pair, or x-wing"
+    )]
+    InvalidTechnique,
+}
+
+/// The reasons that a number, clue, or pencil mark cannot be placed.
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum PlacementError {
+    /// The square is outside of the 9x9 grid.
+    #[error("({row}, {col}) is outside of the puzzle")]
+    OutOfBounds { row: usize, col: usize },
+    /// The number is not in the range [1,9].
+    #[error("cannot place {n} at ({row}, {col})")]
+    InvalidNumber { row: usize, col: usize, n: u8 },
+    /// The square is one of the puzzle's givens, which cannot be changed.
+    #[error("({row}, {col}) is a given")]
+    Given { row: usize, col: usize },
+    /// Two different clues were given for the same square.
+    #[error("({row}, {col}) was given more than one clue")]
+    Overlapping { row: usize, col: usize },
+    /// The clues violate the rules of Sudoku.
+    #[error("the clues violate the rules of Sudoku")]
+    Inconsistent,
+}
+
+/// The limit of a `limits::SolverLimits` that a search gave up on.
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum LimitExceeded {
+    /// The search would have visited more nodes than max, its `max_nodes`.
+    #[error("the search would visit more than {max} nodes")]
+    Nodes { max: u64 },
+    /// The search would have stacked more guesses than max, its
+    /// `max_guess_depth`.
+    #[error("the search would guess more than {max} deep")]
+    GuessDepth { max: usize },
+    /// The search ran for longer than max, its `max_time`.
+    #[error("the search would take longer than {max:?}")]
+    #[cfg(feature = "std")]
+    Time { max: Duration },
+}
+
+/// The reasons that `generate::generate_within` gives up without a puzzle.
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum GenerationError {
+    /// Every grid dug gave a puzzle of the wrong difficulty.
+    #[error("no puzzle of the difficulty was found in {attempts} attempts")]
+    Exhausted { attempts: usize },
+}
+
+/// The reasons that a `transform::Transform` cannot be applied.
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum TransformError {
+    /// A band or stack was not in the range [0,2], or a row or column was
+    /// not in the range [0,8].
+    #[error("{index} is out of range")]
+    OutOfRange { index: usize },
+    /// Two rows to swap were in different bands, or two columns in
+    /// different stacks, which would break the boxes.
+    #[error("{a} and {b} are not in the same band or stack")]
+    DifferentBoxes { a: usize, b: usize },
+    /// A relabeling did not use each of 1-9 exactly once.
+    #[error("the labels are not a permutation of 1-9")]
+    NotPermutation,
+}
+
+/// Check that (row, col) is inside the 9x9 grid.
+pub(crate) fn check_square(row: usize, col: usize) -> Result<(), PlacementError> {
+    if row < 9 && col < 9 {
+        Ok(())
+    } else {
+        Err(PlacementError::OutOfBounds { row, col })
+    }
+}
+
+/// Check that (row, col) is inside the 9x9 grid and that n can be placed there.
+pub(crate) fn check_number(row: usize, col: usize, n: u8) -> Result<(), PlacementError> {
+    check_square(row, col)?;
+    if (1..=9).contains(&n) {
+        Ok(())
+    } else {
+        Err(PlacementError::InvalidNumber { row, col, n })
+    }
+}
+
+/// The reasons that `verify::verify_solution` rejects a proposed solution.
+/// Unlike a [`Violation`], these are about the proposal, not this crate.
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum VerificationError {
+    /// The proposal did not have 81 squares.
+    #[error("expected 81 squares but found {len}")]
+    WrongLength { len: usize },
+    /// A square of the proposal was not in the range [0,9].
+    #[error("({row}, {col}) is {n}, which is not in the range [0,9]")]
+    InvalidNumber { row: usize, col: usize, n: u8 },
+    /// (row, col) was left empty.
+    #[error("({row}, {col}) is empty")]
+    Incomplete { row: usize, col: usize },
+    /// n is in unit more than once.
+    #[error("{n} is in {unit} more than once")]
+    Repeated { unit: Unit, n: u8 },
+    /// The given at (row, col) was replaced with another number.
+    #[error("({row}, {col}) is {found}, but the puzzle gives {given}")]
+    ChangedGiven {
+        row: usize,
+        col: usize,
+        given: u8,
+        found: u8,
+    },
+}
+
+/// The invariants that the checks in `verify` can find broken. Any of these
+/// means there is a bug in this crate, not in its input.
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum Violation {
+    /// A solution has a different number than the puzzle at (row, col).
+    #[error("the solution does not keep the {n} at ({row}, {col})")]
+    Changed { row: usize, col: usize, n: u8 },
+    /// A row, column, or box of a solution does not hold each of 1-9 once.
+    #[error("{unit} of the solution does not hold each of 1-9 once")]
+    NotPermutation { unit: Unit },
+    /// The solver and the solution counter disagree about a puzzle.
+    #[error("the solver found {found} solutions but {counted} were counted")]
+    Miscounted { found: usize, counted: usize },
+    /// Two equivalent puzzles have different canonical forms.
+    #[error("equivalent puzzles have different canonical forms")]
+    NotCanonical,
+}
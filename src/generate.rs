@@ -0,0 +1,289 @@
+//! Generating new puzzles at random.
+//!
+//! The same random number generator, seeded the same way, always produces
+//! the same puzzle, so a seed is enough to share a puzzle.
+//!
+//! ```
+//! # use rand::SeedableRng;
+//! # use rand_chacha::ChaCha8Rng;
+//! # use sudoku::{generate::generate, rating::Difficulty};
+//! let puzzle = generate(&mut ChaCha8Rng::seed_from_u64(7), Difficulty::Easy);
+//! let again = generate(&mut ChaCha8Rng::seed_from_u64(7), Difficulty::Easy);
+//! assert_eq!(puzzle.to_vec(), again.to_vec());
+//! assert_eq!(Some(Difficulty::Easy), puzzle.difficulty());
+//! assert_eq!(1, puzzle.count_solutions(2));
+//! ```
+
+use crate::{
+    error::{GenerationError, ParseError},
+    nine_by_nine::NineByNine,
+    puzzle::SudokuPuzzle,
+    rating::Difficulty,
+    solution::Solution,
+    technique::{Position, Technique},
+};
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
+use rand::{seq::SliceRandom, Rng};
+
+/// How the givens of a generated puzzle are arranged. With a symmetry, each
+/// given has a partner square that is also a given.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Symmetry {
+    /// Givens are placed anywhere.
+    None,
+    /// Turning the puzzle halfway around leaves the givens where they were.
+    Rotational,
+    /// Flipping the puzzle from left to right leaves the givens where they
+    /// were.
+    Mirror,
+    /// Flipping the puzzle over its diagonal from the top left to the bottom
+    /// right leaves the givens where they were.
+    Diagonal,
+}
+
+impl Symmetry {
+    /// Every symmetry, from no symmetry at all.
+    pub const ALL: [Symmetry; 4] = [
+        Symmetry::None,
+        Symmetry::Rotational,
+        Symmetry::Mirror,
+        Symmetry::Diagonal,
+    ];
+
+    /// The name of this symmetry in lowercase, such as "rotational".
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::None => "none",
+            Symmetry::Rotational => "rotational",
+            Symmetry::Mirror => "mirror",
+            Symmetry::Diagonal => "diagonal",
+        }
+    }
+
+    /// The square that (row, col) is paired with, which may be itself.
+    ///
+    /// ```
+    /// # use sudoku::generate::Symmetry;
+    /// assert_eq!((8, 7), Symmetry::Rotational.partner(0, 1));
+    /// assert_eq!((0, 7), Symmetry::Mirror.partner(0, 1));
+    /// assert_eq!((1, 0), Symmetry::Diagonal.partner(0, 1));
+    /// assert_eq!((4, 4), Symmetry::Rotational.partner(4, 4));
+    /// ```
+    pub fn partner(self, row: usize, col: usize) -> (usize, usize) {
+        match self {
+            Symmetry::None => (row, col),
+            Symmetry::Rotational => (8 - row, 8 - col),
+            Symmetry::Mirror => (row, 8 - col),
+            Symmetry::Diagonal => (col, row),
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = ParseError;
+
+    /// Read a symmetry from its name, ignoring case.
+    ///
+    /// ```
+    /// # use sudoku::generate::Symmetry;
+    /// assert_eq!(Ok(Symmetry::Rotational), "Rotational".parse());
+    /// assert!("spiral".parse::<Symmetry>().is_err());
+    /// ```
+    fn from_str(name: &str) -> Result<Self, ParseError> {
+        Symmetry::ALL
+            .iter()
+            .find(|symmetry| name.eq_ignore_ascii_case(symmetry.name()))
+            .copied()
+            .ok_or(ParseError::InvalidSymmetry)
+    }
+}
+
+/// A random, completely filled grid.
+pub fn random_solution<R: Rng + ?Sized>(rng: &mut R) -> Solution {
+    fill_randomly(SudokuPuzzle::empty(), rng).expect("an empty puzzle has solutions")
+}
+
+/// Fill puzzle by deduction, guessing in a random order when deduction is
+/// not enough.
+fn fill_randomly<R: Rng + ?Sized>(mut puzzle: SudokuPuzzle, rng: &mut R) -> Option<Solution> {
+    if !puzzle.deduce() {
+        return None;
+    }
+    if puzzle.count_unfilled() == 0 {
+        return Some(Solution::new(&puzzle));
+    }
+
+    let (row, col) = puzzle.guess_square();
+    let mut guesses = puzzle.candidates(row, col).to_vec();
+    guesses.shuffle(rng);
+    guesses.into_iter().find_map(|guess| {
+        let mut guessed = puzzle.clone();
+        guessed.put(row, col, Some(guess));
+        fill_randomly(guessed, rng)
+    })
+}
+
+/// A random puzzle with a unique solution and the given difficulty.
+///
+/// Clues are removed from a random grid, in a random order, as long as the
+/// solution stays unique and the puzzle does not get harder than difficulty,
+/// so no clue of the result can be removed without breaking one of those.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, difficulty: Difficulty) -> SudokuPuzzle {
+    loop {
+        if let Some(puzzle) = dig(rng, difficulty, Symmetry::None, |_| true) {
+            return puzzle;
+        }
+    }
+}
+
+/// A random puzzle like [`generate`]'s, giving up once attempts grids have
+/// been dug without one of difficulty. The same generator, seeded the same
+/// way, makes the same puzzle as [`generate`] whenever one is found.
+///
+/// ```
+/// # use rand::SeedableRng;
+/// # use rand_chacha::ChaCha8Rng;
+/// # use sudoku::{error::GenerationError, generate::*, rating::Difficulty};
+/// let puzzle = generate_within(&mut ChaCha8Rng::seed_from_u64(7), Difficulty::Hard, 1000);
+/// let expected = generate(&mut ChaCha8Rng::seed_from_u64(7), Difficulty::Hard);
+/// assert_eq!(Ok(expected.to_vec()), puzzle.map(|puzzle| puzzle.to_vec()));
+///
+/// let mut rng = ChaCha8Rng::seed_from_u64(7);
+/// assert_eq!(
+///     Err(GenerationError::Exhausted { attempts: 0 }),
+///     generate_within(&mut rng, Difficulty::Easy, 0).map(|_| ())
+/// );
+/// ```
+pub fn generate_within<R: Rng + ?Sized>(
+    rng: &mut R,
+    difficulty: Difficulty,
+    attempts: usize,
+) -> Result<SudokuPuzzle, GenerationError> {
+    (0..attempts)
+        .find_map(|_| dig(rng, difficulty, Symmetry::None, |_| true))
+        .ok_or(GenerationError::Exhausted { attempts })
+}
+
+/// A random puzzle like those of [`generate`], that can also be solved by
+/// hand with only techniques and no guessing, or `None` if the one grid
+/// tried gave no such puzzle at difficulty. Each clue is only removed if the
+/// puzzle stays solvable with techniques, so callers can retry until a
+/// puzzle is found; some combinations, such as a medium puzzle with only
+/// naked singles, are never found.
+///
+/// ```
+/// # use rand::SeedableRng;
+/// # use rand_chacha::ChaCha8Rng;
+/// # use sudoku::{generate::generate_solvable_with, rating::Difficulty, technique::*};
+/// let techniques = [Technique::NakedSingle, Technique::HiddenSingle, Technique::NakedPair];
+/// let mut rng = ChaCha8Rng::seed_from_u64(7);
+/// let puzzle = (0..)
+///     .find_map(|_| generate_solvable_with(&mut rng, Difficulty::Hard, &techniques))
+///     .unwrap();
+/// assert_eq!(Some(Difficulty::Hard), puzzle.difficulty());
+/// assert!(Position::solves(&puzzle, &techniques));
+/// ```
+pub fn generate_solvable_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    difficulty: Difficulty,
+    techniques: &[Technique],
+) -> Option<SudokuPuzzle> {
+    dig(rng, difficulty, Symmetry::None, |puzzle| {
+        Position::solves(puzzle, techniques)
+    })
+}
+
+/// A random puzzle like those of [`generate`], with its givens arranged by
+/// symmetry, or `None` if the one grid tried gave no such puzzle at
+/// difficulty. Unless techniques is empty, the puzzle can also be solved by
+/// hand with only them, as for [`generate_solvable_with`]. Clues are removed
+/// in pairs, so callers can retry until a puzzle is found.
+///
+/// ```
+/// # use rand::SeedableRng;
+/// # use rand_chacha::ChaCha8Rng;
+/// # use sudoku::{generate::*, rating::Difficulty};
+/// let mut rng = ChaCha8Rng::seed_from_u64(7);
+/// let puzzle = (0..)
+///     .find_map(|_| generate_symmetric(&mut rng, Difficulty::Hard, Symmetry::Rotational, &[]))
+///     .unwrap();
+/// assert_eq!(Some(Difficulty::Hard), puzzle.difficulty());
+/// assert_eq!(1, puzzle.count_solutions(2));
+/// for (row, col) in (0..9).flat_map(|row| (0..9).map(move |col| (row, col))) {
+///     let (mirror_row, mirror_col) = Symmetry::Rotational.partner(row, col);
+///     assert_eq!(puzzle.is_given(row, col), puzzle.is_given(mirror_row, mirror_col));
+/// }
+/// ```
+pub fn generate_symmetric<R: Rng + ?Sized>(
+    rng: &mut R,
+    difficulty: Difficulty,
+    symmetry: Symmetry,
+    techniques: &[Technique],
+) -> Option<SudokuPuzzle> {
+    dig(rng, difficulty, symmetry, |puzzle| {
+        techniques.is_empty() || Position::solves(puzzle, techniques)
+    })
+}
+
+/// Remove clues from a random grid, as for [`generate`], each together with
+/// its partner by symmetry, keeping only puzzles that keep accepts, and
+/// return the result if it has difficulty.
+fn dig<R, F>(
+    rng: &mut R,
+    difficulty: Difficulty,
+    symmetry: Symmetry,
+    keep: F,
+) -> Option<SudokuPuzzle>
+where
+    R: Rng + ?Sized,
+    F: Fn(&SudokuPuzzle) -> bool,
+{
+    let solution = random_solution(rng);
+    let mut givens = NineByNine::from_fn(|_, _| Some(true));
+    let mut squares: Vec<usize> = (0..81).collect();
+    squares.shuffle(rng);
+
+    // The difficulty of the puzzle dug so far, once rated, starting from the
+    // full grid, which takes no steps. Nothing is harder than expert, so
+    // expert puzzles are rated only once, when digging is done.
+    let mut dug = Some(Difficulty::Easy);
+    for square in squares {
+        let (row, col) = (square / 9, square % 9);
+        // The square was already removed along with its partner
+        if givens.get(row, col) == Some(&false) {
+            continue;
+        }
+        let (mirror_row, mirror_col) = symmetry.partner(row, col);
+        givens.set(row, col, Some(false));
+        givens.set(mirror_row, mirror_col, Some(false));
+        let puzzle = SudokuPuzzle::from_solution_and_mask(&solution, &givens);
+        let rated = if puzzle.count_solutions(2) != 1 || !keep(&puzzle) {
+            None
+        } else if difficulty == Difficulty::Expert {
+            Some(None)
+        } else {
+            puzzle
+                .difficulty()
+                .filter(|rated| *rated <= difficulty)
+                .map(Some)
+        };
+        match rated {
+            Some(rated) => dug = rated,
+            None => {
+                givens.set(row, col, Some(true));
+                givens.set(mirror_row, mirror_col, Some(true));
+            }
+        }
+    }
+
+    let puzzle = SudokuPuzzle::from_solution_and_mask(&solution, &givens);
+    let rated = dug.or_else(|| puzzle.difficulty());
+    Some(puzzle).filter(|_| rated == Some(difficulty))
+}
@@ -0,0 +1,349 @@
+//! Puzzles of other sizes, whose boxes are rectangles of R rows and C
+//! columns, such as the 6x6 puzzles with 2x3 boxes of children's puzzle
+//! books or 12x12 puzzles with 3x4 boxes.
+//!
+//! Squares are written with the symbols 1 to 9 and then A, B, C, and so on
+//! for 10 and up, with 0 or '.' for an empty square.
+//!
+//! ```
+//! # use sudoku::grid::Grid;
+//! let grid: Grid = "
+//!     ....56
+//!     4...2.
+//!     23...4
+//!     .....1
+//!     31....
+//!     ..5..."
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!((2, 3), grid.box_shape());
+//!
+//! let solved = grid.solve().unwrap();
+//! assert!(solved.is_solved());
+//! assert_eq!(1, grid.count_solutions(2));
+//! assert_eq!("123456456123231564564231312645645312", solved.to_line());
+//! ```
+
+use crate::{
+    board::{self, Board},
+    error::{LimitExceeded, ParseError},
+    limits::SolverLimits,
+};
+use alloc::{string::String, vec::Vec};
+use core::{fmt, str::FromStr};
+
+/// The most symbols a grid can have, which is as many as a board can.
+const MAX_SIZE: usize = 32;
+
+/// A puzzle of size rows and size columns divided into boxes of box_rows
+/// by box_cols squares, where size = box_rows * box_cols.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid {
+    box_rows: usize,
+    box_cols: usize,
+    /// A linear, row-major list of the squares, where 0 indicates an empty
+    /// square.
+    cells: Vec<u8>,
+    /// The rows, then the columns, then the boxes, left to right and top to
+    /// bottom.
+    units: Vec<Vec<usize>>,
+    peers: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    /// An empty grid with boxes of box_rows by box_cols squares.
+    ///
+    /// Panics if a box would have no squares, or more than 32; see
+    /// [`Grid::try_empty`] for shapes that have not been checked.
+    pub fn empty(box_rows: usize, box_cols: usize) -> Self {
+        Grid::try_empty(box_rows, box_cols).expect("boxes have from 1 to 32 squares")
+    }
+
+    /// An empty grid with boxes of box_rows by box_cols squares, or an error
+    /// if a box would have no squares, or more than 32.
+    ///
+    /// ```
+    /// # use sudoku::{error::ParseError, grid::Grid};
+    /// assert_eq!(6, Grid::try_empty(2, 3).unwrap().size());
+    /// assert_eq!(
+    ///     Err(ParseError::InvalidBoxShape { box_rows: 99999, box_cols: 99999 }),
+    ///     Grid::try_empty(99999, 99999)
+    /// );
+    /// ```
+    pub fn try_empty(box_rows: usize, box_cols: usize) -> Result<Self, ParseError> {
+        let size = match box_rows.checked_mul(box_cols) {
+            Some(size) if size > 0 && size <= MAX_SIZE => size,
+            _ => return Err(ParseError::InvalidBoxShape { box_rows, box_cols }),
+        };
+
+        let rows = (0..size).map(|row| (0..size).map(|col| row * size + col).collect());
+        let cols = (0..size).map(|col| (0..size).map(|row| row * size + col).collect());
+        let boxes = (0..size).map(|b| {
+            let top = b / box_rows * box_rows;
+            let left = b % box_rows * box_cols;
+            (0..size)
+                .map(|i| (top + i / box_cols) * size + left + i % box_cols)
+                .collect()
+        });
+        let units: Vec<Vec<usize>> = rows.chain(cols).chain(boxes).collect();
+
+        let peers = (0..size * size)
+            .map(|square| {
+                let mut peers: Vec<usize> = units
+                    .iter()
+                    .filter(|unit| unit.contains(&square))
+                    .flatten()
+                    .copied()
+                    .filter(|peer| *peer != square)
+                    .collect();
+                peers.sort_unstable();
+                peers.dedup();
+                peers
+            })
+            .collect();
+
+        Ok(Grid {
+            box_rows,
+            box_cols,
+            cells: alloc::vec![0; size * size],
+            units,
+            peers,
+        })
+    }
+
+    /// Read a grid with boxes of box_rows by box_cols squares from its
+    /// symbols in row-major order, ignoring whitespace. Boxes must have
+    /// from 1 to 32 squares.
+    ///
+    /// ```
+    /// # use sudoku::{error::ParseError, grid::Grid};
+    /// let grid = Grid::from_string(3, 4, &"0".repeat(144)).unwrap();
+    /// assert_eq!(12, grid.size());
+    /// assert_eq!(
+    ///     Err(ParseError::InvalidSymbol { index: 0, c: 'D' }),
+    ///     Grid::from_string(3, 4, &format!("D{}", "0".repeat(143)))
+    /// );
+    /// ```
+    pub fn from_string(box_rows: usize, box_cols: usize, source: &str) -> Result<Self, ParseError> {
+        let mut grid = Grid::try_empty(box_rows, box_cols)?;
+        let size = grid.size();
+        let symbols: Vec<char> = source.chars().filter(|c| !c.is_whitespace()).collect();
+        if symbols.len() != size * size {
+            return Err(ParseError::WrongGridLength {
+                expected: size * size,
+                len: symbols.len(),
+            });
+        }
+
+        for (index, c) in symbols.into_iter().enumerate() {
+            grid.cells[index] = match c {
+                '.' => 0,
+                c => match c.to_digit(36) {
+                    Some(n) if n as usize <= size => n as u8,
+                    _ => return Err(ParseError::InvalidSymbol { index, c }),
+                },
+            };
+        }
+        Ok(grid)
+    }
+
+    /// The shape of box that a grid of size rows would usually have: as
+    /// close to square as possible, with no more rows than columns.
+    ///
+    /// ```
+    /// # use sudoku::grid::Grid;
+    /// assert_eq!((2, 3), Grid::box_shape_for(6));
+    /// assert_eq!((3, 3), Grid::box_shape_for(9));
+    /// assert_eq!((3, 4), Grid::box_shape_for(12));
+    /// ```
+    pub fn box_shape_for(size: usize) -> (usize, usize) {
+        let box_rows = (1..=size)
+            .take_while(|rows| rows * rows <= size)
+            .filter(|rows| size.is_multiple_of(*rows))
+            .last()
+            .unwrap_or(1);
+        (box_rows, size / box_rows)
+    }
+
+    /// The number of rows, which is also the number of columns.
+    pub fn size(&self) -> usize {
+        self.box_rows * self.box_cols
+    }
+
+    /// The rows and columns of each box.
+    pub fn box_shape(&self) -> (usize, usize) {
+        (self.box_rows, self.box_cols)
+    }
+
+    /// The symbol at (row, col), if that square has been filled.
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        match self.cells[row * self.size() + col] {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    /// A linear, row-major list of the squares, where 0 indicates an empty
+    /// square.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.cells.clone()
+    }
+
+    /// The grid on one line, in the form read by `from_string`.
+    pub fn to_line(&self) -> String {
+        self.cells.iter().map(|n| symbol(*n)).collect()
+    }
+
+    /// Indicates whether every square is filled without breaking the rules.
+    pub fn is_solved(&self) -> bool {
+        !self.cells.contains(&0) && board::is_consistent(self)
+    }
+
+    /// Solve the grid, if possible, returning it with every square filled.
+    pub fn solve(&self) -> Option<Grid> {
+        board::solve(self)
+    }
+
+    /// Count the solutions of the grid, stopping once limit have been found.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        board::count_solutions(self, limit)
+    }
+
+    /// Count the solutions of the grid like `count_solutions`, giving up
+    /// once the search breaks one of limits.
+    pub fn count_solutions_within(
+        &self,
+        limit: usize,
+        limits: &SolverLimits,
+    ) -> Result<usize, LimitExceeded> {
+        board::count_solutions_within(self, limit, limits)
+    }
+
+    /// A copy of the grid with filled squares removed for as long as its
+    /// solution stays unique, or `None` if it does not have exactly one
+    /// solution, like [`SudokuPuzzle::minimized`](crate::puzzle::SudokuPuzzle::minimized).
+    ///
+    /// ```
+    /// # use sudoku::grid::Grid;
+    /// let grid: Grid = "123456456123231564564231312645645312".parse().unwrap();
+    /// let minimized = grid.minimized().unwrap();
+    /// assert_eq!(grid.solve(), minimized.solve());
+    /// assert!(minimized.to_vec().contains(&0));
+    /// assert!(Grid::empty(2, 3).minimized().is_none());
+    /// ```
+    pub fn minimized(&self) -> Option<Grid> {
+        if self.count_solutions(2) != 1 {
+            return None;
+        }
+
+        let mut grid = self.clone();
+        for square in 0..grid.cells.len() {
+            let n = grid.cells[square];
+            if n == 0 {
+                continue;
+            }
+            grid.cells[square] = 0;
+            if grid.count_solutions(2) != 1 {
+                grid.cells[square] = n;
+            }
+        }
+        Some(grid)
+    }
+}
+
+impl Board for Grid {
+    fn size(&self) -> usize {
+        Grid::size(self)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Option<u8> {
+        self.get(row, col)
+    }
+
+    fn set_cell(&mut self, row: usize, col: usize, val: Option<u8>) {
+        let size = self.size();
+        self.cells[row * size + col] = val.unwrap_or(0);
+    }
+
+    fn unit_count(&self) -> usize {
+        self.units.len()
+    }
+
+    fn unit(&self, i: usize) -> &[usize] {
+        &self.units[i]
+    }
+
+    fn peers(&self, square: usize) -> &[usize] {
+        &self.peers[square]
+    }
+}
+
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    /// Read a grid from its symbols, with the usual box shape for its size
+    /// (see [`Grid::box_shape_for`]).
+    fn from_str(source: &str) -> Result<Self, ParseError> {
+        let len = source.chars().filter(|c| !c.is_whitespace()).count();
+        let size = (1..=MAX_SIZE)
+            .find(|size| size * size >= len)
+            .unwrap_or(MAX_SIZE);
+        if size * size != len {
+            return Err(ParseError::WrongGridLength {
+                expected: size * size,
+                len,
+            });
+        }
+        let (box_rows, box_cols) = Grid::box_shape_for(size);
+        Grid::from_string(box_rows, box_cols, source)
+    }
+}
+
+impl fmt::Display for Grid {
+    /// The grid with its symbols separated by spaces, '.' for an empty
+    /// square, and lines between the boxes.
+    ///
+    /// ```
+    /// # use sudoku::grid::Grid;
+    /// let grid: Grid = "1234341221434321".parse().unwrap();
+    /// assert_eq!(
+    ///     "1 2 | 3 4\n3 4 | 1 2\n----+----\n2 1 | 4 3\n4 3 | 2 1",
+    ///     grid.to_string()
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size = self.size();
+        for row in 0..size {
+            let mut line = String::new();
+            for col in 0..size {
+                if col > 0 {
+                    line.push_str(if col % self.box_cols == 0 { " | " } else { " " });
+                }
+                line.push(match self.get(row, col) {
+                    Some(n) => symbol(n),
+                    None => '.',
+                });
+            }
+
+            if row > 0 {
+                writeln!(f)?;
+                if row % self.box_rows == 0 {
+                    let rule: String = line
+                        .chars()
+                        .map(|c| if c == '|' { '+' } else { '-' })
+                        .collect();
+                    writeln!(f, "{}", rule)?;
+                }
+            }
+            write!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// The character for symbol n, or '0' for an empty square.
+fn symbol(n: u8) -> char {
+    core::char::from_digit(u32::from(n), 36)
+        .unwrap_or('?')
+        .to_ascii_uppercase()
+}
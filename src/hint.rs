@@ -0,0 +1,144 @@
+//! Hints that give away the solver's next step a little at a time, so that
+//! a player can be nudged without being handed the answer.
+//!
+//! ```
+//! # use sudoku::{hint::Level, puzzle::SudokuPuzzle};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let hint = puzzle.hint().unwrap();
+//! for level in Level::ALL.iter() {
+//!     println!("{}", hint.reveal(*level));
+//! }
+//! ```
+
+use crate::{
+    solution::Solution,
+    step::Step,
+    units::{box_index, Unit},
+};
+use alloc::{format, string::String};
+
+/// How much of a hint to give away.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Level {
+    /// Name the technique that finds the next number.
+    Technique,
+    /// Point at the row, column, or box to look in.
+    Region,
+    /// Give the square and the number that goes there.
+    Cell,
+}
+
+impl Level {
+    /// Every level, from the least given away to the most.
+    pub const ALL: [Level; 3] = [Level::Technique, Level::Region, Level::Cell];
+
+    /// The level that gives away a little more, if there is one.
+    ///
+    /// ```
+    /// # use sudoku::hint::Level;
+    /// assert_eq!(Some(Level::Region), Level::Technique.next());
+    /// assert_eq!(None, Level::Cell.next());
+    /// ```
+    pub fn next(self) -> Option<Level> {
+        match self {
+            Level::Technique => Some(Level::Region),
+            Level::Region => Some(Level::Cell),
+            Level::Cell => None,
+        }
+    }
+}
+
+/// The next step toward solving a puzzle, as found by
+/// [`SudokuPuzzle::hint`](crate::puzzle::SudokuPuzzle::hint).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Hint {
+    step: Step,
+}
+
+impl Hint {
+    /// The hint for the first of steps, which solve a puzzle to solution.
+    /// When the first step is a guess, the hint is the solution's number
+    /// for the guessed square instead.
+    pub(crate) fn from_steps(steps: &[Step], solution: &Solution) -> Option<Self> {
+        let step = match *steps.first()? {
+            Step::Guess { row, col, .. } | Step::Backtrack { row, col } => Step::Guess {
+                row,
+                col,
+                n: solution.get(row, col),
+            },
+            step => step,
+        };
+        Some(Hint { step })
+    }
+
+    /// The step that the hint is for. A [`Step::Guess`] means nothing could
+    /// be deduced, and its number is the one from the solution.
+    pub fn step(&self) -> Step {
+        self.step
+    }
+
+    /// The square and the number that goes there.
+    pub fn cell(&self) -> (usize, usize, u8) {
+        let (row, col) = self.step.square();
+        (row, col, self.step.number().unwrap_or(0))
+    }
+
+    /// The unit to look in: for a hidden single, the unit where its number
+    /// has only one place, and otherwise the box of the square.
+    pub fn region(&self) -> Unit {
+        match self.step {
+            Step::HiddenSingle { unit, .. } => unit,
+            step => {
+                let (row, col) = step.square();
+                Unit::Box(box_index(row, col))
+            }
+        }
+    }
+
+    /// The hint as a sentence, giving away as much as level.
+    ///
+    /// ```
+    /// # use sudoku::{hint::Level, puzzle::SudokuPuzzle};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let hint = puzzle.hint().unwrap();
+    /// assert!(hint.reveal(Level::Technique).starts_with("Look for a"));
+    /// assert!(!hint.reveal(Level::Region).contains(&format!("r{}", hint.cell().0 + 1)));
+    /// assert_eq!(hint.step().explain(), hint.reveal(Level::Cell));
+    /// ```
+    pub fn reveal(&self, level: Level) -> String {
+        match (level, self.step) {
+            (Level::Technique, Step::Single { .. }) => {
+                String::from("Look for a naked single: a square where only one number can go.")
+            }
+            (Level::Technique, Step::HiddenSingle { .. }) => String::from(
+                "Look for a hidden single: a number that can only go in one square of a \
+                 row, column, or box.",
+            ),
+            (Level::Technique, _) => String::from(
+                "Nothing more can be deduced, so a square has to be guessed or checked \
+                 against the solution.",
+            ),
+            (Level::Region, Step::Single { .. }) => format!(
+                "Look for a square in {} where only one number can go.",
+                self.region()
+            ),
+            (Level::Region, Step::HiddenSingle { n, unit, .. }) => {
+                format!("Look for where {} can go in {}.", n, unit)
+            }
+            (Level::Region, _) => format!("Look in {}.", self.region()),
+            (Level::Cell, Step::Guess { row, col, n }) => format!(
+                "Nothing can be deduced from here, but the solution has {} at r{}c{}.",
+                n,
+                row + 1,
+                col + 1
+            ),
+            (Level::Cell, step) => step.explain(),
+        }
+    }
+}
@@ -0,0 +1,576 @@
+use crate::{
+    error::{check_number, check_square, Error, ParseError, PlacementError},
+    nine_set::NineSet,
+    puzzle::SudokuPuzzle,
+};
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+/// A single reversible change to a puzzle.
+#[derive(Clone, Copy, PartialEq)]
+enum Edit {
+    /// The number at (row, col) changed from `before` to `after`.
+    Value {
+        row: usize,
+        col: usize,
+        before: Option<u8>,
+        after: Option<u8>,
+    },
+    /// The pencil marks at (row, col) changed from `before` to `after`.
+    Marks {
+        row: usize,
+        col: usize,
+        before: NineSet,
+        after: NineSet,
+    },
+}
+
+impl Edit {
+    /// Whether this edit could be applied to puzzle: the square is as it was
+    /// before the edit, and the edit does not change a given.
+    fn applies_to(self, puzzle: &SudokuPuzzle) -> bool {
+        match self {
+            Edit::Value {
+                row, col, before, ..
+            } => !puzzle.is_given(row, col) && puzzle.get(row, col) == before,
+            Edit::Marks {
+                row, col, before, ..
+            } => puzzle.marks(row, col) == before,
+        }
+    }
+
+    /// Read an "edit value row col before after" or "edit marks row col
+    /// before after" line, without its first word. Empty squares are 0 and
+    /// empty sets of marks are '-'.
+    fn parse<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<Edit> {
+        let kind = words.next()?;
+        let row = words.next()?.parse().ok().filter(|row| *row < 9)?;
+        let col = words.next()?.parse().ok().filter(|col| *col < 9)?;
+        let (before, after) = (words.next()?, words.next()?);
+        if words.next().is_some() {
+            return None;
+        }
+
+        match kind {
+            "value" => Some(Edit::Value {
+                row,
+                col,
+                before: parse_value(before)?,
+                after: parse_value(after)?,
+            }),
+            "marks" => Some(Edit::Marks {
+                row,
+                col,
+                before: parse_marks(before)?,
+                after: parse_marks(after)?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The edit as a line of a saved game.
+    fn save(self, text: &mut String) {
+        match self {
+            Edit::Value {
+                row,
+                col,
+                before,
+                after,
+            } => writeln!(
+                text,
+                "edit value {} {} {} {}",
+                row,
+                col,
+                before.unwrap_or(0),
+                after.unwrap_or(0)
+            ),
+            Edit::Marks {
+                row,
+                col,
+                before,
+                after,
+            } => writeln!(
+                text,
+                "edit marks {} {} {} {}",
+                row,
+                col,
+                save_marks(before),
+                save_marks(after)
+            ),
+        }
+        .unwrap();
+    }
+
+    /// The edit that reverses this one.
+    fn inverse(self) -> Self {
+        match self {
+            Edit::Value {
+                row,
+                col,
+                before,
+                after,
+            } => Edit::Value {
+                row,
+                col,
+                before: after,
+                after: before,
+            },
+            Edit::Marks {
+                row,
+                col,
+                before,
+                after,
+            } => Edit::Marks {
+                row,
+                col,
+                before: after,
+                after: before,
+            },
+        }
+    }
+}
+
+/// A puzzle together with the record of placements, erasures, and
+/// pencil-mark edits made to it, which can be undone and redone.
+///
+/// ```
+/// # use sudoku::{history::History, puzzle::SudokuPuzzle};
+/// let mut history = History::new(SudokuPuzzle::empty());
+/// history.place(0, 0, 5).unwrap();
+/// history.toggle_mark(0, 1, 3).unwrap();
+///
+/// assert!(history.undo());
+/// assert!(history.puzzle().marks(0, 1).is_empty());
+/// assert!(history.undo());
+/// assert_eq!(None, history.puzzle().get(0, 0));
+/// assert!(!history.undo());
+///
+/// assert!(history.redo());
+/// assert_eq!(Some(5), history.puzzle().get(0, 0));
+/// ```
+#[derive(Clone)]
+pub struct History {
+    puzzle: SudokuPuzzle,
+    undo: Vec<Vec<Edit>>,
+    redo: Vec<Vec<Edit>>,
+}
+
+impl History {
+    /// Begin recording the edits made to puzzle.
+    pub fn new(puzzle: SudokuPuzzle) -> Self {
+        History {
+            puzzle,
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// The puzzle in its current state.
+    pub fn puzzle(&self) -> &SudokuPuzzle {
+        &self.puzzle
+    }
+
+    /// Stop recording and return the puzzle in its current state.
+    pub fn into_puzzle(self) -> SudokuPuzzle {
+        self.puzzle
+    }
+
+    /// Fill (row, col) with n. n must be in the range [1,9], and (row, col)
+    /// must not be a given.
+    pub fn place(&mut self, row: usize, col: usize, n: u8) -> Result<(), Error> {
+        check_number(row, col, n)?;
+        self.check_not_given(row, col)?;
+        let edit = self.value_edit(row, col, Some(n));
+        self.record(vec![edit]);
+        Ok(())
+    }
+
+    /// Empty (row, col) and remove its pencil marks, as a single move.
+    /// Givens cannot be cleared.
+    ///
+    /// ```
+    /// # use sudoku::{history::History, puzzle::SudokuPuzzle};
+    /// let mut history = History::new(SudokuPuzzle::empty());
+    /// history.place(0, 0, 5).unwrap();
+    /// history.add_mark(0, 0, 3).unwrap();
+    /// history.clear(0, 0).unwrap();
+    ///
+    /// assert!(history.undo());
+    /// assert_eq!(Some(5), history.puzzle().get(0, 0));
+    /// assert_eq!(vec![3], history.puzzle().marks(0, 0).to_vec());
+    /// ```
+    pub fn clear(&mut self, row: usize, col: usize) -> Result<(), Error> {
+        check_square(row, col)?;
+        self.check_not_given(row, col)?;
+        let edits = vec![
+            self.value_edit(row, col, None),
+            self.marks_edit(row, col, NineSet::empty()),
+        ];
+        self.record(edits);
+        Ok(())
+    }
+
+    /// Return the puzzle to its givens, as a single move that can be undone.
+    pub fn reset(&mut self) {
+        let mut edits = Vec::new();
+        for row in 0..9 {
+            for col in 0..9 {
+                if !self.puzzle.is_given(row, col) {
+                    edits.push(self.value_edit(row, col, None));
+                }
+                edits.push(self.marks_edit(row, col, NineSet::empty()));
+            }
+        }
+        self.record(edits);
+    }
+
+    /// Set the pencil marks of every empty square to the numbers that could
+    /// be placed there without breaking the rules of Sudoku, as a single move.
+    ///
+    /// ```
+    /// # use sudoku::{history::History, puzzle::SudokuPuzzle};
+    /// let mut history = History::new(SudokuPuzzle::empty());
+    /// history.place(0, 0, 5).unwrap();
+    /// history.fill_marks();
+    /// assert!(!history.puzzle().marks(0, 1).contains(5));
+    /// assert!(history.puzzle().marks(8, 8).contains(5));
+    /// assert!(history.puzzle().marks(0, 0).is_empty());
+    ///
+    /// assert!(history.undo());
+    /// assert!(history.puzzle().marks(8, 8).is_empty());
+    /// ```
+    pub fn fill_marks(&mut self) {
+        let mut edits = Vec::new();
+        for row in 0..9 {
+            for col in 0..9 {
+                if self.puzzle.get(row, col).is_none() {
+                    let candidates = self.puzzle.candidates(row, col);
+                    edits.push(self.marks_edit(row, col, candidates));
+                }
+            }
+        }
+        self.record(edits);
+    }
+
+    /// Add the pencil mark n to (row, col). n must be in the range [1,9].
+    pub fn add_mark(&mut self, row: usize, col: usize, n: u8) -> Result<(), Error> {
+        check_number(row, col, n)?;
+        let mut marks = self.puzzle.marks(row, col);
+        marks.add(n);
+        let edit = self.marks_edit(row, col, marks);
+        self.record(vec![edit]);
+        Ok(())
+    }
+
+    /// Remove the pencil mark n from (row, col), if it is present.
+    pub fn remove_mark(&mut self, row: usize, col: usize, n: u8) -> Result<(), Error> {
+        check_number(row, col, n)?;
+        let mut marks = self.puzzle.marks(row, col);
+        marks.remove(n);
+        let edit = self.marks_edit(row, col, marks);
+        self.record(vec![edit]);
+        Ok(())
+    }
+
+    /// Add the pencil mark n to (row, col) if it is absent, otherwise remove it.
+    pub fn toggle_mark(&mut self, row: usize, col: usize, n: u8) -> Result<(), Error> {
+        check_number(row, col, n)?;
+        if self.puzzle.marks(row, col).contains(n) {
+            self.remove_mark(row, col, n)
+        } else {
+            self.add_mark(row, col, n)
+        }
+    }
+
+    /// Indicates whether there is a move that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Indicates whether there is an undone move that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Revert the most recent move. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(edits) => {
+                for edit in edits.iter().rev() {
+                    self.apply(edit.inverse());
+                }
+                self.redo.push(edits);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapply the most recently undone move. Returns `false` if there was
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(edits) => {
+                for edit in edits.iter() {
+                    self.apply(*edit);
+                }
+                self.undo.push(edits);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Describe the puzzle, its pencil marks, and every move that can be
+    /// undone or redone, as text that can be passed to [`History::restore`].
+    ///
+    /// ```
+    /// # use sudoku::{history::History, puzzle::SudokuPuzzle};
+    /// let puzzle = SudokuPuzzle::from_string(&format!("1{}", "0".repeat(80))).unwrap();
+    /// let mut history = History::new(puzzle);
+    /// history.place(0, 1, 2).unwrap();
+    /// history.toggle_mark(0, 2, 3).unwrap();
+    /// history.toggle_mark(0, 2, 4).unwrap();
+    /// history.undo();
+    ///
+    /// let mut restored = History::restore(&history.save()).unwrap();
+    /// assert!(restored.puzzle().is_given(0, 0));
+    /// assert!(!restored.puzzle().is_given(0, 1));
+    /// assert_eq!(vec![3], restored.puzzle().marks(0, 2).to_vec());
+    /// assert!(restored.redo());
+    /// assert_eq!(vec![3, 4], restored.puzzle().marks(0, 2).to_vec());
+    /// ```
+    pub fn save(&self) -> String {
+        let mut text = String::from("givens ");
+        for row in 0..9 {
+            for col in 0..9 {
+                let given =
+                    Some(self.puzzle.get(row, col)).filter(|_| self.puzzle.is_given(row, col));
+                text.push(char::from(b'0' + given.flatten().unwrap_or(0)));
+            }
+        }
+        text.push_str("\nnumbers ");
+        for n in self.puzzle.to_vec() {
+            text.push(char::from(b'0' + n));
+        }
+        text.push('\n');
+
+        for row in 0..9 {
+            for col in 0..9 {
+                let marks = self.puzzle.marks(row, col);
+                if !marks.is_empty() {
+                    writeln!(text, "marks {} {} {}", row, col, save_marks(marks)).unwrap();
+                }
+            }
+        }
+        for (name, moves) in [("undo", &self.undo), ("redo", &self.redo)].iter() {
+            for edits in moves.iter() {
+                writeln!(text, "{}", name).unwrap();
+                for edit in edits {
+                    edit.save(&mut text);
+                }
+            }
+        }
+        text
+    }
+
+    /// Continue a game from the text made by [`History::save`], checking that
+    /// every move could have been made.
+    ///
+    /// ```
+    /// # use sudoku::{error::ParseError, history::History, Error};
+    /// let saved = format!("givens {0}\nnumbers {0}\nundo\nedit value 0 0 1 2", "0".repeat(81));
+    /// let err = History::restore(&saved).err();
+    /// assert_eq!(Some(Error::Parse(ParseError::InvalidSavedGame { line: 4 })), err);
+    /// ```
+    pub fn restore(saved: &str) -> Result<Self, Error> {
+        let invalid = |line: usize| ParseError::InvalidSavedGame { line };
+        let mut lines = saved
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (line, givens) = lines.next().ok_or_else(|| invalid(1))?;
+        let givens = givens
+            .strip_prefix("givens ")
+            .ok_or_else(|| invalid(line))?;
+        let mut puzzle = SudokuPuzzle::from_string(givens)?;
+
+        let (line, numbers) = lines.next().ok_or_else(|| invalid(line + 1))?;
+        let numbers = numbers
+            .strip_prefix("numbers ")
+            .ok_or_else(|| invalid(line))?;
+        let numbers = SudokuPuzzle::from_string(numbers)?.to_vec();
+        for (square, n) in numbers.into_iter().enumerate() {
+            let (row, col) = (square / 9, square % 9);
+            if puzzle.is_given(row, col) {
+                if puzzle.get(row, col) != Some(n) {
+                    return Err(invalid(line).into());
+                }
+            } else if n != 0 {
+                puzzle.put(row, col, Some(n));
+            }
+        }
+
+        // Each move with the line numbers of its edits, and whether it is
+        // on the redo stack
+        let mut moves: Vec<(bool, Vec<(usize, Edit)>)> = Vec::new();
+        for (line, text) in lines {
+            let mut words = text.split(' ');
+            match words.next() {
+                Some("marks") if moves.is_empty() => {
+                    let row = words.next().and_then(|w| w.parse().ok());
+                    let col = words.next().and_then(|w| w.parse().ok());
+                    let marks = words.next().and_then(parse_marks);
+                    match (row, col, marks, words.next()) {
+                        (Some(row), Some(col), Some(marks), None) => puzzle
+                            .set_marks(row, col, marks)
+                            .map_err(|_| invalid(line))?,
+                        _ => return Err(invalid(line).into()),
+                    }
+                }
+                Some("undo") | Some("redo") if words.next().is_none() => {
+                    let redo = text == "redo";
+                    if moves
+                        .last()
+                        .is_some_and(|(last_redo, edits)| edits.is_empty() || (*last_redo && !redo))
+                    {
+                        return Err(invalid(line).into());
+                    }
+                    moves.push((redo, Vec::new()));
+                }
+                Some("edit") => match (moves.last_mut(), Edit::parse(words)) {
+                    (Some((_, edits)), Some(edit)) => edits.push((line, edit)),
+                    _ => return Err(invalid(line).into()),
+                },
+                _ => return Err(invalid(line).into()),
+            }
+        }
+        if let Some((_, edits)) = moves.last() {
+            if edits.is_empty() {
+                return Err(invalid(saved.lines().count()).into());
+            }
+        }
+
+        // Undoing every move and then redoing them must be possible from the
+        // current state of the puzzle
+        let mut history = History::new(puzzle);
+        let (redo, undo): (Vec<_>, Vec<_>) = moves.into_iter().partition(|(redo, _)| *redo);
+        let mut state = history.puzzle.clone();
+        for (_, edits) in undo.iter().rev() {
+            for (line, edit) in edits.iter().rev() {
+                apply_checked(&mut state, edit.inverse()).ok_or_else(|| invalid(*line))?;
+            }
+        }
+        let mut state = history.puzzle.clone();
+        for (_, edits) in redo.iter().rev() {
+            for (line, edit) in edits {
+                apply_checked(&mut state, *edit).ok_or_else(|| invalid(*line))?;
+            }
+        }
+
+        history.undo = undo
+            .into_iter()
+            .map(|(_, edits)| edits.into_iter().map(|(_, edit)| edit).collect())
+            .collect();
+        history.redo = redo
+            .into_iter()
+            .map(|(_, edits)| edits.into_iter().map(|(_, edit)| edit).collect())
+            .collect();
+        Ok(history)
+    }
+
+    fn check_not_given(&self, row: usize, col: usize) -> Result<(), PlacementError> {
+        if self.puzzle.is_given(row, col) {
+            Err(PlacementError::Given { row, col })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn value_edit(&self, row: usize, col: usize, after: Option<u8>) -> Edit {
+        Edit::Value {
+            row,
+            col,
+            before: self.puzzle.get(row, col),
+            after,
+        }
+    }
+
+    fn marks_edit(&self, row: usize, col: usize, after: NineSet) -> Edit {
+        Edit::Marks {
+            row,
+            col,
+            before: self.puzzle.marks(row, col),
+            after,
+        }
+    }
+
+    /// Apply the edits of a new move and record it. Any undone moves are
+    /// discarded, since they no longer follow from the current state.
+    fn record(&mut self, edits: Vec<Edit>) {
+        let edits = edits
+            .into_iter()
+            .filter(|edit| *edit != edit.inverse())
+            .collect::<Vec<Edit>>();
+        if edits.is_empty() {
+            return;
+        }
+
+        for edit in edits.iter() {
+            self.apply(*edit);
+        }
+        self.undo.push(edits);
+        self.redo.clear();
+    }
+
+    /// Apply an edit, which was validated when it was first recorded.
+    fn apply(&mut self, edit: Edit) {
+        let result = match edit {
+            Edit::Value { row, col, after, .. } => self.puzzle.set(row, col, after),
+            Edit::Marks { row, col, after, .. } => self.puzzle.set_marks(row, col, after),
+        };
+        debug_assert!(result.is_ok());
+    }
+}
+
+/// Apply edit to puzzle if it applies to the puzzle's current state.
+fn apply_checked(puzzle: &mut SudokuPuzzle, edit: Edit) -> Option<()> {
+    if !edit.applies_to(puzzle) {
+        return None;
+    }
+    match edit {
+        Edit::Value { row, col, after, .. } => puzzle.set(row, col, after).ok(),
+        Edit::Marks { row, col, after, .. } => puzzle.set_marks(row, col, after).ok(),
+    }
+}
+
+/// Read a square's number, where 0 is an empty square.
+fn parse_value(word: &str) -> Option<Option<u8>> {
+    match word.parse::<u8>().ok()? {
+        0 => Some(None),
+        n if n <= 9 => Some(Some(n)),
+        _ => None,
+    }
+}
+
+/// Read a set of pencil marks written as digits, or '-' for none.
+fn parse_marks(word: &str) -> Option<NineSet> {
+    match word {
+        "-" => Some(NineSet::empty()),
+        digits => digits
+            .chars()
+            .map(|c| c.to_digit(10).filter(|n| *n != 0).map(|n| n as u8))
+            .collect(),
+    }
+}
+
+/// Write a set of pencil marks as digits, or '-' for none.
+fn save_marks(marks: NineSet) -> String {
+    if marks.is_empty() {
+        return String::from("-");
+    }
+    marks.iter().map(|n| char::from(b'0' + n)).collect()
+}
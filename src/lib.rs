@@ -1,3 +1,59 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod batch;
+pub mod board;
+pub mod builder;
+mod bytes;
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod calibration;
+mod canonical;
+pub mod context;
+#[cfg(feature = "corpus")]
+pub mod corpus;
+pub mod db;
+pub mod deterministic;
+mod dlx;
+pub mod error;
+pub mod generate;
+pub mod grid;
+pub mod hint;
+pub mod history;
+pub mod limits;
+#[cfg(feature = "server")]
+mod metrics;
+pub mod minimal;
 pub mod nine_by_nine;
 pub mod nine_set;
+pub mod prelude;
 pub mod puzzle;
+pub mod rating;
+pub mod render;
+pub mod replay;
+pub mod search;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod session;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
+pub mod solution;
+pub mod step;
+pub mod strategy;
+pub mod stuck;
+pub mod sukaku;
+pub mod teaching;
+pub mod technique;
+pub mod trace;
+pub mod transform;
+pub mod units;
+pub mod verify;
+#[cfg(feature = "pdf")]
+pub mod worksheet;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::Error;
@@ -0,0 +1,42 @@
+//! Limits on how much work the solver may do, for puzzles from untrusted
+//! sources. Without them, a crafted grid with few givens can keep the search
+//! busy for a very long time.
+//!
+//! ```
+//! # use sudoku::{error::LimitExceeded, limits::SolverLimits, puzzle::SudokuPuzzle};
+//! let limits = SolverLimits {
+//!     max_nodes: Some(10),
+//!     ..SolverLimits::default()
+//! };
+//! let easy = SudokuPuzzle::from_string(
+//!     "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+//! )
+//! .unwrap();
+//! assert_eq!(Ok(easy.solve()), easy.solve_within(&limits));
+//!
+//! // Nothing but a 1 in the corner takes more guesses than that to count
+//! let open = SudokuPuzzle::from_string(&format!("1{}", "0".repeat(80))).unwrap();
+//! assert_eq!(
+//!     Err(LimitExceeded::Nodes { max: 10 }),
+//!     open.count_solutions_within(1000, &limits)
+//! );
+//! ```
+
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+/// How much work a search may do before giving up with
+/// [`LimitExceeded`](crate::error::LimitExceeded). A limit of `None` is no
+/// limit, which is the default for each.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct SolverLimits {
+    /// The most points the search may visit: the puzzle itself and each
+    /// guess made from it.
+    pub max_nodes: Option<u64>,
+    /// The most guesses that may be made on top of each other before one is
+    /// taken back.
+    pub max_guess_depth: Option<usize>,
+    /// The longest the search may run. Needs the `std` feature for a clock.
+    #[cfg(feature = "std")]
+    pub max_time: Option<Duration>,
+}
@@ -1,45 +1,172 @@
 extern crate sudoku;
-use sudoku::puzzle::SudokuPuzzle;
 
-use std::io;
+mod cli;
 
-fn main() -> io::Result<()> {
-    println!("Please enter the puzzle as a sequence of 81 numbers.");
-    println!("Use '0' to indicate an empty space.");
-    println!("You may include line breaks, but no other whitespace.");
-    println!("Press enter on an empty line when you are done.\n");
+use clap::{Parser, Subcommand};
+use cli::exit::Exit;
+use std::{io, process::ExitCode};
 
-    let mut puzzle_source = String::new();
-    loop {
-        let mut buffer = String::new();
-        std::io::stdin().read_line(&mut buffer)?;
+/// Solve Sudoku puzzles using deduction and backtracking.
+#[derive(Parser)]
+#[command(version, after_help = cli::exit::HELP)]
+struct Cli {
+    /// How to print puzzles, solutions, and results [default: grid]
+    #[arg(long, global = true, value_enum)]
+    format: Option<cli::output::Format>,
 
-        if buffer.trim_end().is_empty() {
-            break;
-        } else {
-            puzzle_source.push_str(&buffer);
-        }
-    }
+    /// Print one JSON object per puzzle; short for --format json
+    #[arg(long, global = true, conflicts_with = "format")]
+    json: bool,
 
-    let puzzle = SudokuPuzzle::from_string(&puzzle_source);
-    println!("Input:\n{:?}\n\n", puzzle);
+    /// Print without colors, as also happens when NO_COLOR is set
+    #[arg(long, global = true)]
+    no_color: bool,
 
-    match puzzle.solve() {
-        Some(solution) => println!("Solution:\n{:?}", solution),
-        None => println!("No solution could be found"),
-    }
+    /// Seed the random number generator, so that runs can be repeated
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+
+    /// Read defaults from this file instead of ~/.config/sudoku/config.toml
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<std::path::PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
 
-    Ok(())
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one or more puzzles
+    Solve(cli::solve::SolveArgs),
+    /// Check that puzzles are consistent and have exactly one solution
+    Check(cli::check::CheckArgs),
+    /// Solve a puzzle by hand in the terminal
+    Play(cli::play::PlayArgs),
+    /// Practise a solving technique on positions that need it
+    Train(cli::train::TrainArgs),
+    /// Remove givens from puzzles that are not needed for a unique solution
+    Minimize(cli::minimize::MinimizeArgs),
+    /// Print puzzles in canonical form, optionally dropping equivalent ones
+    Canonicalize(cli::canonicalize::CanonicalizeArgs),
+    /// Make new puzzles with unique solutions
+    Generate(cli::generate::GenerateArgs),
+    /// Print the day's generated puzzle, the same for everyone
+    Daily(cli::daily::DailyArgs),
+    /// Keep rated puzzles in a database file and look them up
+    Db(cli::db::DbArgs),
+    /// Time the solver on a built-in set of puzzles or those in a file
+    Bench(cli::bench::BenchArgs),
+    /// Time several solving strategies on the same puzzles, side by side
+    Compare(cli::compare::CompareArgs),
+    /// Compare ratings with those of a list of rated puzzles
+    Calibrate(cli::calibrate::CalibrateArgs),
+    /// Rewrite a file of puzzles in another format
+    Convert(cli::convert::ConvertArgs),
+    /// Draw a puzzle as an SVG, PNG, HTML, or text file
+    Render(cli::render::RenderArgs),
+    /// Write a printable PDF of generated puzzles and their answers
+    Worksheet(cli::worksheet::WorksheetArgs),
+    /// Run the HTTP solver service
+    #[cfg(feature = "server")]
+    Serve(cli::serve::ServeArgs),
 }
 
-// Example input:
-//
-// 410036000
-// 007000850
-// 600000000
-// 090000200
-// 006070008
-// 000000091
-// 002014000
-// 000003000
-// 740008509
+fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // --help and --version are reported as errors, but printed to stdout
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() {
+                Exit::Usage
+            } else {
+                Exit::Success
+            }
+            .into();
+        }
+    };
+    let config = match cli::config::Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Exit::Usage.into();
+        }
+    };
+    let color = cli::output::use_color(cli.no_color || config.color == Some(false));
+    let format = if cli.json {
+        cli::output::Format::Json
+    } else {
+        cli.format.or(config.format).unwrap_or_default()
+    };
+
+    // Without a subcommand, prompt for a single puzzle to solve
+    let result = match cli.command {
+        Some(Command::Solve(mut args)) => {
+            args.max_nodes = args.max_nodes.or(config.solve.max_nodes);
+            args.max_depth = args.max_depth.or(config.solve.max_depth);
+            args.max_time = args.max_time.or(config.solve.max_time);
+            cli::solve::run(&args, format, color)
+        }
+        Some(Command::Check(mut args)) => {
+            args.max_nodes = args.max_nodes.or(config.solve.max_nodes);
+            args.max_depth = args.max_depth.or(config.solve.max_depth);
+            args.max_time = args.max_time.or(config.solve.max_time);
+            cli::check::run(&args, format, color)
+        }
+        Some(Command::Play(mut args)) => {
+            if args.resume.is_none() {
+                args.save = args.save.or(config.play.save);
+            }
+            cli::play::run(&args)
+        }
+        Some(Command::Train(args)) => cli::train::run(&args, cli.seed),
+        Some(Command::Minimize(args)) => cli::minimize::run(&args, format, color),
+        Some(Command::Canonicalize(args)) => cli::canonicalize::run(&args, format, color),
+        Some(Command::Generate(mut args)) => {
+            args.difficulty = args.difficulty.or(config.generate.difficulty);
+            args.symmetry = args.symmetry.or(config.generate.symmetry);
+            args.weights = config.rating.weights();
+            cli::generate::run(&args, cli.seed, format, color)
+        }
+        Some(Command::Daily(args)) => cli::daily::run(&args, cli.seed, format, color),
+        Some(Command::Db(args)) => cli::db::run(&args, format, color),
+        Some(Command::Bench(args)) => cli::bench::run(&args, format),
+        Some(Command::Compare(args)) => cli::compare::run(&args, format),
+        Some(Command::Calibrate(mut args)) => {
+            args.weights = config.rating.weights();
+            cli::calibrate::run(&args, format)
+        }
+        Some(Command::Convert(args)) => cli::convert::run(&args),
+        Some(Command::Render(args)) => cli::render::run(&args),
+        Some(Command::Worksheet(mut args)) => {
+            args.difficulty = args.difficulty.or(config.generate.difficulty);
+            cli::worksheet::run(&args, cli.seed)
+        }
+        #[cfg(feature = "server")]
+        Some(Command::Serve(args)) => cli::serve::run(&args),
+        None => {
+            let args = cli::solve::SolveArgs {
+                max_nodes: config.solve.max_nodes,
+                max_depth: config.solve.max_depth,
+                max_time: config.solve.max_time,
+                ..cli::solve::SolveArgs::default()
+            };
+            cli::solve::run(&args, format, color)
+        }
+    };
+
+    // Stop quietly when output is piped into a program that exits early,
+    // such as head
+    match result {
+        Ok(exit) => exit.into(),
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Exit::Success.into(),
+        Err(e) if cli::input::is_not_text(&e) => {
+            eprintln!("Error: {}", e);
+            Exit::ParseError.into()
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            Exit::IoError.into()
+        }
+    }
+}
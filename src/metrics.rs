@@ -0,0 +1,156 @@
+//! The metrics of the HTTP service, written in the Prometheus text format
+//! for its `/metrics` endpoint.
+
+use alloc::{collections::BTreeMap, string::String};
+use core::{fmt::Write, time::Duration};
+
+/// The upper bounds, in seconds, of the buckets of the request duration
+/// histograms.
+const BUCKETS: [f64; 12] = [
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.1, 0.5, 2.5, 10.0,
+];
+
+/// How long the requests to an endpoint took.
+#[derive(Default)]
+struct Histogram {
+    /// The requests that took no longer than each bucket's bound, not
+    /// counting those in earlier buckets.
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    /// The total number of seconds taken.
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if let Some(bucket) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// Everything the service has counted since it started.
+pub(crate) struct Metrics {
+    /// Requests answered by (endpoint, status).
+    requests: BTreeMap<(String, u16), u64>,
+    durations: BTreeMap<String, Histogram>,
+    timeouts: u64,
+    not_unique: u64,
+}
+
+impl Metrics {
+    pub(crate) const fn new() -> Self {
+        Metrics {
+            requests: BTreeMap::new(),
+            durations: BTreeMap::new(),
+            timeouts: 0,
+            not_unique: 0,
+        }
+    }
+
+    /// Count a request to endpoint that was answered with status after
+    /// elapsed.
+    pub(crate) fn record_request(&mut self, endpoint: &str, status: u16, elapsed: Duration) {
+        *self
+            .requests
+            .entry((String::from(endpoint), status))
+            .or_default() += 1;
+        self.durations
+            .entry(String::from(endpoint))
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Count a request that was given up on because the solver took too
+    /// long.
+    pub(crate) fn record_timeout(&mut self) {
+        self.timeouts += 1;
+    }
+
+    /// Count a puzzle that was found to have more than one solution.
+    pub(crate) fn record_not_unique(&mut self) {
+        self.not_unique += 1;
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub(crate) fn render(&self) -> String {
+        let mut text = String::new();
+        header(
+            &mut text,
+            "sudoku_requests_total",
+            "counter",
+            "Requests answered, by endpoint and status.",
+        );
+        for ((endpoint, status), count) in &self.requests {
+            writeln!(
+                text,
+                "sudoku_requests_total{{endpoint=\"{}\",status=\"{}\"}} {}",
+                endpoint, status, count
+            )
+            .unwrap();
+        }
+
+        header(
+            &mut text,
+            "sudoku_request_duration_seconds",
+            "histogram",
+            "How long requests took to answer, by endpoint.",
+        );
+        for (endpoint, histogram) in &self.durations {
+            let name = "sudoku_request_duration_seconds";
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(histogram.buckets.iter()) {
+                cumulative += count;
+                writeln!(
+                    text,
+                    "{}_bucket{{endpoint=\"{}\",le=\"{}\"}} {}",
+                    name, endpoint, bound, cumulative
+                )
+                .unwrap();
+            }
+            writeln!(
+                text,
+                "{}_bucket{{endpoint=\"{}\",le=\"+Inf\"}} {}",
+                name, endpoint, histogram.count
+            )
+            .unwrap();
+            writeln!(
+                text,
+                "{}_sum{{endpoint=\"{}\"}} {}",
+                name, endpoint, histogram.sum
+            )
+            .unwrap();
+            writeln!(
+                text,
+                "{}_count{{endpoint=\"{}\"}} {}",
+                name, endpoint, histogram.count
+            )
+            .unwrap();
+        }
+
+        header(
+            &mut text,
+            "sudoku_timeouts_total",
+            "counter",
+            "Requests given up on because the solver took too long.",
+        );
+        writeln!(text, "sudoku_timeouts_total {}", self.timeouts).unwrap();
+
+        header(
+            &mut text,
+            "sudoku_not_unique_total",
+            "counter",
+            "Puzzles checked that have more than one solution.",
+        );
+        writeln!(text, "sudoku_not_unique_total {}", self.not_unique).unwrap();
+        text
+    }
+}
+
+/// Write the HELP and TYPE lines of a metric.
+fn header(text: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(text, "# HELP {} {}", name, help).unwrap();
+    writeln!(text, "# TYPE {} {}", name, kind).unwrap();
+}
@@ -0,0 +1,207 @@
+//! Enumerating the minimal puzzles of a solution grid: the puzzles made of
+//! some of its squares that have it as their only solution, and from which
+//! no square can be removed without allowing another.
+//!
+//! The search starts from the full grid and removes squares in increasing
+//! order, so each set of squares is visited at most once. Removing a square
+//! never makes a solution unique again, so a square whose removal allows a
+//! second solution is never removed below that point, and a branch is
+//! abandoned as soon as it cannot get down to the clue bound. Even so, the
+//! search is exhaustive: with a bound above the low 20s it will run for far
+//! longer than anyone will wait, so take as many puzzles as are needed.
+//!
+//! ```
+//! # use sudoku::{minimal, puzzle::SudokuPuzzle};
+//! let solution = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap()
+//! .solve()
+//! .unwrap();
+//!
+//! let mut reports = 0;
+//! let puzzles: Vec<_> = minimal::minimal_puzzles(&solution, 30)
+//!     .with_progress(10, |_| reports += 1)
+//!     .take(2)
+//!     .collect();
+//! for puzzle in &puzzles {
+//!     assert_eq!(Some(solution), puzzle.solve());
+//!     assert_eq!(puzzle.to_vec(), puzzle.minimized().unwrap().to_vec());
+//! }
+//! assert!(reports > 0);
+//! ```
+
+use crate::{puzzle::SudokuPuzzle, solution::Solution};
+use alloc::{boxed::Box, vec::Vec};
+use core::convert::TryFrom;
+
+/// Every square of the grid, as a mask.
+const FULL: u128 = (1 << 81) - 1;
+
+/// A function to call with the progress of a search.
+type Report<'a> = Box<dyn FnMut(&Progress) + 'a>;
+
+/// How far a search for minimal puzzles has got.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Progress {
+    /// How many sets of squares have been visited.
+    pub visited: u64,
+    /// How many of those were abandoned because no puzzle within the clue
+    /// bound can be made by removing more squares.
+    pub pruned: u64,
+    /// How many minimal puzzles have been found.
+    pub found: u64,
+}
+
+/// An iterator over the minimal puzzles of a solution grid with at most a
+/// given number of clues, in a fixed order.
+pub struct MinimalPuzzles<'a> {
+    solution: Vec<u8>,
+    max_clues: usize,
+    /// The sets of squares whose subsets are still to be visited.
+    stack: Vec<Node>,
+    progress: Progress,
+    /// How often to report progress, in visits, and who to report it to.
+    report: Option<(u64, Report<'a>)>,
+}
+
+/// A set of squares in the search. Masks have bit i set for square i.
+struct Node {
+    /// The squares kept.
+    clues: u128,
+    /// Squares whose removal is known to allow a second solution.
+    necessary: u128,
+    /// The squares that can still be removed, in increasing order.
+    removable: Vec<u8>,
+    /// The index in removable of the next square to remove.
+    next: usize,
+}
+
+/// Search for every minimal puzzle of solution with at most max_clues
+/// clues. No puzzle has fewer than 17.
+pub fn minimal_puzzles<'a>(solution: &Solution, max_clues: usize) -> MinimalPuzzles<'a> {
+    // Any one square of a full grid can be removed
+    let root = Node {
+        clues: FULL,
+        necessary: 0,
+        removable: (0..81).collect(),
+        next: 0,
+    };
+    MinimalPuzzles {
+        solution: solution.to_vec(),
+        max_clues,
+        stack: alloc::vec![root],
+        progress: Progress::default(),
+        report: None,
+    }
+}
+
+impl<'a> MinimalPuzzles<'a> {
+    /// Call report with the progress of the search each time it has visited
+    /// another `every` sets of squares, so that long searches can show they
+    /// are still going between puzzles.
+    pub fn with_progress(mut self, every: u64, report: impl FnMut(&Progress) + 'a) -> Self {
+        self.report = Some((every.max(1), Box::new(report)));
+        self
+    }
+
+    /// How far the search has got.
+    pub fn progress(&self) -> Progress {
+        self.progress
+    }
+
+    /// The puzzle made of the squares in clues.
+    fn puzzle(&self, clues: u128) -> SudokuPuzzle {
+        let nums: Vec<u8> = (0..81)
+            .map(|i| {
+                if clues & (1 << i) != 0 {
+                    self.solution[i]
+                } else {
+                    0
+                }
+            })
+            .collect();
+        SudokuPuzzle::try_from(&nums[..]).expect("squares of a solution are in [1,9]")
+    }
+
+    fn is_unique(&self, clues: u128) -> bool {
+        self.puzzle(clues).count_solutions(2) == 1
+    }
+
+    /// Visit the set of squares clues, from which only the squares in
+    /// candidates might still be removed. Keep it to visit its subsets if
+    /// they might lead to a puzzle within the bound, and return its puzzle
+    /// if it is minimal and within the bound.
+    fn visit(
+        &mut self,
+        clues: u128,
+        mut necessary: u128,
+        candidates: &[u8],
+    ) -> Option<SudokuPuzzle> {
+        self.progress.visited += 1;
+        if let Some((every, report)) = &mut self.report {
+            if self.progress.visited.is_multiple_of(*every) {
+                report(&self.progress);
+            }
+        }
+
+        let mut removable = Vec::new();
+        for square in candidates {
+            let bit = 1 << square;
+            if self.is_unique(clues & !bit) {
+                removable.push(*square);
+            } else {
+                necessary |= bit;
+            }
+        }
+        let count = clues.count_ones() as usize;
+        if count - removable.len() > self.max_clues {
+            self.progress.pruned += 1;
+            return None;
+        }
+
+        // Squares kept earlier in the order were not checked above
+        let unchecked = clues & !necessary;
+        let minimal = removable.is_empty()
+            && (0..81)
+                .filter(|i| unchecked & (1 << i) != 0)
+                .all(|square| !self.is_unique(clues & !(1 << square)));
+
+        if !removable.is_empty() {
+            self.stack.push(Node {
+                clues,
+                necessary,
+                removable,
+                next: 0,
+            });
+        }
+        if minimal && count <= self.max_clues {
+            self.progress.found += 1;
+            Some(self.puzzle(clues))
+        } else {
+            None
+        }
+    }
+}
+
+impl Iterator for MinimalPuzzles<'_> {
+    type Item = SudokuPuzzle;
+
+    fn next(&mut self) -> Option<SudokuPuzzle> {
+        loop {
+            let node = self.stack.last_mut()?;
+            if node.next == node.removable.len() {
+                self.stack.pop();
+                continue;
+            }
+            let square = node.removable[node.next];
+            node.next += 1;
+            let clues = node.clues & !(1 << square);
+            let necessary = node.necessary;
+            let candidates = node.removable[node.next..].to_vec();
+            if let Some(puzzle) = self.visit(clues, necessary, &candidates) {
+                return Some(puzzle);
+            }
+        }
+    }
+}
@@ -1,4 +1,14 @@
-use std::fmt;
+use crate::{
+    error::{check_square, Error},
+    units::PEERS,
+};
+use alloc::{format, string::String};
+use core::{
+    array, fmt,
+    iter::Enumerate,
+    ops::{Index, IndexMut},
+    slice,
+};
 
 /// A 9x9 array of `Option<T>`'s
 #[derive(Clone)]
@@ -14,10 +24,23 @@ impl<T> From<[Option<T>; 81]> for NineByNine<T> {
     }
 }
 
-impl<T: Copy> NineByNine<T> {
+impl<T> NineByNine<T> {
     /// Create a new 9x9 that consists of only `None` elements.
     pub fn new() -> Self {
-        Self { data: [None; 81] }
+        Self::from_fn(|_, _| None)
+    }
+
+    /// Create a new 9x9 whose element at (row, col) is `f(row, col)`.
+    ///
+    /// ```
+    /// # use sudoku::nine_by_nine::NineByNine;
+    /// let grid = NineByNine::from_fn(|row, col| Some(vec![row; col]));
+    /// assert_eq!(Some(&vec![2, 2, 2]), grid.get(2, 3));
+    /// ```
+    pub fn from_fn<F: FnMut(usize, usize) -> Option<T>>(mut f: F) -> Self {
+        Self {
+            data: array::from_fn(|i| f(i / 9, i % 9)),
+        }
     }
 
     /// Indicates the number of None elements in this 9x9.
@@ -28,23 +51,232 @@ impl<T: Copy> NineByNine<T> {
     /// Get the data element at (row, col).
     /// row and column must each be in the range [0, 9).
     pub fn get(&self, row: usize, col: usize) -> Option<&T> {
-        assert!(row < 9);
-        assert!(col < 9);
-
-        self.data[row * 9 + col].as_ref()
+        self[(row, col)].as_ref()
     }
 
     /// Set the data element at (row, col);
     /// row and column must each be in the range [0, 9).
     pub fn set(&mut self, row: usize, col: usize, val: Option<T>) {
+        self[(row, col)] = val;
+    }
+
+    /// Get the data element at (row, col), or `None` if (row, col) is
+    /// outside of the 9x9.
+    ///
+    /// ```
+    /// # use sudoku::nine_by_nine::NineByNine;
+    /// let mut grid = NineByNine::new();
+    /// grid.set(0, 0, Some(1));
+    /// assert_eq!(Some(&Some(1)), grid.get_checked(0, 0));
+    /// assert_eq!(Some(&None), grid.get_checked(0, 1));
+    /// assert_eq!(None, grid.get_checked(0, 9));
+    /// ```
+    pub fn get_checked(&self, row: usize, col: usize) -> Option<&Option<T>> {
+        if row < 9 && col < 9 {
+            Some(&self[(row, col)])
+        } else {
+            None
+        }
+    }
+
+    /// Set the data element at (row, col), or return an error if (row, col)
+    /// is outside of the 9x9.
+    ///
+    /// ```
+    /// # use sudoku::nine_by_nine::NineByNine;
+    /// let mut grid = NineByNine::new();
+    /// assert!(grid.try_set(8, 8, Some(1)).is_ok());
+    /// assert!(grid.try_set(9, 8, Some(1)).is_err());
+    /// ```
+    pub fn try_set(&mut self, row: usize, col: usize, val: Option<T>) -> Result<(), Error> {
+        check_square(row, col)?;
+        self[(row, col)] = val;
+        Ok(())
+    }
+
+    /// Create a new 9x9 whose element at (row, col) is `f(row, col, v)`,
+    /// where v is the element of this 9x9 at (row, col).
+    ///
+    /// ```
+    /// # use sudoku::nine_by_nine::NineByNine;
+    /// let mut grid = NineByNine::new();
+    /// grid.set(1, 2, Some(5));
+    /// let doubled = grid.map(|_, _, n| n.map(|n| n * 2));
+    /// assert_eq!(Some(&10), doubled.get(1, 2));
+    /// assert_eq!(None, doubled.get(0, 0));
+    /// ```
+    pub fn map<U, F>(&self, mut f: F) -> NineByNine<U>
+    where
+        F: FnMut(usize, usize, Option<&T>) -> Option<U>,
+    {
+        NineByNine::from_fn(|row, col| f(row, col, self.get(row, col)))
+    }
+
+    /// Create a new 9x9 that pairs the elements of this 9x9 with the elements
+    /// of other. A square is `None` unless it is filled in both 9x9s.
+    ///
+    /// ```
+    /// # use sudoku::nine_by_nine::NineByNine;
+    /// let mut nums = NineByNine::new();
+    /// let mut names = NineByNine::new();
+    /// nums.set(0, 0, Some(1));
+    /// names.set(0, 0, Some("one"));
+    /// names.set(0, 1, Some("two"));
+    ///
+    /// let zipped = nums.zip(&names);
+    /// assert_eq!(Some(&(&1, &"one")), zipped.get(0, 0));
+    /// assert_eq!(None, zipped.get(0, 1));
+    /// ```
+    pub fn zip<'a, U>(&'a self, other: &'a NineByNine<U>) -> NineByNine<(&'a T, &'a U)> {
+        NineByNine::from_fn(|row, col| self.get(row, col).zip(other.get(row, col)))
+    }
+
+    /// Iterate over the elements of this 9x9 and their (row, col)
+    /// coordinates, in row-major order.
+    ///
+    /// ```
+    /// # use sudoku::nine_by_nine::NineByNine;
+    /// let mut grid = NineByNine::new();
+    /// grid.set(4, 7, Some('x'));
+    /// let filled: Vec<_> = grid.iter().filter(|(_, v)| v.is_some()).collect();
+    /// assert_eq!(vec![((4, 7), &Some('x'))], filled);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.data.iter().enumerate(),
+        }
+    }
+
+    /// Iterate over mutable references to the elements of this 9x9 and
+    /// their (row, col) coordinates, in row-major order.
+    ///
+    /// ```
+    /// # use sudoku::nine_by_nine::NineByNine;
+    /// let mut grid = NineByNine::new();
+    /// for ((row, col), v) in grid.iter_mut() {
+    ///     *v = Some(row * 9 + col);
+    /// }
+    /// assert_eq!(Some(&80), grid.get(8, 8));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.data.iter_mut().enumerate(),
+        }
+    }
+}
+
+/// Convert the linear index of an element into its (row, col) coordinates.
+fn coordinates(i: usize) -> (usize, usize) {
+    (i / 9, i % 9)
+}
+
+/// An iterator over the elements of a NineByNine and their coordinates.
+pub struct Iter<'a, T> {
+    inner: Enumerate<slice::Iter<'a, Option<T>>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = ((usize, usize), &'a Option<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(i, v)| (coordinates(i), v))
+    }
+}
+
+/// An iterator over mutable references to the elements of a NineByNine
+/// and their coordinates.
+pub struct IterMut<'a, T> {
+    inner: Enumerate<slice::IterMut<'a, Option<T>>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = ((usize, usize), &'a mut Option<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(i, v)| (coordinates(i), v))
+    }
+}
+
+/// An iterator that moves the elements of a NineByNine out alongside
+/// their coordinates.
+pub struct IntoIter<T> {
+    inner: Enumerate<array::IntoIter<Option<T>, 81>>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = ((usize, usize), Option<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(i, v)| (coordinates(i), v))
+    }
+}
+
+impl<T> IntoIterator for NineByNine<T> {
+    type Item = ((usize, usize), Option<T>);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            inner: IntoIterator::into_iter(self.data).enumerate(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a NineByNine<T> {
+    type Item = ((usize, usize), &'a Option<T>);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut NineByNine<T> {
+    type Item = ((usize, usize), &'a mut Option<T>);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Index<(usize, usize)> for NineByNine<T> {
+    type Output = Option<T>;
+
+    /// The element at (row, col).
+    /// row and column must each be in the range [0, 9).
+    ///
+    /// ```
+    /// # use sudoku::nine_by_nine::NineByNine;
+    /// let mut grid = NineByNine::new();
+    /// grid[(3, 4)] = Some(7);
+    /// assert_eq!(Some(7), grid[(3, 4)]);
+    /// assert_eq!(None, grid[(4, 3)]);
+    /// ```
+    fn index(&self, (row, col): (usize, usize)) -> &Option<T> {
+        assert!(row < 9);
+        assert!(col < 9);
+
+        &self.data[row * 9 + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for NineByNine<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Option<T> {
         assert!(row < 9);
         assert!(col < 9);
 
-        self.data[row * 9 + col] = val;
+        &mut self.data[row * 9 + col]
+    }
+}
+
+impl<T> Default for NineByNine<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl<T: fmt::Debug + Copy> fmt::Debug for NineByNine<T> {
+impl<T: fmt::Debug> fmt::Debug for NineByNine<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let to_string = |n: Option<&T>| match n {
             Some(n) => format!("| {:?} ", n),
@@ -57,10 +289,34 @@ impl<T: fmt::Debug + Copy> fmt::Debug for NineByNine<T> {
             for col in 0..9 {
                 row_str.push_str(&to_string(self.get(row, col)))
             }
-            separator = std::iter::repeat('-').take(row_str.len() + 1).collect();
+            separator = "-".repeat(row_str.len() + 1);
             write!(f, "{}\n{}|\n", separator, row_str).unwrap();
         }
 
         write!(f, "{}", separator)
     }
 }
+
+/// The coordinates of the 20 squares that share a row, column, or 3x3
+/// square with (row, col), not including (row, col) itself.
+/// row and column must each be in the range [0, 9).
+///
+/// ```
+/// # use sudoku::nine_by_nine::peers;
+/// let peers = peers(4, 4);
+/// assert!(peers.contains(&(4, 0)));
+/// assert!(peers.contains(&(0, 4)));
+/// assert!(peers.contains(&(3, 5)));
+/// assert!(!peers.contains(&(4, 4)));
+/// assert!(!peers.contains(&(0, 0)));
+/// ```
+pub fn peers(row: usize, col: usize) -> [(usize, usize); 20] {
+    assert!(row < 9);
+    assert!(col < 9);
+
+    let mut peers = [(0, 0); 20];
+    for (peer, square) in peers.iter_mut().zip(PEERS[row * 9 + col].iter()) {
+        *peer = coordinates(*square);
+    }
+    peers
+}
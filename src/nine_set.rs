@@ -1,16 +1,74 @@
-use std::fmt::Debug;
+use alloc::vec::Vec;
+use core::{
+    fmt::Debug,
+    iter::FromIterator,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not},
+};
 
 /// A Set collection that can hold numbers in the range [1,9].
 #[derive(PartialEq, Clone, Copy)]
 pub struct NineSet {
-    contents: [bool; 9],
+    /// Bit n - 1 is set when n is a member of the set.
+    bits: u16,
 }
 
+/// The bits used to represent the numbers [1,9].
+const ALL_BITS: u16 = 0b1_1111_1111;
+
 impl NineSet {
     /// Create and return an empty NineSet
     pub fn empty() -> Self {
-        let contents = [false; 9];
-        NineSet { contents }
+        NineSet { bits: 0 }
+    }
+
+    /// Create and return a NineSet containing every number in the range [1,9]
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// assert_eq!(9, NineSet::full().size());
+    /// ```
+    pub fn full() -> Self {
+        NineSet { bits: ALL_BITS }
+    }
+
+    /// Create a NineSet from its bitmask, where bit n - 1 is set when n is
+    /// a member. Bits above the ninth are ignored.
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        NineSet {
+            bits: bits & ALL_BITS,
+        }
+    }
+
+    /// The bitmask of this NineSet, where bit n - 1 is set when n is a member.
+    pub(crate) fn bits(&self) -> u16 {
+        self.bits
+    }
+
+    /// Indicates whether this NineSet contains no numbers.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let mut set = NineSet::empty();
+    /// assert!(set.is_empty());
+    /// set.add(5);
+    /// assert!(!set.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Indicates whether this NineSet contains every number in the range [1,9].
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let mut set = NineSet::full();
+    /// assert!(set.is_full());
+    /// set.remove(5);
+    /// assert!(!set.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.bits == ALL_BITS
     }
 
     /// Add n to this NineSet. n must be in the range [1,9]
@@ -24,7 +82,34 @@ impl NineSet {
     pub fn add(&mut self, n: u8) {
         assert!(n >= 1);
         assert!(n <= 9);
-        self.contents[(n - 1) as usize] = true;
+        self.bits |= 1 << (n - 1);
+    }
+
+    /// Remove n from this NineSet, if it is present. n must be in the range [1,9]
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let mut set = NineSet::from(vec![4, 5]);
+    /// set.remove(5);
+    /// assert!(!set.contains(5));
+    /// assert!(set.contains(4));
+    /// ```
+    pub fn remove(&mut self, n: u8) {
+        assert!(n >= 1);
+        assert!(n <= 9);
+        self.bits &= !(1 << (n - 1));
+    }
+
+    /// Remove every number from this NineSet.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let mut set = NineSet::from(vec![1, 2, 3]);
+    /// set.clear();
+    /// assert_eq!(0, set.size());
+    /// ```
+    pub fn clear(&mut self) {
+        self.bits = 0;
     }
 
     /// Indicates whether this NineSet contains n.
@@ -37,7 +122,7 @@ impl NineSet {
     /// assert!(!set.contains(6));
     /// ```
     pub fn contains(&self, n: u8) -> bool {
-        n >= 1 && n <= 9 && self.contents[(n - 1) as usize]
+        (1..=9).contains(&n) && self.bits & (1 << (n - 1)) != 0
     }
 
     /// Returns the number of unique numbers contained in this set.
@@ -47,10 +132,29 @@ impl NineSet {
     /// let mut set = NineSet::empty();
     /// set.add(5);
     /// set.add(7);
-    /// assert!(2, set.size());
+    /// assert_eq!(2, set.size());
     /// ```
     pub fn size(&self) -> usize {
-        self.contents.iter().filter(|x| **x).count()
+        self.bits.count_ones() as usize
+    }
+
+    /// Returns the only number in this set, or `None` if the set
+    /// does not contain exactly one number.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let mut set = NineSet::empty();
+    /// set.add(5);
+    /// assert_eq!(Some(5), set.single());
+    /// set.add(7);
+    /// assert_eq!(None, set.single());
+    /// ```
+    pub fn single(&self) -> Option<u8> {
+        if self.bits.count_ones() == 1 {
+            Some(self.bits.trailing_zeros() as u8 + 1)
+        } else {
+            None
+        }
     }
 
     /// Creates and returns a Vec containing the numbers contained in this set.
@@ -63,13 +167,19 @@ impl NineSet {
     /// assert_eq!(vec![1, 9], set.to_vec());
     /// ```
     pub fn to_vec(&self) -> Vec<u8> {
-        let mut vec = Vec::new();
-        for n in 1..=9 {
-            if self.contains(n) {
-                vec.push(n as u8);
-            }
-        }
-        vec
+        self.iter().collect()
+    }
+
+    /// Iterates over the numbers contained in this set, in ascending order.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let set: NineSet = vec![3, 1].into_iter().collect();
+    /// let doubled: Vec<u8> = set.iter().map(|n| n * 2).collect();
+    /// assert_eq!(vec![2, 6], doubled);
+    /// ```
+    pub fn iter(&self) -> Iter {
+        Iter { bits: self.bits }
     }
 
     /// Creates and returns a set that contains all and only the numbers
@@ -84,11 +194,70 @@ impl NineSet {
     /// assert_eq!(vec![2, 3, 4, 5, 6, 7, 8], set.complement().to_vec());
     /// ```
     pub fn complement(&self) -> Self {
-        let mut contents = [true; 9];
-        for i in 0..contents.len() {
-            contents[i] = !self.contents[i];
+        NineSet {
+            bits: !self.bits & ALL_BITS,
+        }
+    }
+
+    /// Creates and returns a set containing the numbers that are members
+    /// of both this set and other.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let set1 = NineSet::from(vec![1, 2, 3]);
+    /// let set2 = NineSet::from(vec![2, 3, 4]);
+    /// assert_eq!(vec![2, 3], set1.intersection(&set2).to_vec());
+    /// ```
+    pub fn intersection(&self, other: &NineSet) -> Self {
+        NineSet {
+            bits: self.bits & other.bits,
+        }
+    }
+
+    /// Creates and returns a set containing the numbers that are members
+    /// of this set but not of other.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let set1 = NineSet::from(vec![1, 2, 3]);
+    /// let set2 = NineSet::from(vec![2, 3, 4]);
+    /// assert_eq!(vec![1], set1.difference(&set2).to_vec());
+    /// ```
+    pub fn difference(&self, other: &NineSet) -> Self {
+        NineSet {
+            bits: self.bits & !other.bits,
+        }
+    }
+
+    /// Creates and returns a set containing the numbers that are members
+    /// of exactly one of this set and other.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let set1 = NineSet::from(vec![1, 2, 3]);
+    /// let set2 = NineSet::from(vec![2, 3, 4]);
+    /// assert_eq!(vec![1, 4], set1.symmetric_difference(&set2).to_vec());
+    /// ```
+    pub fn symmetric_difference(&self, other: &NineSet) -> Self {
+        NineSet {
+            bits: self.bits ^ other.bits,
+        }
+    }
+
+    /// Remove every number for which keep returns false from this set.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let mut set = NineSet::from(vec![1, 2, 3, 4]);
+    /// set.retain(|n| n % 2 == 0);
+    /// assert_eq!(vec![2, 4], set.to_vec());
+    /// ```
+    pub fn retain<F: FnMut(u8) -> bool>(&mut self, mut keep: F) {
+        for n in 1..=9 {
+            if self.contains(n) && !keep(n) {
+                self.remove(n);
+            }
         }
-        NineSet { contents }
     }
 }
 
@@ -105,15 +274,83 @@ impl From<Vec<u8>> for NineSet {
     }
 }
 
+impl FromIterator<u8> for NineSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(nums: I) -> Self {
+        let mut set = NineSet::empty();
+        set.extend(nums);
+        set
+    }
+}
+
+impl Extend<u8> for NineSet {
+    /// Add each of nums to this NineSet. Each must be in the range [1,9]
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let mut set = NineSet::from(vec![1]);
+    /// set.extend(vec![2, 3]);
+    /// assert_eq!(vec![1, 2, 3], set.to_vec());
+    /// ```
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, nums: I) {
+        for n in nums {
+            self.add(n);
+        }
+    }
+}
+
+impl IntoIterator for NineSet {
+    type Item = u8;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &NineSet {
+    type Item = u8;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+/// An iterator over the numbers in a NineSet, in ascending order.
+#[derive(Clone)]
+pub struct Iter {
+    bits: u16,
+}
+
+impl Iterator for Iter {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.bits == 0 {
+            return None;
+        }
+        let n = self.bits.trailing_zeros() as u8 + 1;
+        self.bits &= self.bits - 1;
+        Some(n)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
 impl Debug for NineSet {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "NineSet:")?;
-        f.debug_list().entries(self.to_vec().iter()).finish()
+        f.debug_list().entries(self.iter()).finish()
     }
 }
 
 /// Creates and returns a set containing all the numbers contained
-/// by at least one of the sets in the provided list of sets.
+/// by at least one of the provided sets.
 ///
 /// ```
 /// # use sudoku::nine_set::{NineSet, union};
@@ -123,15 +360,90 @@ impl Debug for NineSet {
 /// set1.add(2);
 /// set2.add(2);
 /// set2.add(3);
-/// let union = union(vec![set1, set2]).to_vec()
+/// let union = union(vec![set1, set2]).to_vec();
 /// assert_eq!(vec![1, 2, 3], union);
 /// ```
-pub fn union(sets: Vec<NineSet>) -> NineSet {
-    let mut union = NineSet::empty();
-    for n in 1..=9 {
-        if sets.iter().any(|s| s.contains(n)) {
-            union.add(n);
+pub fn union<I: IntoIterator<Item = NineSet>>(sets: I) -> NineSet {
+    let bits = sets.into_iter().fold(0, |bits, set| bits | set.bits);
+    NineSet { bits }
+}
+
+impl BitOr for NineSet {
+    type Output = NineSet;
+
+    /// The union of two sets.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let set = NineSet::from(vec![1, 2]) | NineSet::from(vec![2, 3]);
+    /// assert_eq!(vec![1, 2, 3], set.to_vec());
+    /// ```
+    fn bitor(self, other: NineSet) -> NineSet {
+        NineSet {
+            bits: self.bits | other.bits,
         }
     }
-    union
+}
+
+impl BitAnd for NineSet {
+    type Output = NineSet;
+
+    /// The intersection of two sets.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let set = NineSet::from(vec![1, 2]) & NineSet::from(vec![2, 3]);
+    /// assert_eq!(vec![2], set.to_vec());
+    /// ```
+    fn bitand(self, other: NineSet) -> NineSet {
+        self.intersection(&other)
+    }
+}
+
+impl BitXor for NineSet {
+    type Output = NineSet;
+
+    /// The symmetric difference of two sets.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let set = NineSet::from(vec![1, 2]) ^ NineSet::from(vec![2, 3]);
+    /// assert_eq!(vec![1, 3], set.to_vec());
+    /// ```
+    fn bitxor(self, other: NineSet) -> NineSet {
+        self.symmetric_difference(&other)
+    }
+}
+
+impl Not for NineSet {
+    type Output = NineSet;
+
+    /// The complement of a set.
+    ///
+    /// ```
+    /// # use sudoku::nine_set::NineSet;
+    /// let set = !NineSet::from(vec![1, 2, 3, 4, 5, 6, 7]);
+    /// assert_eq!(vec![8, 9], set.to_vec());
+    /// ```
+    fn not(self) -> NineSet {
+        self.complement()
+    }
+}
+
+impl BitOrAssign for NineSet {
+    fn bitor_assign(&mut self, other: NineSet) {
+        *self = *self | other;
+    }
+}
+
+impl BitAndAssign for NineSet {
+    fn bitand_assign(&mut self, other: NineSet) {
+        *self = *self & other;
+    }
+}
+
+impl BitXorAssign for NineSet {
+    fn bitxor_assign(&mut self, other: NineSet) {
+        *self = *self ^ other;
+    }
 }
@@ -0,0 +1,42 @@
+//! The types most programs need, so that one `use` brings them all in.
+//!
+//! ```
+//! use sudoku::prelude::*;
+//!
+//! fn hardest(puzzles: &[&str]) -> Result<Option<Difficulty>, Error> {
+//!     let mut hardest = None;
+//!     for source in puzzles {
+//!         let puzzle = SudokuPuzzle::from_string(source)?;
+//!         hardest = hardest.max(puzzle.difficulty());
+//!     }
+//!     Ok(hardest)
+//! }
+//!
+//! let puzzles = [
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//!     "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+//! ];
+//! assert!(hardest(&puzzles).unwrap().is_some());
+//! assert!(hardest(&["12"]).is_err());
+//! ```
+
+pub use crate::{
+    board::Board,
+    builder::SudokuPuzzleBuilder,
+    error::{Error, GenerationError, LimitExceeded, ParseError, PlacementError, TransformError},
+    grid::Grid,
+    hint::Hint,
+    history::History,
+    limits::SolverLimits,
+    nine_set::NineSet,
+    puzzle::{Ambiguity, CellDiff, Mistake, SudokuPuzzle, Viability},
+    rating::{Difficulty, Rating},
+    replay::Recording,
+    search::Solutions,
+    session::{Session, Status},
+    solution::Solution,
+    step::Step,
+    sukaku::Sukaku,
+    transform::{Axis, Transform},
+    units::Unit,
+};
@@ -1,254 +1,1231 @@
 use crate::{
+    board::{self, Trail},
+    canonical,
+    error::{check_number, check_square, Error, LimitExceeded, ParseError, PlacementError},
+    hint::Hint,
+    limits::SolverLimits,
     nine_by_nine::NineByNine,
-    nine_set::{union, NineSet},
+    nine_set::NineSet,
+    rating::{Difficulty, Rating, RatingConfig},
+    search::Solutions,
+    solution::Solution,
+    step::Step,
+    transform::{self, Transform},
+    units::{PEERS, SQUARE_UNITS, UNITS},
 };
-use std::fmt;
+#[cfg(feature = "async")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "async")]
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::{convert::TryFrom, fmt};
+use rand::Rng;
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+use crate::simd;
 
 /// A Sudoku puzzle.
 #[derive(Clone)]
 pub struct SudokuPuzzle {
-    nums: NineByNine<u8>,
+    /// A linear, row-major list of the 81 squares, where 0 indicates an
+    /// empty square.
+    nums: [u8; 81],
+    /// The squares that were filled when the puzzle was created, in the
+    /// same layout as `nums`.
+    givens: [u8; 81],
+    marks: NineByNine<NineSet>,
+    /// The numbers each square could be. Kept up to date as squares are
+    /// filled and emptied, rather than being rebuilt from scratch.
+    candidates: NineByNine<NineSet>,
+}
+
+/// A square whose number differs between two puzzles.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CellDiff {
+    pub row: usize,
+    pub col: usize,
+    /// The number in the puzzle that `diff` was called on.
+    pub this: Option<u8>,
+    /// The number in the puzzle that was passed to `diff`.
+    pub other: Option<u8>,
+}
+
+/// A square the player filled with a number that doesn't match the
+/// solution. The right number is left out, so that it isn't given away.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Mistake {
+    pub row: usize,
+    pub col: usize,
+    /// The number the player entered.
+    pub entered: u8,
+}
+
+/// Two different solutions of the same puzzle, which show that it does not
+/// have a unique solution.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ambiguity {
+    pub first: Solution,
+    pub second: Solution,
+    /// The squares where the two solutions differ, in row-major order.
+    pub squares: Vec<(usize, usize)>,
 }
 
+/// Whether a number can go in a square of a puzzle's solution.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Viability {
+    /// Some solution has the number there.
+    Viable,
+    /// No solution has the number there.
+    Impossible,
+    /// The search ran out of guesses before finding out.
+    Unknown,
+}
+
+/// How many guesses [`SudokuPuzzle::is_candidate_viable`] makes before giving
+/// up. Few puzzles need more than a few hundred.
+const VIABILITY_GUESSES: usize = 10_000;
+
 impl SudokuPuzzle {
+    /// Create a new Puzzle with every space empty.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut puzzle = SudokuPuzzle::empty();
+    /// assert_eq!(None, puzzle.get(4, 4));
+    /// puzzle.set(4, 4, Some(5)).unwrap();
+    /// assert_eq!(Some(5), puzzle.get(4, 4));
+    /// ```
+    pub fn empty() -> Self {
+        SudokuPuzzle {
+            nums: [0; 81],
+            givens: [0; 81],
+            marks: NineByNine::new(),
+            candidates: NineByNine::from_fn(|_, _| Some(NineSet::full())),
+        }
+    }
+
+    /// Create a puzzle whose givens are the numbers of solution at the
+    /// squares that are `true` in mask. Every other square is empty.
+    ///
+    /// ```
+    /// # use sudoku::{nine_by_nine::NineByNine, puzzle::SudokuPuzzle};
+    /// # let puzzle = SudokuPuzzle::from_string(
+    /// #     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// # )
+    /// # .unwrap();
+    /// let solution = puzzle.solve().unwrap();
+    /// let diagonal = NineByNine::from_fn(|row, col| Some(row == col));
+    /// let masked = SudokuPuzzle::from_solution_and_mask(&solution, &diagonal);
+    /// assert_eq!(Some(solution.get(4, 4)), masked.get(4, 4));
+    /// assert_eq!(None, masked.get(4, 5));
+    /// assert!(masked.is_given(8, 8));
+    /// ```
+    pub fn from_solution_and_mask(solution: &Solution, mask: &NineByNine<bool>) -> Self {
+        let mut nums = [0; 81];
+        for (i, num) in nums.iter_mut().enumerate() {
+            if mask.get(i / 9, i % 9) == Some(&true) {
+                *num = solution.get(i / 9, i % 9);
+            }
+        }
+        SudokuPuzzle::try_from(&nums[..]).expect("solutions only hold 1-9")
+    }
+
     /// Create a new Puzzle from the given string.
     ///
     /// The string should consist of 81 numbers in the range [0,9],
     /// where '0' indicates an empty space and '1'-'9' represent a
     /// filled spaces with the given number.
-    pub fn from_string(source: &str) -> Self {
+    ///
+    /// ```
+    /// # use sudoku::{error::ParseError, puzzle::SudokuPuzzle, Error};
+    /// let puzzle = SudokuPuzzle::from_string(&"0".repeat(81)).unwrap();
+    /// assert_eq!(0.0, puzzle.progress());
+    ///
+    /// let err = SudokuPuzzle::from_string("12x").unwrap_err();
+    /// assert_eq!(Error::Parse(ParseError::InvalidCharacter { index: 2, c: 'x' }), err);
+    /// ```
+    pub fn from_string(source: &str) -> Result<Self, Error> {
         let source_nums = source
             .lines()
             .map(|l| l.trim())
             .flat_map(|l| l.chars())
-            .map(|c| c.to_digit(10).unwrap() as u8)
-            .map(|n| if n == 0 { None } else { Some(n) })
-            .collect::<Vec<Option<u8>>>();
+            .enumerate()
+            .map(|(index, c)| match c.to_digit(10) {
+                Some(n) => Ok(n as u8),
+                None => Err(ParseError::InvalidCharacter { index, c }),
+            })
+            .collect::<Result<Vec<u8>, ParseError>>()?;
 
-        let mut nums = NineByNine::new();
-        for row in 0..9 {
-            for col in 0..9 {
-                nums.set(row, col, source_nums[row * 9 + col]);
-            }
-        }
+        Self::try_from(&source_nums[..])
+    }
 
-        SudokuPuzzle { nums }
+    /// A linear, row-major list of the 81 squares of this puzzle,
+    /// where 0 indicates an empty square.
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut bytes = vec![0; 81];
+    /// bytes[10] = 7;
+    /// let puzzle = SudokuPuzzle::try_from(&bytes[..]).unwrap();
+    /// assert_eq!(Some(7), puzzle.get(1, 1));
+    /// assert_eq!(bytes, puzzle.to_vec());
+    ///
+    /// assert!(SudokuPuzzle::try_from(&bytes[..80]).is_err());
+    /// ```
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.nums.to_vec()
     }
 
-    /// Indicates whether this puzzle is correctly solved
-    pub fn is_solved(&self) -> bool {
-        self.count_unfilled() == 0 && self.is_consistent()
+    /// The number at (row, col), if that square has been filled.
+    /// row and column must each be in the range [0, 9).
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        match self.nums[index(row, col)] {
+            0 => None,
+            n => Some(n),
+        }
     }
 
-    /// Solve this puzzle, if possible, filling in any unfilled spaces.
-    pub fn solve(&self) -> Option<SudokuPuzzle> {
-        let solution = self.fill_all();
-
-        if let Some(solution) = solution {
-            if solution.is_solved() {
-                Some(solution)
-            } else if solution.is_consistent() {
-                solution.try_guesses()
-            } else {
-                None
-            }
+    /// The number at (row, col), if that square has been filled, or `None`
+    /// if (row, col) is outside of the puzzle.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let puzzle = SudokuPuzzle::empty();
+    /// assert_eq!(Some(None), puzzle.get_checked(8, 8));
+    /// assert_eq!(None, puzzle.get_checked(8, 9));
+    /// ```
+    pub fn get_checked(&self, row: usize, col: usize) -> Option<Option<u8>> {
+        if row < 9 && col < 9 {
+            Some(self.get(row, col))
         } else {
             None
         }
     }
 
-    /// Find a square that could be filled multiple ways. Try each
-    /// choice and return the first that leads to a valid solution.
-    /// return None if no choice leads to a valid solution.
-    fn try_guesses(&self) -> Option<SudokuPuzzle> {
-        let could_be_sets = self.could_be_sets();
+    /// Fill (row, col) with val, or empty it if val is `None`.
+    /// val must be in the range [1,9], and (row, col) must not be a given.
+    pub fn set(&mut self, row: usize, col: usize, val: Option<u8>) -> Result<(), Error> {
+        match val {
+            Some(n) => check_number(row, col, n)?,
+            None => check_square(row, col)?,
+        }
+        if self.is_given(row, col) {
+            return Err(PlacementError::Given { row, col }.into());
+        }
+        self.put(row, col, val);
+        Ok(())
+    }
+
+    /// The numbers that (row, col) could be without breaking the rules of
+    /// Sudoku, given the numbers already filled in. A filled square's only
+    /// candidate is its own number.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut puzzle = SudokuPuzzle::empty();
+    /// puzzle.set(0, 0, Some(1)).unwrap();
+    /// puzzle.set(4, 1, Some(2)).unwrap();
+    /// puzzle.set(2, 2, Some(3)).unwrap();
+    /// assert_eq!(vec![4, 5, 6, 7, 8, 9], puzzle.candidates(1, 1).to_vec());
+    ///
+    /// puzzle.set(4, 1, None).unwrap();
+    /// assert_eq!(vec![2, 4, 5, 6, 7, 8, 9], puzzle.candidates(1, 1).to_vec());
+    /// ```
+    pub fn candidates(&self, row: usize, col: usize) -> NineSet {
+        *self.candidates.get(row, col).unwrap()
+    }
+
+    /// Fill or empty (row, col), updating the candidates of the square and
+    /// of its peers, which are the only squares whose candidates can change.
+    pub(crate) fn put(&mut self, row: usize, col: usize, val: Option<u8>) {
+        let square = index(row, col);
+        let previous = self.nums[square];
+        self.nums[square] = val.unwrap_or(0);
 
-        // Find a square that could be filled multiple ways
-        let (mut row, mut col) = (0, 0);
-        for r in 0..9 {
-            for c in 0..9 {
-                if could_be_sets.get(r, c).unwrap().size() > 1 {
-                    row = r;
-                    col = c;
+        match (previous, val) {
+            (0, Some(n)) => {
+                let mut set = NineSet::empty();
+                set.add(n);
+                self.candidates[(row, col)] = Some(set);
+
+                for peer in PEERS[square].iter() {
+                    if self.nums[*peer] == 0 {
+                        if let Some(candidates) = &mut self.candidates[(peer / 9, peer % 9)] {
+                            candidates.remove(n);
+                        }
+                    }
+                }
+            }
+            _ => {
+                // Emptying a square can make its number possible again in
+                // some peers, but only if no other square rules it out.
+                let units = self.unit_sets();
+                self.candidates[(row, col)] = Some(self.could_be_set(&units, row, col));
+                for peer in PEERS[square].iter() {
+                    let (r, c) = (peer / 9, peer % 9);
+                    self.candidates[(r, c)] = Some(self.could_be_set(&units, r, c));
                 }
             }
         }
-        let guesses = could_be_sets.get(row, col);
+    }
 
-        // Try each guess, recursively attempting to solve the puzzle that
-        // results from making that guess, until some guess yields a solution.
-        for guess in guesses.unwrap().to_vec().iter() {
-            let mut puzzle_guess = self.clone();
-            puzzle_guess.nums.set(row, col, Some(*guess));
-            if let Some(solution) = puzzle_guess.solve() {
-                return Some(solution);
-            }
+    /// Indicates whether (row, col) was filled when the puzzle was created.
+    pub fn is_given(&self, row: usize, col: usize) -> bool {
+        self.givens[index(row, col)] != 0
+    }
+
+    /// Which squares were filled when the puzzle was created: the pattern
+    /// of its clues, with `Some(true)` for each given and `Some(false)` for
+    /// every other square.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// puzzle.set(0, 2, Some(5)).unwrap();
+    /// let mask = puzzle.givens_mask();
+    /// assert_eq!(Some(&true), mask.get(0, 0));
+    /// assert_eq!(Some(&false), mask.get(0, 2));
+    ///
+    /// // The mask and the solution make the puzzle again
+    /// let solution = puzzle.solve().unwrap();
+    /// let again = SudokuPuzzle::from_solution_and_mask(&solution, &mask);
+    /// assert_eq!(None, again.get(0, 2));
+    /// assert_eq!(Some(4), again.get(0, 0));
+    /// ```
+    pub fn givens_mask(&self) -> NineByNine<bool> {
+        NineByNine::from_fn(|row, col| Some(self.is_given(row, col)))
+    }
+
+    /// Treat the numbers currently filled in as the puzzle's givens.
+    pub(crate) fn fix_givens(&mut self) {
+        self.givens = self.nums;
+    }
+
+    /// Empty (row, col) and remove its pencil marks.
+    /// Givens cannot be cleared.
+    ///
+    /// ```
+    /// # use sudoku::builder::SudokuPuzzleBuilder;
+    /// let mut puzzle = SudokuPuzzleBuilder::new().clue(0, 0, 4).build().unwrap();
+    /// puzzle.set(0, 1, Some(1)).unwrap();
+    /// puzzle.add_mark(0, 1, 7).unwrap();
+    ///
+    /// puzzle.clear(0, 1).unwrap();
+    /// assert_eq!(None, puzzle.get(0, 1));
+    /// assert!(puzzle.marks(0, 1).is_empty());
+    /// assert!(puzzle.clear(0, 0).is_err());
+    /// assert_eq!(Some(4), puzzle.get(0, 0));
+    /// ```
+    pub fn clear(&mut self, row: usize, col: usize) -> Result<(), Error> {
+        self.set(row, col, None)?;
+        self.marks[(row, col)] = None;
+        Ok(())
+    }
+
+    /// Return the puzzle to its givens, emptying every other square and
+    /// removing all pencil marks.
+    pub fn reset(&mut self) {
+        self.nums = self.givens;
+        self.marks = NineByNine::new();
+        self.candidates = self.could_be_sets();
+    }
+
+    /// The pencil marks noted at (row, col).
+    ///
+    /// Pencil marks are player notes and are kept separate from the
+    /// candidates that the solver computes; the solver never reads them.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut puzzle = SudokuPuzzle::empty();
+    /// puzzle.add_mark(0, 0, 3).unwrap();
+    /// puzzle.toggle_mark(0, 0, 7).unwrap();
+    /// assert_eq!(vec![3, 7], puzzle.marks(0, 0).to_vec());
+    /// puzzle.toggle_mark(0, 0, 3).unwrap();
+    /// assert_eq!(vec![7], puzzle.marks(0, 0).to_vec());
+    /// ```
+    pub fn marks(&self, row: usize, col: usize) -> NineSet {
+        match self.marks.get(row, col) {
+            Some(marks) => *marks,
+            None => NineSet::empty(),
         }
+    }
 
-        None
+    /// Add the pencil mark n to (row, col). n must be in the range [1,9].
+    pub fn add_mark(&mut self, row: usize, col: usize, n: u8) -> Result<(), Error> {
+        check_number(row, col, n)?;
+        let mut marks = self.marks(row, col);
+        marks.add(n);
+        self.marks[(row, col)] = Some(marks);
+        Ok(())
     }
 
-    /// Fill every index that can be filled by iterative deduction.
-    /// Return `None` if some square could never be filled
-    fn fill_all(&self) -> Option<SudokuPuzzle> {
-        let mut prev_unfilled = self.nums.count_nones();
-        let mut filled = self.fill_once();
+    /// Remove the pencil mark n from (row, col), if it is present.
+    pub fn remove_mark(&mut self, row: usize, col: usize, n: u8) -> Result<(), Error> {
+        check_number(row, col, n)?;
+        let mut marks = self.marks(row, col);
+        marks.remove(n);
+        self.set_marks(row, col, marks)
+    }
 
-        while filled.is_some() && filled.as_ref().unwrap().count_unfilled() != prev_unfilled {
-            prev_unfilled = filled.as_ref().unwrap().count_unfilled();
-            filled = filled.as_ref().unwrap().fill_once();
+    /// Add the pencil mark n to (row, col) if it is absent, otherwise remove it.
+    pub fn toggle_mark(&mut self, row: usize, col: usize, n: u8) -> Result<(), Error> {
+        check_number(row, col, n)?;
+        if self.marks(row, col).contains(n) {
+            self.remove_mark(row, col, n)
+        } else {
+            self.add_mark(row, col, n)
         }
+    }
 
-        filled
+    /// Replace the pencil marks at (row, col) with marks.
+    pub fn set_marks(&mut self, row: usize, col: usize, marks: NineSet) -> Result<(), Error> {
+        check_square(row, col)?;
+        if marks.is_empty() {
+            self.marks[(row, col)] = None;
+        } else {
+            self.marks[(row, col)] = Some(marks);
+        }
+        Ok(())
     }
 
-    /// Do one pass of the puzzle and fill any numbers that can be deduced.
-    /// Return `None` if some square could never be filled while maintaining
-    /// consistency with the other squares that have already been filled.
-    fn fill_once(&self) -> Option<SudokuPuzzle> {
-        let could_be_sets = self.could_be_sets();
+    /// Remove every pencil mark from (row, col).
+    pub fn clear_marks(&mut self, row: usize, col: usize) -> Result<(), Error> {
+        self.set_marks(row, col, NineSet::empty())
+    }
 
-        let mut nums = [None; 81];
+    /// The fraction of squares in this puzzle that have been filled,
+    /// in the range [0, 1].
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut puzzle = SudokuPuzzle::empty();
+    /// assert_eq!(0.0, puzzle.progress());
+    /// for col in 0..9 {
+    ///     puzzle.set(0, col, Some(col as u8 + 1)).unwrap();
+    /// }
+    /// assert_eq!(1.0 / 9.0, puzzle.progress());
+    /// ```
+    pub fn progress(&self) -> f64 {
+        (81 - self.count_unfilled()) as f64 / 81.0
+    }
+
+    /// How many more times each number must be placed to complete the puzzle.
+    /// Index 0 holds the count for 1, index 8 the count for 9.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut puzzle = SudokuPuzzle::empty();
+    /// puzzle.set(0, 0, Some(1)).unwrap();
+    /// puzzle.set(1, 3, Some(1)).unwrap();
+    /// puzzle.set(8, 8, Some(9)).unwrap();
+    /// assert_eq!([7, 9, 9, 9, 9, 9, 9, 9, 8], puzzle.remaining_by_digit());
+    /// ```
+    pub fn remaining_by_digit(&self) -> [u8; 9] {
+        let mut remaining = [9u8; 9];
+        for n in self.nums.iter().filter(|n| **n != 0) {
+            let count = &mut remaining[(*n - 1) as usize];
+            *count = (*count).saturating_sub(1);
+        }
+        remaining
+    }
+
+    /// List the squares whose numbers differ between this puzzle and other,
+    /// in row-major order. Pencil marks are not compared.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::{CellDiff, SudokuPuzzle};
+    /// let mut player = SudokuPuzzle::empty();
+    /// let mut solution = SudokuPuzzle::empty();
+    /// player.set(2, 3, Some(4)).unwrap();
+    /// solution.set(2, 3, Some(5)).unwrap();
+    /// solution.set(7, 0, Some(1)).unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         CellDiff { row: 2, col: 3, this: Some(4), other: Some(5) },
+    ///         CellDiff { row: 7, col: 0, this: None, other: Some(1) },
+    ///     ],
+    ///     player.diff(&solution)
+    /// );
+    /// ```
+    pub fn diff(&self, other: &SudokuPuzzle) -> Vec<CellDiff> {
+        let mut diffs = Vec::new();
         for row in 0..9 {
             for col in 0..9 {
-                let could_be = could_be_sets.get(row, col).unwrap();
-                if could_be.size() == 1 {
-                    nums[row * 9 + col] = Some(could_be.to_vec()[0]);
-                } else if could_be.size() == 0 {
-                    return None;
+                let (this, other) = (self.get(row, col), other.get(row, col));
+                if this != other {
+                    diffs.push(CellDiff {
+                        row,
+                        col,
+                        this,
+                        other,
+                    });
                 }
             }
         }
+        diffs
+    }
+
+    /// List the squares filled since the puzzle was created whose numbers
+    /// don't match solution, in row-major order. Empty squares and givens are
+    /// never mistakes.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::{Mistake, SudokuPuzzle};
+    /// let mut puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let solution = puzzle.solve().unwrap();
+    /// puzzle.set(0, 2, Some(5)).unwrap();
+    /// puzzle.set(0, 3, Some(9)).unwrap();
+    /// assert_eq!(
+    ///     vec![Mistake { row: 0, col: 3, entered: 9 }],
+    ///     puzzle.check_against(&solution)
+    /// );
+    /// ```
+    pub fn check_against(&self, solution: &Solution) -> Vec<Mistake> {
+        (0..81)
+            .filter(|square| self.givens[*square] == 0 && self.nums[*square] != 0)
+            .map(|square| (square / 9, square % 9, self.nums[square]))
+            .filter(|(row, col, n)| solution.get(*row, *col) != *n)
+            .map(|(row, col, entered)| Mistake { row, col, entered })
+            .collect()
+    }
+
+    /// The squares whose number also appears elsewhere in the same row,
+    /// column, or 3x3 square, in row-major order. A puzzle with no
+    /// conflicts is consistent with the rules of Sudoku.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut puzzle = SudokuPuzzle::empty();
+    /// puzzle.set(0, 0, Some(5)).unwrap();
+    /// puzzle.set(0, 1, Some(3)).unwrap();
+    /// assert!(puzzle.conflicts().is_empty());
+    ///
+    /// puzzle.set(1, 1, Some(5)).unwrap();
+    /// puzzle.set(8, 1, Some(3)).unwrap();
+    /// assert_eq!(vec![(0, 0), (0, 1), (1, 1), (8, 1)], puzzle.conflicts());
+    /// ```
+    pub fn conflicts(&self) -> Vec<(usize, usize)> {
+        let mut conflicting = [false; 81];
+        for unit in UNITS.iter() {
+            for (i, a) in unit.iter().enumerate() {
+                for b in unit[i + 1..].iter() {
+                    if self.nums[*a] != 0 && self.nums[*a] == self.nums[*b] {
+                        conflicting[*a] = true;
+                        conflicting[*b] = true;
+                    }
+                }
+            }
+        }
+
+        (0..81)
+            .filter(|square| conflicting[*square])
+            .map(|square| (square / 9, square % 9))
+            .collect()
+    }
+
+    /// Indicates whether this puzzle is correctly solved
+    pub fn is_solved(&self) -> bool {
+        self.count_unfilled() == 0 && self.is_consistent()
+    }
+
+    /// Solve this puzzle, if possible, filling in any unfilled spaces.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let solution = puzzle.solve().unwrap();
+    /// assert_eq!(5, solution.get(0, 2));
+    /// ```
+    pub fn solve(&self) -> Option<Solution> {
+        self.search().map(|solved| Solution::new(&solved))
+    }
+
+    /// Solve this puzzle on a thread of a shared pool of blocking threads,
+    /// without blocking the async task that awaits it. Works with any async
+    /// runtime. Dropping the future cancels the search, which stops at its
+    /// next guess.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let solution = futures_lite::future::block_on(puzzle.solve_async());
+    /// assert_eq!(puzzle.solve(), solution);
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn solve_async(&self) -> impl core::future::Future<Output = Option<Solution>> + Send {
+        let puzzle = self.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let cancel = StopOnDrop(Arc::clone(&stop));
+        async move {
+            // Stop the search if this future is dropped before it finishes
+            let _cancel = cancel;
+            let solved = blocking::unblock(move || puzzle.search_until(&stop)).await;
+            solved.map(|solved| Solution::new(&solved))
+        }
+    }
+
+    /// Iterate over every solution of this puzzle. The search can be saved
+    /// and resumed part way through; see [`crate::search`].
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let mut top_row = String::from("1234567");
+    /// top_row.push_str(&"0".repeat(74));
+    /// let puzzle = SudokuPuzzle::from_string(&top_row).unwrap();
+    /// let solutions: Vec<_> = puzzle.solutions().take(3).collect();
+    /// assert_eq!(3, solutions.len());
+    /// assert!(solutions.iter().all(|s| s.get(0, 0) == 1));
+    /// assert_ne!(solutions[0], solutions[1]);
+    /// ```
+    pub fn solutions(&self) -> Solutions {
+        Solutions::new(self)
+    }
+
+    /// Count the solutions of this puzzle, stopping once limit have been found.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(1, puzzle.count_solutions(2));
+    /// assert_eq!(10, SudokuPuzzle::empty().count_solutions(10));
+    /// ```
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.count_solutions_within(limit, &SolverLimits::default())
+            .expect("a search without limits never breaks one")
+    }
+
+    /// Solve this puzzle like [`SudokuPuzzle::solve`], giving up once the
+    /// search breaks one of limits.
+    pub fn solve_within(&self, limits: &SolverLimits) -> Result<Option<Solution>, LimitExceeded> {
+        let mut working = self.clone();
+        working.solve_in_place_within(limits)
+    }
+
+    /// Count the solutions of this puzzle like
+    /// [`SudokuPuzzle::count_solutions`], giving up once the search breaks
+    /// one of limits.
+    pub fn count_solutions_within(
+        &self,
+        limit: usize,
+        limits: &SolverLimits,
+    ) -> Result<usize, LimitExceeded> {
+        let mut working = self.clone();
+        working.count_in_place_within(limit, limits)
+    }
 
-        Some(SudokuPuzzle {
-            nums: NineByNine::from(nums),
+    /// Two different solutions of this puzzle and the squares where they
+    /// differ, or `None` if it has at most one solution.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008500",
+    /// )
+    /// .unwrap();
+    /// let ambiguity = puzzle.ambiguity().unwrap();
+    /// assert_ne!(ambiguity.first, ambiguity.second);
+    /// for (row, col) in ambiguity.squares {
+    ///     assert_eq!(None, puzzle.get(row, col));
+    ///     assert_ne!(ambiguity.first.get(row, col), ambiguity.second.get(row, col));
+    /// }
+    /// ```
+    pub fn ambiguity(&self) -> Option<Ambiguity> {
+        let mut solutions = self.solutions();
+        let (first, second) = (solutions.next()?, solutions.next()?);
+        let squares = (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .filter(|(row, col)| first.get(*row, *col) != second.get(*row, *col))
+            .collect();
+        Some(Ambiguity {
+            first,
+            second,
+            squares,
         })
     }
 
-    /// Generate the sets of numbers that each index could be
-    fn could_be_sets(&self) -> NineByNine<NineSet> {
-        let mut sets = [None; 81];
+    /// Find out whether filling (row, col) with n leaves a puzzle that can
+    /// still be solved, giving up with [`Viability::Unknown`] after a fixed
+    /// number of guesses. Numbers already ruled out by a peer are impossible
+    /// without searching at all.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::{SudokuPuzzle, Viability};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// // 4 is already in the row, and 9 breaks no rules but leads nowhere
+    /// assert!(!puzzle.candidates(0, 2).contains(4));
+    /// assert!(puzzle.candidates(0, 2).contains(9));
+    /// assert_eq!(Viability::Impossible, puzzle.is_candidate_viable(0, 2, 4));
+    /// assert_eq!(Viability::Impossible, puzzle.is_candidate_viable(0, 2, 9));
+    /// assert_eq!(Viability::Viable, puzzle.is_candidate_viable(0, 2, 5));
+    /// ```
+    pub fn is_candidate_viable(&self, row: usize, col: usize, n: u8) -> Viability {
+        self.is_candidate_viable_within(row, col, n, VIABILITY_GUESSES)
+    }
+
+    /// Find out whether filling (row, col) with n leaves a puzzle that can
+    /// still be solved, like [`SudokuPuzzle::is_candidate_viable`], making at
+    /// most guesses guesses.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::{SudokuPuzzle, Viability};
+    /// // With so few givens, the solver has to guess
+    /// let puzzle = SudokuPuzzle::from_string(&format!("123456789{}", "0".repeat(72))).unwrap();
+    /// assert_eq!(Viability::Unknown, puzzle.is_candidate_viable_within(8, 8, 1, 0));
+    /// assert_eq!(Viability::Viable, puzzle.is_candidate_viable_within(8, 8, 1, 100));
+    /// ```
+    pub fn is_candidate_viable_within(
+        &self,
+        row: usize,
+        col: usize,
+        n: u8,
+        guesses: usize,
+    ) -> Viability {
+        if !self.candidates(row, col).contains(n) {
+            return Viability::Impossible;
+        }
+
+        let mut working = self.clone();
+        working.put(row, col, Some(n));
+        let mut trail = Trail {
+            guesses_left: Some(guesses),
+            ..Trail::new()
+        };
+        if board::search_in_place(&mut working, &mut trail) {
+            Viability::Viable
+        } else if trail.exhausted {
+            Viability::Unknown
+        } else {
+            Viability::Impossible
+        }
+    }
+
+    /// The canonical form of this puzzle, which is the same for any two
+    /// puzzles that are equivalent: one can be made from the other by
+    /// relabeling digits, transposing, and reordering bands, stacks, and the
+    /// rows and columns within them. It is the smallest such puzzle when read
+    /// as 81 digits (minlex), and its filled squares are its givens.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+    /// )
+    /// .unwrap();
+    /// // The same puzzle with 1s and 2s swapped and the first two rows swapped
+    /// let equivalent = SudokuPuzzle::from_string(
+    ///     "900305002003010600002806400008201900700000008006708100001609500800103009005020300",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(puzzle.canonical().to_vec(), equivalent.canonical().to_vec());
+    /// ```
+    pub fn canonical(&self) -> SudokuPuzzle {
+        let nums = canonical::minlex(&self.nums);
+        SudokuPuzzle::try_from(&nums[..]).expect("relabeled numbers are still in [0,9]")
+    }
+
+    /// A copy of this puzzle rearranged by transform, with its givens, the
+    /// numbers filled in since, and pencil marks all moved and relabeled
+    /// together.
+    ///
+    /// ```
+    /// # use sudoku::{puzzle::SudokuPuzzle, transform::Transform};
+    /// let mut puzzle = SudokuPuzzle::from_string(&format!("12{}", "0".repeat(79))).unwrap();
+    /// puzzle.set(1, 0, Some(3)).unwrap();
+    ///
+    /// let swapped = puzzle.transform(Transform::SwapRows(0, 1)).unwrap();
+    /// assert_eq!(Some(3), swapped.get(0, 0));
+    /// assert_eq!(Some(1), swapped.get(1, 0));
+    /// assert!(swapped.is_given(1, 0) && !swapped.is_given(0, 0));
+    ///
+    /// let labels = [9, 8, 7, 6, 5, 4, 3, 2, 1];
+    /// let relabeled = puzzle.transform(Transform::Relabel(labels)).unwrap();
+    /// assert_eq!(Some(9), relabeled.get(0, 0));
+    ///
+    /// // Rows 2 and 3 are in different bands
+    /// assert!(puzzle.transform(Transform::SwapRows(2, 3)).is_err());
+    /// ```
+    pub fn transform(&self, transform: Transform) -> Result<SudokuPuzzle, Error> {
+        transform.check()?;
+        let mut givens = [0; 81];
+        for (square, given) in givens.iter_mut().enumerate() {
+            let (row, col) = transform.source(square / 9, square % 9);
+            *given = transform.label(self.givens[index(row, col)]);
+        }
+
+        let mut puzzle = SudokuPuzzle::try_from(&givens[..])?;
         for row in 0..9 {
             for col in 0..9 {
-                sets[row * 9 + col] = if let Some(n) = self.nums.get(row, col) {
-                    let mut set = NineSet::empty();
-                    set.add(*n);
-                    Some(set)
-                } else {
-                    Some(self.could_be_set(row, col))
+                let (from_row, from_col) = transform.source(row, col);
+                if !self.is_given(from_row, from_col) {
+                    if let Some(n) = self.get(from_row, from_col) {
+                        puzzle.put(row, col, Some(transform.label(n)));
+                    }
                 }
+                let marks = self.marks(from_row, from_col);
+                puzzle.set_marks(row, col, marks.iter().map(|n| transform.label(n)).collect())?;
             }
         }
-        NineByNine::from(sets)
+        Ok(puzzle)
     }
 
-    /// Generate the set of numbers that the given index could be
-    fn could_be_set(&self, row: usize, col: usize) -> NineSet {
-        union(vec![
-            self.row_set(row),
-            self.col_set(col),
-            self.sqr_set((row / 3 * 3) + (col / 3)),
-        ])
-        .complement()
+    /// A copy of this puzzle disguised by a random combination of
+    /// transformations: it looks different, but it is equivalent, with the
+    /// same canonical form and a solution rearranged the same way. The same
+    /// random number generator, seeded the same way, always disguises a
+    /// puzzle the same way.
+    ///
+    /// ```
+    /// # use rand::SeedableRng;
+    /// # use rand_chacha::ChaCha8Rng;
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let scrambled = puzzle.scramble(&mut ChaCha8Rng::seed_from_u64(7));
+    /// assert_ne!(puzzle.to_vec(), scrambled.to_vec());
+    /// assert_eq!(puzzle.canonical().to_vec(), scrambled.canonical().to_vec());
+    /// assert_eq!(1, scrambled.count_solutions(2));
+    /// ```
+    pub fn scramble<R: Rng + ?Sized>(&self, rng: &mut R) -> SudokuPuzzle {
+        transform::random(rng)
+            .into_iter()
+            .try_fold(self.clone(), |puzzle, transform| {
+                puzzle.transform(transform)
+            })
+            .expect("random transformations are valid")
     }
 
-    /// Indicates whether this puzzle is consistent, that is,
-    /// it does not violate the the rules of Sudoku.
-    fn is_consistent(&self) -> bool {
-        for i in 0..9 {
-            let row = self.row_list(i);
-            let col = self.col_list(i);
-            let sqr = self.sqr_list(i);
-
-            if row.len() != NineSet::from(row).size()
-                || col.len() != NineSet::from(col).size()
-                || sqr.len() != NineSet::from(sqr).size()
-            {
-                return false;
+    /// A copy of this puzzle with filled squares removed for as long as its
+    /// solution stays unique, or `None` if it does not have exactly one
+    /// solution. The squares left are the givens of the copy.
+    ///
+    /// Squares are tried in row-major order, so no single square of the result
+    /// can be removed, but a different order might leave fewer.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+    /// )
+    /// .unwrap();
+    /// let minimized = puzzle.minimized().unwrap();
+    /// assert_eq!(puzzle.solve(), minimized.solve());
+    /// let clues = |p: &SudokuPuzzle| p.to_vec().iter().filter(|n| **n != 0).count();
+    /// assert!(clues(&minimized) < clues(&puzzle));
+    /// assert!(SudokuPuzzle::empty().minimized().is_none());
+    /// ```
+    pub fn minimized(&self) -> Option<SudokuPuzzle> {
+        if self.count_solutions(2) != 1 {
+            return None;
+        }
+
+        let mut nums = self.nums;
+        for square in 0..81 {
+            let n = nums[square];
+            if n == 0 {
+                continue;
+            }
+            nums[square] = 0;
+            if SudokuPuzzle::try_from(&nums[..]).ok()?.count_solutions(2) != 1 {
+                nums[square] = n;
             }
         }
-        true
+        SudokuPuzzle::try_from(&nums[..]).ok()
     }
 
-    /// Returns the number of squares in this puzzle that
-    /// have not been filled with a number already.
-    fn count_unfilled(&self) -> usize {
-        self.nums.count_nones()
+    /// How hard this puzzle is to solve, or `None` if it has no solution.
+    /// The solution is not checked for uniqueness.
+    ///
+    /// ```
+    /// # use sudoku::{puzzle::SudokuPuzzle, rating::Difficulty};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(Some(Difficulty::Expert), puzzle.difficulty());
+    /// ```
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.rating().map(|rating| rating.difficulty)
     }
 
-    /// The list of numbers (with any repeats) in the row with the given index.
-    fn row_list(&self, row_idx: usize) -> Vec<u8> {
-        assert!(row_idx < 9);
+    /// How hard this puzzle is to solve, as a difficulty and a score, or
+    /// `None` if it has no solution.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// let easy = SudokuPuzzle::from_string(
+    ///     "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+    /// )
+    /// .unwrap();
+    /// let hard = SudokuPuzzle::from_string(
+    ///     "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    /// )
+    /// .unwrap();
+    /// assert!(easy.rating().unwrap().score < hard.rating().unwrap().score);
+    /// ```
+    pub fn rating(&self) -> Option<Rating> {
+        self.rating_with(&RatingConfig::default())
+    }
+
+    /// How hard this puzzle is to solve, scored with the weights in config,
+    /// or `None` if it has no solution.
+    pub fn rating_with(&self, config: &RatingConfig) -> Option<Rating> {
+        let (solution, steps) = self.solve_with_steps();
+        solution.map(|_| Rating::from_steps_with(&steps, config))
+    }
 
-        let mut row_list = Vec::with_capacity(9);
-        for col in 0..9 {
-            if let Some(n) = self.nums.get(row_idx, col) {
-                row_list.push(*n);
+    /// Rate this puzzle like [`SudokuPuzzle::rating_with`], giving up once
+    /// the search breaks one of limits. Every step is kept until the rating
+    /// is made, so limiting the nodes also limits the memory used.
+    ///
+    /// ```
+    /// # use sudoku::{error::LimitExceeded, limits::SolverLimits, puzzle::SudokuPuzzle, rating::RatingConfig};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    /// )
+    /// .unwrap();
+    /// let config = RatingConfig::default();
+    /// let unlimited = SolverLimits::default();
+    /// assert_eq!(Ok(puzzle.rating()), puzzle.rating_within(&config, &unlimited));
+    ///
+    /// let limits = SolverLimits { max_nodes: Some(10), ..unlimited };
+    /// assert_eq!(
+    ///     Err(LimitExceeded::Nodes { max: 10 }),
+    ///     puzzle.rating_within(&config, &limits)
+    /// );
+    /// ```
+    pub fn rating_within(
+        &self,
+        config: &RatingConfig,
+        limits: &SolverLimits,
+    ) -> Result<Option<Rating>, LimitExceeded> {
+        let mut working = self.clone();
+        let mut trail = Trail {
+            steps: Some(Vec::new()),
+            ..Trail::within(limits)
+        };
+        let solved = board::search_in_place(&mut working, &mut trail);
+        match trail.exceeded {
+            Some(exceeded) => Err(exceeded),
+            None if solved => {
+                let steps = trail.steps.unwrap_or_default();
+                Ok(Some(Rating::from_steps_with(&steps, config)))
             }
+            None => Ok(None),
         }
-        row_list
     }
 
-    /// The list of numbers (with any repeats) in the square with the given index.
-    fn col_list(&self, col_idx: usize) -> Vec<u8> {
-        assert!(col_idx < 9);
+    /// Solve this puzzle, if possible, and return every step the solver took
+    /// along the way, including guesses that had to be taken back.
+    ///
+    /// ```
+    /// # use sudoku::{puzzle::SudokuPuzzle, step::Step};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let (solution, steps) = puzzle.solve_with_steps();
+    /// assert_eq!(puzzle.solve(), solution);
+    /// assert!(steps.iter().any(|step| matches!(step, Step::Single { .. })));
+    /// ```
+    pub fn solve_with_steps(&self) -> (Option<Solution>, Vec<Step>) {
+        let mut working = self.clone();
+        let mut trail = Trail::logged();
+
+        let solution = if board::search_in_place(&mut working, &mut trail) {
+            Some(Solution::new(&working))
+        } else {
+            None
+        };
+        (solution, trail.steps.unwrap_or_default())
+    }
+
+    /// The solver's next step from the squares filled so far, to be given
+    /// away a [`Level`](crate::hint::Level) at a time, or `None` if the
+    /// puzzle is solved or has no solution as filled in.
+    ///
+    /// ```
+    /// # use sudoku::{hint::Level, puzzle::SudokuPuzzle};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let hint = puzzle.hint().unwrap();
+    /// let (row, col, n) = hint.cell();
+    /// assert_eq!(n, puzzle.solve().unwrap().get(row, col));
+    /// ```
+    pub fn hint(&self) -> Option<Hint> {
+        let (solution, steps) = self.solve_with_steps();
+        Hint::from_steps(&steps, &solution?)
+    }
+
+    /// Fill every unfilled space by deduction, guessing when deduction is
+    /// not enough, and return the solved puzzle. The search works on a copy
+    /// of this puzzle and a fixed-size trail, so it makes no heap allocations.
+    fn search(&self) -> Option<SudokuPuzzle> {
+        self.search_on(Trail::new())
+    }
 
-        let mut col_list = Vec::with_capacity(9);
-        for row in 0..9 {
-            if let Some(n) = self.nums.get(row, col_idx) {
-                col_list.push(*n);
-            }
+    /// Search like `search`, but give up and return `None` once stop is set,
+    /// which is checked before every guess.
+    #[cfg(feature = "async")]
+    fn search_until(&self, stop: &AtomicBool) -> Option<SudokuPuzzle> {
+        self.search_on(Trail {
+            stop: Some(stop),
+            ..Trail::new()
+        })
+    }
+
+    /// Search like `search`, solving a copy of this puzzle in working
+    /// instead of a new one. Returns `false` if there is no solution.
+    pub(crate) fn search_into(&self, working: &mut SudokuPuzzle) -> bool {
+        working.clone_from(self);
+        board::search_in_place(working, &mut Trail::new())
+    }
+
+    /// Solve this puzzle in place, as far as limits allow.
+    pub(crate) fn solve_in_place_within(
+        &mut self,
+        limits: &SolverLimits,
+    ) -> Result<Option<Solution>, LimitExceeded> {
+        let mut trail = Trail::within(limits);
+        let solved = board::search_in_place(self, &mut trail);
+        match trail.exceeded {
+            Some(exceeded) => Err(exceeded),
+            None if solved => Ok(Some(Solution::new(self))),
+            None => Ok(None),
         }
-        col_list
     }
 
-    /// The list of numbers (with any repeats) in the square with the given index.
-    /// Indices are in the range [0,9), begin in the upper left hand corner
-    /// of the puzzle, and proceed left to right, top to bottom.
-    fn sqr_list(&self, sqr_idx: usize) -> Vec<u8> {
-        assert!(sqr_idx < 9);
+    /// Count the solutions of this puzzle, stopping once limit have been
+    /// found, as far as limits allow. Every square filled by the search is
+    /// emptied again.
+    pub(crate) fn count_in_place_within(
+        &mut self,
+        limit: usize,
+        limits: &SolverLimits,
+    ) -> Result<usize, LimitExceeded> {
+        if limit == 0 {
+            return Ok(0);
+        }
+        let mut trail = Trail {
+            count_to: Some(limit),
+            ..Trail::within(limits)
+        };
+        if board::search_in_place(self, &mut trail) {
+            // Stopped at the limit with the last solution still filled in
+            board::undo_to(self, &mut trail, 0);
+        }
+        match trail.exceeded {
+            Some(exceeded) => Err(exceeded),
+            None => Ok(trail.found),
+        }
+    }
 
-        let mut sqr_list = Vec::with_capacity(9);
-        for i in 0..9 {
-            let row = sqr_idx / 3 * 3 + i / 3;
-            let col = sqr_idx % 3 * 3 + i % 3;
+    fn search_on(&self, mut trail: Trail) -> Option<SudokuPuzzle> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("solve", unfilled = self.count_unfilled()).entered();
+        let mut working = self.clone();
 
-            if let Some(n) = self.nums.get(row, col) {
-                sqr_list.push(*n);
-            }
+        if board::search_in_place(&mut working, &mut trail) {
+            Some(working)
+        } else {
+            None
         }
-        sqr_list
     }
 
-    /// The set of numbers in the row with the given index.
-    fn row_set(&self, row_idx: usize) -> NineSet {
-        if row_idx > 8 {
-            panic!("attempted to get row {}", row_idx)
+    /// The square to guess at when deduction is not enough, which is the
+    /// last square that could be filled multiple ways.
+    pub(crate) fn guess_square(&self) -> (usize, usize) {
+        let square = board::guess_square(self);
+        (square / 9, square % 9)
+    }
+
+    /// Solve like `solve`, with or without hidden singles and guessing at
+    /// the square with the fewest candidates rather than the last one, and
+    /// also return how many guesses were made.
+    pub(crate) fn solve_counting(
+        &self,
+        hidden_singles: bool,
+        fewest_first: bool,
+    ) -> (Option<Solution>, u64) {
+        let mut working = self.clone();
+        let mut trail = Trail {
+            hidden_singles,
+            fewest_first,
+            ..Trail::new()
+        };
+        let solution = if board::search_in_place(&mut working, &mut trail) {
+            Some(Solution::new(&working))
+        } else {
+            None
+        };
+        (solution, trail.guesses)
+    }
+
+    /// Fill every square that can be filled by deduction alone. Return
+    /// `false` if doing so shows that the puzzle has no solution.
+    pub(crate) fn deduce(&mut self) -> bool {
+        board::fill_all(self, &mut Trail::new())
+    }
+
+    /// Generate the sets of numbers that each index could be
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    fn could_be_sets(&self) -> NineByNine<NineSet> {
+        let units = self.unit_sets();
+        NineByNine::from_fn(|row, col| Some(self.could_be_set(&units, row, col)))
+    }
+
+    /// Generate the sets of numbers that each square could be, a row at a time.
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    fn could_be_sets(&self) -> NineByNine<NineSet> {
+        let units = self.unit_sets();
+        let sets = simd::candidates(&self.nums, &units.sets);
+        NineByNine::from_fn(|row, col| Some(sets[row * 9 + col]))
+    }
+
+    /// Generate the set of numbers that the given index could be
+    fn could_be_set(&self, units: &UnitSets, row: usize, col: usize) -> NineSet {
+        if let Some(n) = self.get(row, col) {
+            let mut set = NineSet::empty();
+            set.add(n);
+            set
+        } else {
+            let [r, c, b] = SQUARE_UNITS[row * 9 + col];
+            !(units.sets[r] | units.sets[c] | units.sets[b])
         }
+    }
+
+    /// Gather the set of numbers in every row, column, and 3x3 square
+    /// in a single pass over the puzzle.
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    fn unit_sets(&self) -> UnitSets {
+        let mut units = UnitSets {
+            sets: [NineSet::empty(); 27],
+            repeats: false,
+        };
 
-        NineSet::from(self.row_list(row_idx))
+        for (square, n) in self.nums.iter().enumerate() {
+            if *n != 0 {
+                for unit in SQUARE_UNITS[square].iter() {
+                    let set = &mut units.sets[*unit];
+                    units.repeats |= set.contains(*n);
+                    set.add(*n);
+                }
+            }
+        }
+        units
+    }
+
+    /// Gather the set of numbers in every row, column, and 3x3 square
+    /// a row at a time.
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    fn unit_sets(&self) -> UnitSets {
+        let (sets, repeats) = simd::unit_sets(&self.nums);
+        UnitSets { sets, repeats }
+    }
+
+    /// Indicates whether this puzzle is consistent, that is,
+    /// it does not violate the the rules of Sudoku.
+    pub(crate) fn is_consistent(&self) -> bool {
+        !self.unit_sets().repeats
+    }
+
+    /// Returns the number of squares in this puzzle that
+    /// have not been filled with a number already.
+    pub(crate) fn count_unfilled(&self) -> usize {
+        self.nums.iter().filter(|n| **n == 0).count()
+    }
+}
+
+/// The linear index of (row, col).
+/// row and column must each be in the range [0, 9).
+fn index(row: usize, col: usize) -> usize {
+    assert!(row < 9);
+    assert!(col < 9);
+
+    row * 9 + col
+}
+
+/// Sets a flag when dropped, to stop a search when its future is dropped.
+#[cfg(feature = "async")]
+struct StopOnDrop(Arc<AtomicBool>);
+
+#[cfg(feature = "async")]
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
     }
+}
 
-    /// The set of numbers in the column with the given index.
-    fn col_set(&self, col_idx: usize) -> NineSet {
-        if col_idx > 8 {
-            panic!("attempted to get column {}", col_idx)
+/// The sets of numbers in each row, column, and 3x3 square of a puzzle,
+/// indexed the same way as `units::UNITS`.
+struct UnitSets {
+    sets: [NineSet; 27],
+    /// Whether any row, column, or square contains a number more than once.
+    repeats: bool,
+}
+
+impl TryFrom<&[u8]> for SudokuPuzzle {
+    type Error = Error;
+
+    /// Create a puzzle from a linear, row-major list of 81 squares, where
+    /// 0 indicates an empty square and 1-9 are the puzzle's givens.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != 81 {
+            return Err(ParseError::WrongLength { len: bytes.len() }.into());
         }
 
-        NineSet::from(self.col_list(col_idx))
+        let mut nums = [0; 81];
+        for (index, (num, n)) in nums.iter_mut().zip(bytes).enumerate() {
+            if *n > 9 {
+                return Err(ParseError::InvalidNumber { index, n: *n }.into());
+            }
+            *num = *n;
+        }
+
+        let mut puzzle = SudokuPuzzle {
+            givens: nums,
+            nums,
+            marks: NineByNine::new(),
+            candidates: NineByNine::new(),
+        };
+        puzzle.candidates = puzzle.could_be_sets();
+        Ok(puzzle)
     }
+}
 
-    /// The set of numbers in the 3x3 square at the given index. Indices are in the
-    /// range [0,9), begin in the upper left hand corner of the puzzle,
-    /// and proceed left to right, top to bottom.
-    fn sqr_set(&self, sqr_idx: usize) -> NineSet {
-        assert!(sqr_idx < 9);
-        NineSet::from(self.sqr_list(sqr_idx))
+impl Default for SudokuPuzzle {
+    fn default() -> Self {
+        Self::empty()
     }
 }
 
 impl fmt::Debug for SudokuPuzzle {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.nums)
+        let nums = NineByNine::from_fn(|row, col| self.get(row, col));
+        write!(f, "{:?}", nums)
     }
 }
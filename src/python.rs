@@ -0,0 +1,114 @@
+//! A Python extension module, enabled with the `python` feature and built
+//! with maturin.
+//!
+//! ```python
+//! import numpy as np
+//! from sudoku import SudokuPuzzle, generate
+//!
+//! puzzle = SudokuPuzzle("410036000007000850600000000090000200006070008000000091002014000000003000740008509")
+//! puzzle.solve()       # "415836927237149856..."
+//! puzzle.rate()        # ("medium", 79)
+//! grid = np.frombuffer(puzzle.to_bytes(), dtype=np.uint8).reshape(9, 9)
+//! generate("hard", seed=7)
+//! ```
+
+use crate::{deterministic, puzzle::SudokuPuzzle, rating::Difficulty, Error};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyBytes};
+
+impl From<Error> for PyErr {
+    fn from(e: Error) -> Self {
+        PyValueError::new_err(e.to_string())
+    }
+}
+
+/// The squares of a grid as 81 digits.
+fn digits(nums: &[u8]) -> String {
+    nums.iter().map(|n| char::from(b'0' + n)).collect()
+}
+
+/// A Sudoku puzzle, read from 81 digits where 0 or '.' is an empty square.
+#[pyclass(name = "SudokuPuzzle", module = "sudoku", from_py_object)]
+#[derive(Clone)]
+struct PyPuzzle {
+    puzzle: SudokuPuzzle,
+}
+
+#[pymethods]
+impl PyPuzzle {
+    #[new]
+    fn new(puzzle: &str) -> PyResult<Self> {
+        let puzzle = SudokuPuzzle::from_string(&puzzle.replace('.', "0"))?;
+        Ok(PyPuzzle { puzzle })
+    }
+
+    /// The number at (row, col), or None if the square is empty.
+    fn get(&self, row: usize, col: usize) -> PyResult<Option<u8>> {
+        self.puzzle.get_checked(row, col).ok_or_else(|| {
+            PyValueError::new_err(format!("({}, {}) is outside of the puzzle", row, col))
+        })
+    }
+
+    /// The solution as 81 digits, or None if there is none.
+    fn solve(&self) -> Option<String> {
+        self.puzzle
+            .solve()
+            .map(|solution| digits(&solution.to_vec()))
+    }
+
+    /// The number of solutions, counting no further than limit.
+    #[pyo3(signature = (limit = 2))]
+    fn count_solutions(&self, limit: usize) -> usize {
+        self.puzzle.count_solutions(limit)
+    }
+
+    /// The difficulty's name and the rating score, or None if there is no
+    /// solution.
+    fn rate(&self) -> Option<(&'static str, u32)> {
+        self.puzzle
+            .rating()
+            .map(|rating| (rating.difficulty.name(), rating.score))
+    }
+
+    /// The squares as 9 lists of 9 numbers, with 0 for empty squares.
+    fn to_list(&self) -> Vec<Vec<u32>> {
+        let nums = self.puzzle.to_vec();
+        nums.chunks(9)
+            .map(|row| row.iter().map(|n| u32::from(*n)).collect())
+            .collect()
+    }
+
+    /// The squares as 81 bytes in row-major order, with 0 for empty squares,
+    /// for `numpy.frombuffer`.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.puzzle.to_vec())
+    }
+
+    fn __str__(&self) -> String {
+        digits(&self.puzzle.to_vec())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SudokuPuzzle(\"{}\")", digits(&self.puzzle.to_vec()))
+    }
+}
+
+/// Generate a puzzle with a unique solution of the named difficulty. The
+/// same seed always gives the same puzzle; without one it is random.
+#[pyfunction(name = "generate")]
+#[pyo3(signature = (difficulty = "medium", seed = None))]
+fn py_generate(difficulty: &str, seed: Option<u64>) -> PyResult<PyPuzzle> {
+    let difficulty: Difficulty = difficulty.parse().map_err(Error::from)?;
+    let seed = seed.unwrap_or_else(|| {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
+        now.map_or(0, |now| now.as_nanos() as u64)
+    });
+    let puzzle = deterministic::generate(seed, difficulty);
+    Ok(PyPuzzle { puzzle })
+}
+
+#[pymodule(name = "sudoku")]
+fn sudoku_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPuzzle>()?;
+    m.add_function(wrap_pyfunction!(py_generate, m)?)?;
+    Ok(())
+}
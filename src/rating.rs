@@ -0,0 +1,149 @@
+//! How hard a puzzle is for a person to solve.
+
+use crate::{error::ParseError, step::Step};
+use core::{fmt, str::FromStr};
+
+/// How hard a puzzle is, judged by the techniques the solver needs for it.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Difficulty {
+    /// Every square can be filled because it is the only place left for a
+    /// number: naked singles only.
+    Easy,
+    /// Some number has to be placed because it has only one square left in
+    /// a row, column, or box: hidden singles.
+    Medium,
+    /// Singles are not enough, so the solver has to guess, but every guess
+    /// it makes turns out to be right.
+    Hard,
+    /// Some guesses lead to contradictions and have to be taken back.
+    Expert,
+}
+
+impl Difficulty {
+    /// Every difficulty, from easiest to hardest.
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    /// The name of this difficulty in lowercase, such as "medium".
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ParseError;
+
+    /// Read a difficulty from its name, ignoring case.
+    ///
+    /// ```
+    /// # use sudoku::rating::Difficulty;
+    /// assert_eq!(Ok(Difficulty::Medium), "Medium".parse());
+    /// assert!("impossible".parse::<Difficulty>().is_err());
+    /// ```
+    fn from_str(name: &str) -> Result<Self, ParseError> {
+        Difficulty::ALL
+            .iter()
+            .find(|difficulty| name.eq_ignore_ascii_case(difficulty.name()))
+            .copied()
+            .ok_or(ParseError::InvalidDifficulty)
+    }
+}
+
+/// The weight that each kind of step adds to a rating's score, which can be
+/// tuned so that scores line up with another rating scale.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct RatingConfig {
+    pub single: u32,
+    pub hidden_single: u32,
+    pub guess: u32,
+    pub backtrack: u32,
+}
+
+impl Default for RatingConfig {
+    fn default() -> Self {
+        RatingConfig {
+            single: 1,
+            hidden_single: 2,
+            guess: 10,
+            backtrack: 20,
+        }
+    }
+}
+
+/// How hard a puzzle is, both as a difficulty and as a score that tells
+/// puzzles of the same difficulty apart.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    /// The total weight of the steps taken to solve the puzzle. With the
+    /// default weights, that is 1 for each naked single, 2 for each hidden
+    /// single, 10 for each guess, and 20 for each backtrack.
+    pub score: u32,
+}
+
+impl Rating {
+    /// Rate a puzzle from the steps that the solver took to solve it, with
+    /// the default weights.
+    ///
+    /// ```
+    /// # use sudoku::{rating::{Difficulty, Rating}, step::Step};
+    /// let steps = [
+    ///     Step::Single { row: 0, col: 0, n: 1 },
+    ///     Step::Guess { row: 0, col: 1, n: 2 },
+    /// ];
+    /// let rating = Rating::from_steps(&steps);
+    /// assert_eq!(Difficulty::Hard, rating.difficulty);
+    /// assert_eq!(11, rating.score);
+    /// ```
+    pub fn from_steps(steps: &[Step]) -> Self {
+        Rating::from_steps_with(steps, &RatingConfig::default())
+    }
+
+    /// Rate a puzzle from the steps that the solver took to solve it, with
+    /// the weights in config. The difficulty does not depend on the weights.
+    ///
+    /// ```
+    /// # use sudoku::{rating::{Rating, RatingConfig}, step::Step};
+    /// let steps = [
+    ///     Step::Single { row: 0, col: 0, n: 1 },
+    ///     Step::Guess { row: 0, col: 1, n: 2 },
+    /// ];
+    /// let config = RatingConfig {
+    ///     guess: 50,
+    ///     ..RatingConfig::default()
+    /// };
+    /// assert_eq!(51, Rating::from_steps_with(&steps, &config).score);
+    /// ```
+    pub fn from_steps_with(steps: &[Step], config: &RatingConfig) -> Self {
+        let mut rating = Rating {
+            difficulty: Difficulty::Easy,
+            score: 0,
+        };
+        for step in steps {
+            let (difficulty, weight) = match step {
+                Step::Single { .. } => (Difficulty::Easy, config.single),
+                Step::HiddenSingle { .. } => (Difficulty::Medium, config.hidden_single),
+                Step::Guess { .. } => (Difficulty::Hard, config.guess),
+                Step::Backtrack { .. } => (Difficulty::Expert, config.backtrack),
+            };
+            rating.difficulty = rating.difficulty.max(difficulty);
+            rating.score = rating.score.saturating_add(weight);
+        }
+        rating
+    }
+}
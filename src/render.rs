@@ -0,0 +1,355 @@
+//! Drawing puzzles as images, for printing or sharing.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, render::{self, RenderOptions}};
+//! let puzzle = SudokuPuzzle::empty();
+//! let options = RenderOptions {
+//!     candidates: true,
+//!     highlight: vec![(4, 4)],
+//!     ..RenderOptions::default()
+//! };
+//! let svg = render::svg(&puzzle, &options);
+//! assert!(svg.starts_with("<svg"));
+//!
+//! let image = render::raster(&puzzle, &options);
+//! assert_eq!(image.width * image.height * 3, image.pixels.len());
+//! ```
+
+use crate::puzzle::SudokuPuzzle;
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+/// What to draw besides the puzzle's numbers.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// Fill the empty squares from the solution, in a different color, if
+    /// the puzzle can be solved.
+    pub solution: bool,
+    /// Write the candidates of each empty square in small digits.
+    pub candidates: bool,
+    /// The (row, col) of squares to shade. Squares outside of the grid are
+    /// ignored.
+    pub highlight: Vec<(usize, usize)>,
+}
+
+/// The squares to shade that are inside the grid.
+fn highlighted(options: &RenderOptions) -> impl Iterator<Item = (usize, usize)> + '_ {
+    options
+        .highlight
+        .iter()
+        .copied()
+        .filter(|(row, col)| *row < 9 && *col < 9)
+}
+
+/// What is drawn in each square.
+enum Content {
+    Given(u8),
+    Filled(u8),
+    Solved(u8),
+    Candidates(Vec<u8>),
+}
+
+/// The contents of every square, row by row.
+fn contents(puzzle: &SudokuPuzzle, options: &RenderOptions) -> Vec<Content> {
+    let solution = if options.solution {
+        puzzle.solve()
+    } else {
+        None
+    };
+    let mut contents = Vec::with_capacity(81);
+    for row in 0..9 {
+        for col in 0..9 {
+            contents.push(match (puzzle.get(row, col), solution) {
+                (Some(n), _) if puzzle.is_given(row, col) => Content::Given(n),
+                (Some(n), _) => Content::Filled(n),
+                (None, Some(solution)) => Content::Solved(solution.get(row, col)),
+                (None, None) if options.candidates => {
+                    Content::Candidates(puzzle.candidates(row, col).to_vec())
+                }
+                (None, None) => Content::Candidates(Vec::new()),
+            });
+        }
+    }
+    contents
+}
+
+const CELL: usize = 50;
+const MARGIN: usize = 10;
+
+/// The puzzle as a standalone SVG document.
+pub fn svg(puzzle: &SudokuPuzzle, options: &RenderOptions) -> String {
+    let size = CELL * 9 + MARGIN * 2;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\" font-family=\"sans-serif\">\n",
+        size
+    );
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+
+    for (row, col) in highlighted(options) {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#fff3a0\"/>",
+            MARGIN + col * CELL,
+            MARGIN + row * CELL,
+            CELL,
+            CELL
+        );
+    }
+
+    for (square, content) in contents(puzzle, options).iter().enumerate() {
+        let x = MARGIN + (square % 9) * CELL;
+        let y = MARGIN + (square / 9) * CELL;
+        let (n, color, weight) = match content {
+            Content::Given(n) => (*n, "black", "bold"),
+            Content::Filled(n) => (*n, "#2a6fdb", "normal"),
+            Content::Solved(n) => (*n, "#999999", "normal"),
+            Content::Candidates(candidates) => {
+                for n in candidates {
+                    let sub = CELL / 3;
+                    let _ = writeln!(
+                        svg,
+                        "<text x=\"{}\" y=\"{}\" font-size=\"12\" fill=\"#666666\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                        x + (usize::from(n - 1) % 3) * sub + sub / 2,
+                        y + (usize::from(n - 1) / 3) * sub + sub / 2,
+                        n
+                    );
+                }
+                continue;
+            }
+        };
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" font-size=\"32\" font-weight=\"{}\" fill=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+            x + CELL / 2,
+            y + CELL / 2,
+            weight,
+            color,
+            n
+        );
+    }
+
+    for i in 0..=9 {
+        let at = MARGIN + i * CELL;
+        let width = if i % 3 == 0 { 3 } else { 1 };
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"black\" stroke-width=\"{3}\"/>",
+            at,
+            MARGIN,
+            size - MARGIN,
+            width
+        );
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{1}\" y1=\"{0}\" x2=\"{2}\" y2=\"{0}\" stroke=\"black\" stroke-width=\"{3}\"/>",
+            at,
+            MARGIN,
+            size - MARGIN,
+            width
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The puzzle as an HTML page with the SVG drawing inline.
+pub fn html(puzzle: &SudokuPuzzle, options: &RenderOptions) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Sudoku</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        svg(puzzle, options)
+    )
+}
+
+/// An image as rows of RGB pixels, three bytes each, from the top left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: [u8; 3]) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                let i = (y * self.width + x) * 3;
+                self.pixels[i..i + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Draw the digit n with its top left at (x, y), each font pixel
+    /// scale pixels wide.
+    fn digit(&mut self, n: u8, x: usize, y: usize, scale: usize, color: [u8; 3]) {
+        for (dy, bits) in FONT[usize::from(n - 1)].iter().enumerate() {
+            for dx in 0..5 {
+                if bits & (0b10000 >> dx) != 0 {
+                    self.fill(x + dx * scale, y + dy * scale, scale, scale, color);
+                }
+            }
+        }
+    }
+}
+
+/// The digits 1 to 9 as 5x7 bitmaps, one row per byte.
+const FONT: [[u8; 7]; 9] = [
+    [
+        0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+    ],
+    [
+        0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+    ],
+    [
+        0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+    ],
+    [
+        0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+    ],
+    [
+        0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+    ],
+    [
+        0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+    ],
+    [
+        0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+    ],
+    [
+        0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+    ],
+    [
+        0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+    ],
+];
+
+/// The digits 1 to 9 in superscript, for candidates in `text`.
+const SMALL_DIGITS: [char; 9] = ['¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// The puzzle as text drawn with box-drawing characters: heavy lines
+/// between the 3x3 boxes and light lines between squares, with each square
+/// three lines tall so that its candidates, in small digits, sit in a 3x3
+/// grid of their own. It is 73 columns wide. Shading is not drawn.
+///
+/// ```
+/// # use sudoku::{puzzle::SudokuPuzzle, render::{self, RenderOptions}};
+/// let puzzle = SudokuPuzzle::from_string(
+///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+/// )
+/// .unwrap();
+/// let options = RenderOptions {
+///     candidates: true,
+///     ..RenderOptions::default()
+/// };
+/// let text = render::text(&puzzle, &options);
+/// let lines: Vec<&str> = text.lines().collect();
+/// assert_eq!(37, lines.len());
+/// assert!(lines.iter().all(|line| line.chars().count() == 73));
+/// assert_eq!(
+///     "┃   4   │   1   │   ⁵   ┃   ⁵   │   3   │   6   ┃       │       │       ┃",
+///     lines[2]
+/// );
+/// ```
+pub fn text(puzzle: &SudokuPuzzle, options: &RenderOptions) -> String {
+    let contents = contents(puzzle, options);
+    let mut text = rule('━', ['┏', '┯', '┳', '┓']);
+    for row in 0..9 {
+        for line in 0..3 {
+            text.push('┃');
+            for col in 0..9 {
+                match &contents[row * 9 + col] {
+                    Content::Given(n) | Content::Filled(n) | Content::Solved(n) => match line {
+                        1 => {
+                            let _ = write!(text, "   {}   ", n);
+                        }
+                        _ => text.push_str("       "),
+                    },
+                    Content::Candidates(candidates) => {
+                        text.push(' ');
+                        for n in line as u8 * 3 + 1..=line as u8 * 3 + 3 {
+                            text.push(if candidates.contains(&n) {
+                                SMALL_DIGITS[usize::from(n - 1)]
+                            } else {
+                                ' '
+                            });
+                            text.push(' ');
+                        }
+                    }
+                }
+                text.push(if col % 3 == 2 { '┃' } else { '│' });
+            }
+            text.push('\n');
+        }
+        text.push_str(&match row {
+            8 => rule('━', ['┗', '┷', '┻', '┛']),
+            _ if row % 3 == 2 => rule('━', ['┣', '┿', '╋', '┫']),
+            _ => rule('─', ['┠', '┼', '╂', '┨']),
+        });
+    }
+    text
+}
+
+/// A line across the grid of `text` drawn with line, with the joints given
+/// as [left end, between squares, between 3x3 boxes, right end].
+fn rule(line: char, [left, between, between_boxes, right]: [char; 4]) -> String {
+    let mut rule = String::from(left);
+    for col in 0..9 {
+        rule.extend([line; 7]);
+        rule.push(match col {
+            8 => right,
+            _ if col % 3 == 2 => between_boxes,
+            _ => between,
+        });
+    }
+    rule.push('\n');
+    rule
+}
+
+/// The puzzle drawn as pixels, the same way as by `svg` but with a
+/// built-in bitmap font.
+pub fn raster(puzzle: &SudokuPuzzle, options: &RenderOptions) -> Image {
+    const WHITE: [u8; 3] = [255, 255, 255];
+    const BLACK: [u8; 3] = [0, 0, 0];
+    let size = CELL * 9 + MARGIN * 2;
+    let mut image = Image {
+        width: size,
+        height: size,
+        pixels: vec![255; size * size * 3],
+    };
+    image.fill(0, 0, size, size, WHITE);
+
+    for (row, col) in highlighted(options) {
+        let (x, y) = (MARGIN + col * CELL, MARGIN + row * CELL);
+        image.fill(x, y, CELL, CELL, [0xff, 0xf3, 0xa0]);
+    }
+
+    for (square, content) in contents(puzzle, options).iter().enumerate() {
+        let x = MARGIN + (square % 9) * CELL;
+        let y = MARGIN + (square / 9) * CELL;
+        let (n, color) = match content {
+            Content::Given(n) => (*n, BLACK),
+            Content::Filled(n) => (*n, [0x2a, 0x6f, 0xdb]),
+            Content::Solved(n) => (*n, [0x99, 0x99, 0x99]),
+            Content::Candidates(candidates) => {
+                let sub = CELL / 3;
+                for n in candidates {
+                    let sub_x = x + (usize::from(n - 1) % 3) * sub + (sub - 10) / 2;
+                    let sub_y = y + (usize::from(n - 1) / 3) * sub + (sub - 14) / 2;
+                    image.digit(*n, sub_x, sub_y, 2, [0x66, 0x66, 0x66]);
+                }
+                continue;
+            }
+        };
+        // A 5x7 digit at 5x scale is 25x35
+        image.digit(n, x + (CELL - 25) / 2, y + (CELL - 35) / 2, 5, color);
+    }
+
+    for i in 0..=9 {
+        let width = if i % 3 == 0 { 3 } else { 1 };
+        let at = MARGIN + i * CELL - width / 2;
+        image.fill(at, MARGIN - 1, width, CELL * 9 + 3, BLACK);
+        image.fill(MARGIN - 1, at, CELL * 9 + 3, width, BLACK);
+    }
+
+    image
+}
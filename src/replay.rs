@@ -0,0 +1,372 @@
+//! Recordings of games as they were played: every placement, erasure, note,
+//! and hint, with the time it was made, so that a solve can be reviewed,
+//! shared, or played back.
+//!
+//! A recording is written as text, one line per action after the givens:
+//!
+//! ```text
+//! givens 410036000007000850600000000090000200006070008000000091002014000000003000740008509
+//! 1500 place 0 2 5
+//! 2250 mark 0 3 2
+//! 2400 mark 0 3 7
+//! 3100 erase 0 2
+//! 4000 undo
+//! ```
+//!
+//! Each action starts with the milliseconds since the game began, which
+//! never go down from one line to the next. The actions are:
+//!
+//! | Action            | Meaning                                             |
+//! | ----------------- | --------------------------------------------------- |
+//! | `place row col n` | fill (row, col) with n                              |
+//! | `erase row col`   | empty (row, col) and remove its pencil marks        |
+//! | `mark row col n`  | toggle the pencil mark n in (row, col)              |
+//! | `fill-marks`      | fill every empty square's marks with its candidates |
+//! | `undo`, `redo`    | undo or redo the last move                          |
+//! | `hint row col n`  | the player was shown that n goes in (row, col)      |
+//!
+//! ```
+//! # use core::time::Duration;
+//! # use sudoku::{puzzle::SudokuPuzzle, replay::{Action, Recording}};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let mut recording = Recording::new(puzzle);
+//! let place = Action::Place { row: 0, col: 2, n: 5 };
+//! recording.record(Duration::from_millis(1500), place).unwrap();
+//! recording.record(Duration::from_millis(3100), Action::Erase { row: 0, col: 2 }).unwrap();
+//!
+//! let text = recording.to_string();
+//! assert!(text.ends_with("1500 place 0 2 5\n3100 erase 0 2\n"));
+//! let shared: Recording = text.parse().unwrap();
+//! assert_eq!(recording, shared);
+//!
+//! let mut playback = shared.playback();
+//! assert_eq!(Some(Duration::from_millis(1500)), playback.next_at());
+//! playback.next();
+//! assert_eq!(Some(5), playback.puzzle().get(0, 2));
+//! playback.next();
+//! assert_eq!(None, playback.puzzle().get(0, 2));
+//! assert_eq!(None, playback.next());
+//! ```
+
+use crate::{
+    error::{check_number, Error, ParseError},
+    history::History,
+    puzzle::SudokuPuzzle,
+};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt, slice, str::FromStr, time::Duration};
+
+/// Something a player did to the puzzle.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Action {
+    /// Fill (row, col) with n.
+    Place { row: usize, col: usize, n: u8 },
+    /// Empty (row, col) and remove its pencil marks.
+    Erase { row: usize, col: usize },
+    /// Add the pencil mark n to (row, col) if it is absent, otherwise remove
+    /// it.
+    Mark { row: usize, col: usize, n: u8 },
+    /// Fill every empty square's pencil marks with its candidates.
+    FillMarks,
+    /// Undo the last move, if there is one.
+    Undo,
+    /// Redo the last undone move, if there is one.
+    Redo,
+    /// The player was shown that n goes in (row, col). The board is left as
+    /// it is; filling the square in is a separate [`Action::Place`].
+    Hint { row: usize, col: usize, n: u8 },
+}
+
+impl Action {
+    /// The square the action is made in, if it is made in one.
+    pub fn square(self) -> Option<(usize, usize)> {
+        match self {
+            Action::Place { row, col, .. }
+            | Action::Erase { row, col }
+            | Action::Mark { row, col, .. }
+            | Action::Hint { row, col, .. } => Some((row, col)),
+            Action::FillMarks | Action::Undo | Action::Redo => None,
+        }
+    }
+
+    /// Make the action in history, as a player would. Undoing or redoing
+    /// with nothing to undo or redo does nothing.
+    pub fn apply(self, history: &mut History) -> Result<(), Error> {
+        match self {
+            Action::Place { row, col, n } => history.place(row, col, n),
+            Action::Erase { row, col } => history.clear(row, col),
+            Action::Mark { row, col, n } => history.toggle_mark(row, col, n),
+            Action::FillMarks => {
+                history.fill_marks();
+                Ok(())
+            }
+            Action::Undo => {
+                history.undo();
+                Ok(())
+            }
+            Action::Redo => {
+                history.redo();
+                Ok(())
+            }
+            Action::Hint { row, col, n } => {
+                check_number(row, col, n)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Read the words of an action, such as "place 0 2 5".
+    fn parse<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<Action> {
+        let kind = words.next()?;
+        let mut number = || -> Option<usize> { words.next()?.parse().ok() };
+        let action = match kind {
+            "place" => Action::Place {
+                row: number()?,
+                col: number()?,
+                n: u8::try_from(number()?).ok()?,
+            },
+            "erase" => Action::Erase {
+                row: number()?,
+                col: number()?,
+            },
+            "mark" => Action::Mark {
+                row: number()?,
+                col: number()?,
+                n: u8::try_from(number()?).ok()?,
+            },
+            "hint" => Action::Hint {
+                row: number()?,
+                col: number()?,
+                n: u8::try_from(number()?).ok()?,
+            },
+            "fill-marks" => Action::FillMarks,
+            "undo" => Action::Undo,
+            "redo" => Action::Redo,
+            _ => return None,
+        };
+        match words.next() {
+            Some(_) => None,
+            None => Some(action),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    /// The action as it is written in a recording, without its time.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Action::Place { row, col, n } => write!(f, "place {} {} {}", row, col, n),
+            Action::Erase { row, col } => write!(f, "erase {} {}", row, col),
+            Action::Mark { row, col, n } => write!(f, "mark {} {} {}", row, col, n),
+            Action::FillMarks => write!(f, "fill-marks"),
+            Action::Undo => write!(f, "undo"),
+            Action::Redo => write!(f, "redo"),
+            Action::Hint { row, col, n } => write!(f, "hint {} {} {}", row, col, n),
+        }
+    }
+}
+
+/// An action and when it was made.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Event {
+    /// The time since the game began.
+    pub at: Duration,
+    pub action: Action,
+}
+
+/// A puzzle's givens and every action made to them, in order.
+#[derive(Clone)]
+pub struct Recording {
+    givens: SudokuPuzzle,
+    events: Vec<Event>,
+    /// The game as it stands after the last action, which the next action
+    /// must be possible in.
+    end: History,
+}
+
+impl Recording {
+    /// Begin recording a game of the givens of puzzle. Any other numbers and
+    /// pencil marks are left out.
+    pub fn new(puzzle: SudokuPuzzle) -> Self {
+        let mut givens = puzzle;
+        givens.reset();
+        Recording {
+            end: History::new(givens.clone()),
+            givens,
+            events: Vec::new(),
+        }
+    }
+
+    /// The puzzle the game started from.
+    pub fn puzzle(&self) -> &SudokuPuzzle {
+        &self.givens
+    }
+
+    /// The actions made, in the order they were made.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// The time of the last action, or zero if nothing has been done.
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |event| event.at)
+    }
+
+    /// The game after every action recorded so far.
+    pub fn history(&self) -> &History {
+        &self.end
+    }
+
+    /// Record action, made at the time since the game began. An action that
+    /// a player could not make, such as changing a given, is not recorded.
+    /// Times before the last action's are taken to be the same as it.
+    pub fn record(&mut self, at: Duration, action: Action) -> Result<(), Error> {
+        action.apply(&mut self.end)?;
+        let at = at.max(self.duration());
+        self.events.push(Event { at, action });
+        Ok(())
+    }
+
+    /// Play the game back from the start, one action at a time.
+    pub fn playback(&self) -> Playback<'_> {
+        Playback {
+            history: History::new(self.givens.clone()),
+            events: self.events.iter(),
+        }
+    }
+
+    /// The game as it stood at a time since it began, after every action
+    /// made up to and including then.
+    ///
+    /// ```
+    /// # use core::time::Duration;
+    /// # use sudoku::{puzzle::SudokuPuzzle, replay::{Action, Recording}};
+    /// let mut recording = Recording::new(SudokuPuzzle::empty());
+    /// let second = Duration::from_secs(1);
+    /// recording.record(second, Action::Place { row: 4, col: 4, n: 5 }).unwrap();
+    /// recording.record(second * 2, Action::Undo).unwrap();
+    ///
+    /// assert_eq!(None, recording.state_at(second / 2).puzzle().get(4, 4));
+    /// assert_eq!(Some(5), recording.state_at(second).puzzle().get(4, 4));
+    /// assert_eq!(None, recording.state_at(second * 2).puzzle().get(4, 4));
+    /// ```
+    pub fn state_at(&self, at: Duration) -> History {
+        let mut playback = self.playback();
+        while playback.next_at().is_some_and(|next| next <= at) {
+            playback.next();
+        }
+        playback.history
+    }
+}
+
+impl PartialEq for Recording {
+    fn eq(&self, other: &Self) -> bool {
+        self.givens.to_vec() == other.givens.to_vec() && self.events == other.events
+    }
+}
+
+impl fmt::Debug for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recording")
+            .field("givens", &self.givens.to_vec())
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+impl fmt::Display for Recording {
+    /// The recording in the form read by `from_str`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "givens ")?;
+        for n in self.givens.to_vec() {
+            write!(f, "{}", n)?;
+        }
+        writeln!(f)?;
+        for event in self.events.iter() {
+            writeln!(f, "{} {}", event.at.as_millis(), event.action)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = ParseError;
+
+    /// Read a recording, checking that every action could have been made
+    /// when it was. Blank lines are ignored.
+    ///
+    /// ```
+    /// # use sudoku::{error::ParseError, replay::Recording};
+    /// let text = format!("givens 1{}\n\n100 place 0 1 2\n200 place 0 0 3\n", "0".repeat(80));
+    /// let err = text.parse::<Recording>().err();
+    /// assert_eq!(Some(ParseError::InvalidReplay { line: 4 }), err);
+    /// ```
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let invalid = |line: usize| ParseError::InvalidReplay { line };
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (line, givens) = lines.next().ok_or_else(|| invalid(1))?;
+        let givens = givens
+            .strip_prefix("givens ")
+            .and_then(|givens| SudokuPuzzle::from_string(givens).ok())
+            .ok_or_else(|| invalid(line))?;
+
+        let mut recording = Recording::new(givens);
+        for (line, text) in lines {
+            let mut words = text.split_whitespace();
+            let at = words
+                .next()
+                .and_then(|ms| ms.parse().ok())
+                .map(Duration::from_millis)
+                .filter(|at| *at >= recording.duration())
+                .ok_or_else(|| invalid(line))?;
+            let action = Action::parse(words).ok_or_else(|| invalid(line))?;
+            recording.record(at, action).map_err(|_| invalid(line))?;
+        }
+        Ok(recording)
+    }
+}
+
+/// A recording being played back, which makes each action in turn.
+pub struct Playback<'a> {
+    history: History,
+    events: slice::Iter<'a, Event>,
+}
+
+impl Playback<'_> {
+    /// The game as it stands after the actions played so far.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// The puzzle as it stands after the actions played so far.
+    pub fn puzzle(&self) -> &SudokuPuzzle {
+        self.history.puzzle()
+    }
+
+    /// When the next action was made, or `None` once every action has been
+    /// played.
+    pub fn next_at(&self) -> Option<Duration> {
+        self.events.as_slice().first().map(|event| event.at)
+    }
+}
+
+impl Iterator for Playback<'_> {
+    type Item = Event;
+
+    /// Make the next action and return it.
+    fn next(&mut self) -> Option<Event> {
+        let event = *self.events.next()?;
+        let result = event.action.apply(&mut self.history);
+        debug_assert!(result.is_ok());
+        Some(event)
+    }
+}
@@ -0,0 +1,272 @@
+//! Enumerating every solution of a puzzle, in a search that can be stopped
+//! and picked up again later.
+//!
+//! Exhaustive jobs can run for hours. [`Solutions::checkpoint`] captures how
+//! far a search has got as a few lines of text, which can be written to disk
+//! periodically, and [`Solutions::resume`] continues the search from that
+//! text without repeating any of the work done before it.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, search::Solutions};
+//! let mut top_row = String::from("123456");
+//! top_row.push_str(&"0".repeat(75));
+//! let puzzle = SudokuPuzzle::from_string(&top_row).unwrap();
+//!
+//! let mut solutions = puzzle.solutions();
+//! let first = solutions.next().unwrap();
+//! let saved = solutions.checkpoint();
+//!
+//! // Later, possibly in another process
+//! let mut resumed = Solutions::resume(&saved).unwrap();
+//! assert_eq!(1, resumed.found());
+//! let second = resumed.next().unwrap();
+//! assert_ne!(first, second);
+//! assert_eq!(puzzle.solutions().nth(1), Some(second));
+//! ```
+
+use crate::{
+    error::{Error, ParseError},
+    nine_set::NineSet,
+    puzzle::SudokuPuzzle,
+    solution::Solution,
+};
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+/// An iterator over every solution of a puzzle, in a fixed order.
+///
+/// ```
+/// # use sudoku::puzzle::SudokuPuzzle;
+/// let puzzle = SudokuPuzzle::from_string(
+///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+/// )
+/// .unwrap();
+/// assert_eq!(1, puzzle.solutions().count());
+/// ```
+pub struct Solutions {
+    root: SudokuPuzzle,
+    /// The next puzzle to deduce from, until the search has started.
+    pending: Option<SudokuPuzzle>,
+    /// The guesses that lead to the part of the search being explored.
+    stack: Vec<Frame>,
+    found: u64,
+}
+
+/// A guess made during the search.
+pub(crate) struct Frame {
+    /// The puzzle just before the guess was made.
+    puzzle: SudokuPuzzle,
+    row: usize,
+    col: usize,
+    /// The guess currently being explored.
+    guess: u8,
+    /// The guesses still to be explored after this one.
+    remaining: NineSet,
+}
+
+impl Solutions {
+    /// Start a search for the solutions of puzzle.
+    pub(crate) fn new(puzzle: &SudokuPuzzle) -> Self {
+        Solutions {
+            root: puzzle.clone(),
+            pending: Some(puzzle.clone()),
+            stack: Vec::new(),
+            found: 0,
+        }
+    }
+
+    /// Start a search for the solutions of puzzle that keeps its guesses in
+    /// stack, which must be empty, so that its memory can be reused.
+    pub(crate) fn with_stack(puzzle: &SudokuPuzzle, stack: Vec<Frame>) -> Self {
+        debug_assert!(stack.is_empty());
+        Solutions {
+            stack,
+            ..Solutions::new(puzzle)
+        }
+    }
+
+    /// Stop the search, handing back the memory of its guesses, emptied.
+    pub(crate) fn into_stack(mut self) -> Vec<Frame> {
+        self.stack.clear();
+        self.stack
+    }
+
+    /// The number of solutions produced so far, including those produced
+    /// before the checkpoint this search was resumed from.
+    pub fn found(&self) -> u64 {
+        self.found
+    }
+
+    /// Describe how far this search has got, as text that can be passed to
+    /// [`Solutions::resume`].
+    pub fn checkpoint(&self) -> String {
+        let mut text = String::from("puzzle ");
+        for n in self.root.to_vec() {
+            text.push(char::from(b'0' + n));
+        }
+
+        let state = if self.pending.is_some() {
+            "new"
+        } else if self.stack.is_empty() {
+            "done"
+        } else {
+            "searching"
+        };
+        write!(text, "\nfound {}\nstate {}\n", self.found, state).unwrap();
+
+        for frame in &self.stack {
+            write!(text, "guess {} {} {} ", frame.row, frame.col, frame.guess).unwrap();
+            if frame.remaining.is_empty() {
+                text.push('-');
+            }
+            for n in frame.remaining {
+                text.push(char::from(b'0' + n));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Continue the search described by a checkpoint. Solutions produced
+    /// before the checkpoint was taken are not produced again.
+    ///
+    /// ```
+    /// # use sudoku::{error::ParseError, search::Solutions, Error};
+    /// let err = Solutions::resume("puzzle 123").err();
+    /// assert_eq!(Some(Error::Parse(ParseError::WrongLength { len: 3 })), err);
+    ///
+    /// let err = Solutions::resume(&format!("puzzle {}\nfound 0\nstate maybe", "0".repeat(81))).err();
+    /// assert_eq!(Some(Error::Parse(ParseError::InvalidCheckpoint { line: 3 })), err);
+    /// ```
+    pub fn resume(checkpoint: &str) -> Result<Self, Error> {
+        let mut lines = checkpoint.lines().map(str::trim).filter(|l| !l.is_empty());
+        let invalid = |line: usize| ParseError::InvalidCheckpoint { line };
+
+        let root = lines
+            .next()
+            .and_then(|l| l.strip_prefix("puzzle "))
+            .ok_or_else(|| invalid(1))?;
+        let root = SudokuPuzzle::from_string(root)?;
+
+        let found = lines
+            .next()
+            .and_then(|l| l.strip_prefix("found "))
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| invalid(2))?;
+
+        let mut search = Solutions::new(&root);
+        search.found = found;
+        match lines.next().and_then(|l| l.strip_prefix("state ")) {
+            Some("new") => {}
+            Some("done") => search.pending = None,
+            Some("searching") => {
+                search.pending = None;
+                let mut puzzle = root;
+                for (i, line) in lines.by_ref().enumerate() {
+                    let frame = Frame::parse(line, puzzle).ok_or_else(|| invalid(i + 4))?;
+                    puzzle = frame.puzzle.clone();
+                    puzzle.put(frame.row, frame.col, Some(frame.guess));
+                    search.stack.push(frame);
+                }
+                if search.stack.is_empty() {
+                    return Err(invalid(4).into());
+                }
+            }
+            _ => return Err(invalid(3).into()),
+        }
+
+        match lines.next() {
+            Some(_) => Err(invalid(4 + search.stack.len()).into()),
+            None => Ok(search),
+        }
+    }
+}
+
+impl Frame {
+    /// Read a "guess row col n remaining" line, made after reaching puzzle,
+    /// and check that it is the guess the search would have made there.
+    fn parse(line: &str, mut puzzle: SudokuPuzzle) -> Option<Frame> {
+        let mut words = line.strip_prefix("guess ")?.split(' ');
+        let row = words.next()?.parse().ok()?;
+        let col = words.next()?.parse().ok()?;
+        let guess = words.next()?.parse().ok()?;
+        let remaining = match words.next()? {
+            "-" => NineSet::empty(),
+            digits => digits
+                .chars()
+                .map(|c| c.to_digit(10).filter(|n| *n != 0).map(|n| n as u8))
+                .collect::<Option<NineSet>>()?,
+        };
+        if words.next().is_some() {
+            return None;
+        }
+
+        if !puzzle.deduce() || puzzle.count_unfilled() == 0 || puzzle.guess_square() != (row, col) {
+            return None;
+        }
+        let candidates = puzzle.candidates(row, col);
+        if !candidates.contains(guess)
+            || remaining.contains(guess)
+            || remaining != remaining & candidates
+        {
+            return None;
+        }
+
+        Some(Frame {
+            puzzle,
+            row,
+            col,
+            guess,
+            remaining,
+        })
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Solution;
+
+    fn next(&mut self) -> Option<Solution> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("next_solution", found = self.found).entered();
+        loop {
+            // Start from the root, or else from the next untried guess,
+            // backtracking past any guesses that have been exhausted.
+            let mut puzzle = match self.pending.take() {
+                Some(puzzle) => puzzle,
+                None => loop {
+                    let frame = self.stack.last_mut()?;
+                    if let Some(guess) = frame.remaining.iter().next() {
+                        frame.remaining.remove(guess);
+                        frame.guess = guess;
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(row = frame.row, col = frame.col, n = guess, "guess");
+                        let mut puzzle = frame.puzzle.clone();
+                        puzzle.put(frame.row, frame.col, Some(guess));
+                        break puzzle;
+                    }
+                    self.stack.pop();
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(depth = self.stack.len(), "backtrack");
+                },
+            };
+
+            if !puzzle.deduce() {
+                continue;
+            }
+            if puzzle.count_unfilled() == 0 {
+                self.found = self.found.saturating_add(1);
+                return Some(Solution::new(&puzzle));
+            }
+
+            let (row, col) = puzzle.guess_square();
+            let remaining = puzzle.candidates(row, col);
+            self.stack.push(Frame {
+                puzzle,
+                row,
+                col,
+                guess: 0,
+                remaining,
+            });
+        }
+    }
+}
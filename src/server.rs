@@ -0,0 +1,304 @@
+//! An HTTP solver service, enabled with the `server` feature.
+//!
+//! Every endpoint takes a JSON object in a POST body and answers with a JSON
+//! object. Puzzles are 81 digits, where 0 or '.' is an empty square.
+//!
+//! | Endpoint     | Request                             | Response                                                |
+//! | ------------ | ----------------------------------- | ------------------------------------------------------- |
+//! | `/solve`     | `{"puzzle": "..."}`                 | `{"solution": "..."}`, or null if there is none         |
+//! | `/solve-all` | `{"puzzles": ["...", "..."]}`       | `{"solutions": ["...", null]}`, in the same order       |
+//! | `/rate`      | `{"puzzle": "..."}`                 | `{"difficulty": "hard", "score": 87}`                   |
+//! | `/generate`  | `{"difficulty": "hard", "seed": 7}` | `{"puzzle": "...", "difficulty": "hard", "score": 87}`  |
+//! | `/check`     | `{"puzzle": "..."}`                 | `{"status": "unique", "conflicts": []}`                 |
+//!
+//! Both fields of `/generate` are optional; the difficulty is medium by
+//! default. `/solve-all` solves its puzzles in parallel with the `parallel`
+//! feature, which `server` turns on. A puzzle that cannot be read is answered
+//! with status 400 and `{"error": "..."}`, one that the solver spends more
+//! than ten seconds on with status 503, and one that would need more than a
+//! million guesses (a hundred thousand for `/rate`) with status 422.
+//! `/solve-all` takes at most 1000 puzzles, and answers longer lists with
+//! status 413. `/generate` answers with status 503 if a hundred grids give no
+//! puzzle of the difficulty.
+//!
+//! `GET /metrics` reports, in the Prometheus text format, the requests
+//! answered by endpoint and status, histograms of how long they took, the
+//! requests that timed out, and the puzzles checked that have more than one
+//! solution.
+
+use crate::{
+    batch,
+    context::SolverPool,
+    deterministic,
+    error::{GenerationError, LimitExceeded, ParseError},
+    generate::generate_within,
+    limits::SolverLimits,
+    metrics::Metrics,
+    puzzle::SudokuPuzzle,
+    rating::{Difficulty, RatingConfig},
+};
+use axum::{
+    extract::{MatchedPath, Request},
+    http::StatusCode,
+    middleware::{self, Next},
+    routing::{get, post},
+    Json, Router,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    io,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+/// How long the solver may work on a request before it is answered with
+/// status 503. The searches of every endpoint stop there too, through
+/// `LIMITS`, and generation is bounded by `GENERATE_ATTEMPTS`.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The limits on solving and checking a puzzle, which stop the search itself
+/// rather than abandoning it.
+const LIMITS: SolverLimits = SolverLimits {
+    max_nodes: Some(1_000_000),
+    max_guess_depth: None,
+    max_time: Some(TIMEOUT),
+};
+
+/// The limits on rating a puzzle, which keeps every step of its search, so
+/// fewer nodes are allowed than for solving: a few tens of megabytes of
+/// steps at most.
+const RATING_LIMITS: SolverLimits = SolverLimits {
+    max_nodes: Some(100_000),
+    ..LIMITS
+};
+
+/// The most puzzles `/solve-all` takes at once; longer lists are answered
+/// with status 413.
+const MAX_PUZZLES: usize = 1000;
+
+/// How many grids `/generate` digs for a puzzle of the difficulty before
+/// answering with status 503. Expert puzzles, the rarest, take a few on
+/// average.
+const GENERATE_ATTEMPTS: usize = 100;
+
+/// The solver contexts shared by the requests being handled, so that
+/// solving a puzzle does not allocate.
+static SOLVERS: SolverPool = SolverPool::new();
+
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics::new());
+
+/// The metrics, which are only ever updated a field at a time, so a poisoned
+/// lock is used as is.
+fn metrics() -> MutexGuard<'static, Metrics> {
+    METRICS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A response, or an error status with an error message.
+type Response = Result<Json<Value>, (StatusCode, Json<Value>)>;
+
+#[derive(Deserialize)]
+struct PuzzleRequest {
+    puzzle: String,
+}
+
+#[derive(Deserialize)]
+struct PuzzlesRequest {
+    puzzles: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GenerateRequest {
+    difficulty: Option<String>,
+    seed: Option<u64>,
+}
+
+fn bad_request(message: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": message })))
+}
+
+fn internal_error(e: tokio::task::JoinError) -> (StatusCode, Json<Value>) {
+    let error = Json(json!({ "error": e.to_string() }));
+    (StatusCode::INTERNAL_SERVER_ERROR, error)
+}
+
+/// The response to a search that broke one of `LIMITS`: 503, like any other
+/// timeout, if it ran out of time, or else 422.
+fn limit_exceeded(e: LimitExceeded) -> (StatusCode, Json<Value>) {
+    let error = Json(json!({ "error": e.to_string() }));
+    match e {
+        LimitExceeded::Time { .. } => {
+            metrics().record_timeout();
+            (StatusCode::SERVICE_UNAVAILABLE, error)
+        }
+        _ => (StatusCode::UNPROCESSABLE_ENTITY, error),
+    }
+}
+
+/// Run work on a blocking thread, giving up after `TIMEOUT`.
+async fn solving<T, F>(work: F) -> Result<T, (StatusCode, Json<Value>)>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    match tokio::time::timeout(TIMEOUT, tokio::task::spawn_blocking(work)).await {
+        Ok(result) => result.map_err(internal_error),
+        Err(_) => {
+            metrics().record_timeout();
+            let error = Json(json!({ "error": "the solver took too long" }));
+            Err((StatusCode::SERVICE_UNAVAILABLE, error))
+        }
+    }
+}
+
+fn read(puzzle: &str) -> Result<SudokuPuzzle, (StatusCode, Json<Value>)> {
+    SudokuPuzzle::from_string(&puzzle.replace('.', "0")).map_err(|e| bad_request(e.to_string()))
+}
+
+fn digits(nums: &[u8]) -> String {
+    nums.iter().map(|n| char::from(b'0' + n)).collect()
+}
+
+async fn solve(Json(request): Json<PuzzleRequest>) -> Response {
+    let puzzle = read(&request.puzzle)?;
+    let solution = solving(move || SOLVERS.get().solve_within(&puzzle, &LIMITS))
+        .await?
+        .map_err(limit_exceeded)?;
+    Ok(Json(
+        json!({ "solution": solution.map(|s| digits(&s.to_vec())) }),
+    ))
+}
+
+async fn solve_all(Json(request): Json<PuzzlesRequest>) -> Response {
+    if request.puzzles.len() > MAX_PUZZLES {
+        let message = format!("at most {} puzzles can be solved at once", MAX_PUZZLES);
+        let error = Json(json!({ "error": message }));
+        return Err((StatusCode::PAYLOAD_TOO_LARGE, error));
+    }
+    let puzzles = request
+        .puzzles
+        .iter()
+        .map(|puzzle| read(puzzle))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The puzzles share the time limit, so the whole batch stops with it
+    let started = Instant::now();
+    let solutions = solving(move || {
+        batch::map(&puzzles, |puzzle| {
+            let limits = SolverLimits {
+                max_time: Some(TIMEOUT.saturating_sub(started.elapsed())),
+                ..LIMITS
+            };
+            SOLVERS.get().solve_within(puzzle, &limits)
+        })
+    })
+    .await?
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(limit_exceeded)?;
+    let solutions: Vec<_> = solutions
+        .iter()
+        .map(|solution| solution.map(|s| digits(&s.to_vec())))
+        .collect();
+    Ok(Json(json!({ "solutions": solutions })))
+}
+
+async fn rate(Json(request): Json<PuzzleRequest>) -> Response {
+    let puzzle = read(&request.puzzle)?;
+    let rating = solving(move || puzzle.rating_within(&RatingConfig::default(), &RATING_LIMITS))
+        .await?
+        .map_err(limit_exceeded)?;
+    Ok(Json(json!({
+        "difficulty": rating.map(|rating| rating.difficulty.name()),
+        "score": rating.map(|rating| rating.score),
+    })))
+}
+
+async fn generate_puzzle(Json(request): Json<GenerateRequest>) -> Response {
+    let difficulty: Difficulty = match request.difficulty {
+        Some(name) => name
+            .parse()
+            .map_err(|e: ParseError| bad_request(e.to_string()))?,
+        None => Difficulty::Medium,
+    };
+    let mut rng = match request.seed {
+        Some(seed) => deterministic::rng(seed),
+        None => ChaCha8Rng::from_os_rng(),
+    };
+
+    let (puzzle, rating) = solving(move || {
+        let puzzle = generate_within(&mut rng, difficulty, GENERATE_ATTEMPTS)?;
+        let rating = puzzle.rating();
+        Ok((puzzle, rating))
+    })
+    .await?
+    .map_err(|e: GenerationError| {
+        let error = Json(json!({ "error": e.to_string() }));
+        (StatusCode::SERVICE_UNAVAILABLE, error)
+    })?;
+    let score = rating.map(|rating| rating.score);
+    Ok(Json(json!({
+        "puzzle": digits(&puzzle.to_vec()),
+        "difficulty": difficulty.name(),
+        "score": score,
+    })))
+}
+
+async fn check(Json(request): Json<PuzzleRequest>) -> Response {
+    let puzzle = read(&request.puzzle)?;
+    let conflicts = puzzle.conflicts();
+    let status = if !conflicts.is_empty() {
+        "inconsistent"
+    } else {
+        let solved = puzzle.clone();
+        let count = solving(move || SOLVERS.get().count_solutions_within(&solved, 2, &LIMITS));
+        match count.await?.map_err(limit_exceeded)? {
+            0 => "unsolvable",
+            1 => "unique",
+            _ => {
+                metrics().record_not_unique();
+                "multiple"
+            }
+        }
+    };
+    Ok(Json(json!({ "status": status, "conflicts": conflicts })))
+}
+
+/// Count a request and how long it took to answer.
+async fn track(request: Request, next: Next) -> axum::response::Response {
+    let endpoint = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(String::new, |path| String::from(path.as_str()));
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics().record_request(&endpoint, response.status().as_u16(), start.elapsed());
+    response
+}
+
+async fn render_metrics() -> String {
+    metrics().render()
+}
+
+/// The routes of the service, for serving or for nesting in another router.
+pub fn router() -> Router {
+    Router::new()
+        .route("/solve", post(solve))
+        .route("/solve-all", post(solve_all))
+        .route("/rate", post(rate))
+        .route("/generate", post(generate_puzzle))
+        .route("/check", post(check))
+        .route_layer(middleware::from_fn(track))
+        .route("/metrics", get(render_metrics))
+}
+
+/// Serve the routes on addr until the process is stopped.
+pub async fn serve<A: ToSocketAddrs>(addr: A) -> io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    axum::serve(listener, router()).await
+}
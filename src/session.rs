@@ -0,0 +1,208 @@
+//! A game in progress: a puzzle and the player's entries, checked as each
+//! move is made.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, session::{Session, Status}};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let mut session = Session::new(puzzle);
+//!
+//! // 4 is already in the first row
+//! assert_eq!(Status::Conflicting, session.place(0, 2, 4).unwrap());
+//! assert_eq!(vec![(0, 0), (0, 2)], session.conflicts());
+//! assert!(!session.is_solvable());
+//!
+//! // 9 breaks no rules, but the puzzle's only solution has 5 there
+//! assert_eq!(Status::InProgress, session.place(0, 2, 9).unwrap());
+//! assert!(session.conflicts().is_empty());
+//! assert!(!session.is_solvable());
+//!
+//! assert!(session.undo());
+//! assert!(session.undo());
+//! assert!(session.is_solvable());
+//! ```
+
+use crate::{error::Error, history::History, puzzle::SudokuPuzzle, units::PEERS};
+use alloc::vec::Vec;
+
+/// Where a game stands.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Status {
+    /// Some squares are empty, and no two filled squares conflict.
+    InProgress,
+    /// Some number appears twice in a row, column, or box.
+    Conflicting,
+    /// Every square is filled and no rules are broken.
+    Solved,
+}
+
+/// A puzzle being played, with the player's moves recorded so that they can
+/// be undone, and the squares that break the rules of Sudoku kept up to date
+/// as each move is made.
+#[derive(Clone)]
+pub struct Session {
+    history: History,
+    /// The squares whose numbers conflict with a peer's, as bit i for
+    /// square i.
+    conflicts: u128,
+}
+
+impl Session {
+    /// Start playing puzzle, with no moves made yet.
+    pub fn new(puzzle: SudokuPuzzle) -> Self {
+        Session::from_history(History::new(puzzle))
+    }
+
+    /// Continue playing from history, such as a game restored with
+    /// [`History::restore`].
+    pub fn from_history(history: History) -> Self {
+        let mut session = Session {
+            history,
+            conflicts: 0,
+        };
+        for (row, col) in session.history.puzzle().conflicts() {
+            session.conflicts |= 1 << (row * 9 + col);
+        }
+        session
+    }
+
+    /// The puzzle in its current state.
+    pub fn puzzle(&self) -> &SudokuPuzzle {
+        self.history.puzzle()
+    }
+
+    /// The moves made so far, which can be saved with [`History::save`].
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Stop playing and return the moves made.
+    pub fn into_history(self) -> History {
+        self.history
+    }
+
+    /// Fill (row, col) with n, even if that breaks the rules, and return
+    /// where the game stands after the move. n must be in the range [1,9],
+    /// and (row, col) must not be a given.
+    pub fn place(&mut self, row: usize, col: usize, n: u8) -> Result<Status, Error> {
+        self.history.place(row, col, n)?;
+        self.update(row * 9 + col);
+        Ok(self.status())
+    }
+
+    /// Empty (row, col) and remove its pencil marks, and return where the
+    /// game stands after the move. Givens cannot be cleared.
+    pub fn clear(&mut self, row: usize, col: usize) -> Result<Status, Error> {
+        self.history.clear(row, col)?;
+        self.update(row * 9 + col);
+        Ok(self.status())
+    }
+
+    /// Add the pencil mark n to (row, col) if it is absent, otherwise
+    /// remove it.
+    pub fn toggle_mark(&mut self, row: usize, col: usize, n: u8) -> Result<(), Error> {
+        self.history.toggle_mark(row, col, n)
+    }
+
+    /// Set the pencil marks of every empty square to its candidates.
+    pub fn fill_marks(&mut self) {
+        self.history.fill_marks();
+    }
+
+    /// Return the puzzle to its givens, as a move that can be undone.
+    pub fn reset(&mut self) {
+        self.track(History::reset);
+    }
+
+    /// Revert the most recent move. Returns `false` if there was nothing to
+    /// undo.
+    pub fn undo(&mut self) -> bool {
+        self.track(History::undo)
+    }
+
+    /// Reapply the most recently undone move. Returns `false` if there was
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        self.track(History::redo)
+    }
+
+    /// Where the game stands.
+    pub fn status(&self) -> Status {
+        if self.conflicts != 0 {
+            Status::Conflicting
+        } else if self.history.puzzle().to_vec().contains(&0) {
+            Status::InProgress
+        } else {
+            Status::Solved
+        }
+    }
+
+    /// Indicates whether every square is filled without breaking any rules.
+    ///
+    /// ```
+    /// # use sudoku::{puzzle::SudokuPuzzle, session::{Session, Status}};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "083921657967345821251876493548132976729564138136798245372689514814253769695417382",
+    /// )
+    /// .unwrap();
+    /// let mut session = Session::new(puzzle);
+    /// assert!(!session.is_complete());
+    /// assert_eq!(Status::Solved, session.place(0, 0, 4).unwrap());
+    /// assert!(session.is_complete());
+    /// ```
+    pub fn is_complete(&self) -> bool {
+        self.status() == Status::Solved
+    }
+
+    /// The squares whose numbers appear more than once in a row, column, or
+    /// box, in reading order.
+    pub fn conflicts(&self) -> Vec<(usize, usize)> {
+        (0..81)
+            .filter(|square| self.conflicts & (1 << square) != 0)
+            .map(|square| (square / 9, square % 9))
+            .collect()
+    }
+
+    /// Indicates whether the number at (row, col) appears again in its row,
+    /// column, or box.
+    pub fn is_conflicting(&self, row: usize, col: usize) -> bool {
+        row < 9 && col < 9 && self.conflicts & (1 << (row * 9 + col)) != 0
+    }
+
+    /// Indicates whether the puzzle can still be solved without changing
+    /// any of the numbers filled in, which is false as soon as the player
+    /// makes a mistake, even one that breaks no rules yet.
+    pub fn is_solvable(&self) -> bool {
+        self.conflicts == 0 && self.history.puzzle().count_solutions(1) > 0
+    }
+
+    /// Make a move that can change any square, then update the squares that
+    /// changed.
+    fn track<T>(&mut self, change: impl FnOnce(&mut History) -> T) -> T {
+        let before = self.history.puzzle().to_vec();
+        let result = change(&mut self.history);
+        let after = self.history.puzzle().to_vec();
+        for square in (0..81).filter(|square| before[*square] != after[*square]) {
+            self.update(square);
+        }
+        result
+    }
+
+    /// Recheck the square that changed and its peers, which are the only
+    /// squares whose conflicts it can affect.
+    fn update(&mut self, square: usize) {
+        let nums = self.history.puzzle().to_vec();
+        let conflicting = |square: usize| {
+            nums[square] != 0 && PEERS[square].iter().any(|peer| nums[*peer] == nums[square])
+        };
+        for square in core::iter::once(square).chain(PEERS[square].iter().copied()) {
+            if conflicting(square) {
+                self.conflicts |= 1 << square;
+            } else {
+                self.conflicts &= !(1 << square);
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! SSE2 versions of the per-pass unit computations, used in place of the
+//! scalar versions when the `simd` feature is enabled on x86_64.
+//!
+//! A puzzle is processed a row at a time: the first eight squares of a row
+//! fill the eight 16 bit lanes of a vector, and the ninth is handled on its
+//! own. Each square is represented by the bitmask of its number, so that
+//! combining squares is a bitwise or.
+
+use crate::{nine_set::NineSet, units::box_index};
+use core::arch::x86_64::*;
+
+/// The bitmask of each number, indexed by the number, where 0 is empty.
+const BITS: [u16; 10] = [0, 1, 2, 4, 8, 16, 32, 64, 128, 256];
+
+/// The bitmask of the numbers [1,9].
+const ALL_BITS: u16 = 0b1_1111_1111;
+
+/// The bitmask of each square of a puzzle, padded so that the last row can
+/// be loaded eight lanes at a time.
+struct Masks([u16; 88]);
+
+impl Masks {
+    fn new(nums: &[u8; 81]) -> Self {
+        let mut masks = [0; 88];
+        for (mask, n) in masks.iter_mut().zip(nums.iter()) {
+            *mask = BITS[*n as usize];
+        }
+        Masks(masks)
+    }
+
+    /// The first eight squares of row, one per lane.
+    fn row_head(&self, row: usize) -> __m128i {
+        // SAFETY: row * 9 + 8 <= 80, so all eight lanes are within the array.
+        unsafe { _mm_loadu_si128(self.0[row * 9..].as_ptr() as *const __m128i) }
+    }
+
+    /// The ninth square of row.
+    fn row_tail(&self, row: usize) -> u16 {
+        self.0[row * 9 + 8]
+    }
+}
+
+/// The eight lanes of v.
+fn lanes(v: __m128i) -> [u16; 8] {
+    let mut lanes = [0; 8];
+    // SAFETY: lanes is exactly 128 bits wide.
+    unsafe { _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, v) };
+    lanes
+}
+
+/// The sets of numbers in each of the 27 units of a puzzle, indexed the same
+/// way as `units::UNITS`, and whether any unit contains a number twice.
+pub(crate) fn unit_sets(nums: &[u8; 81]) -> ([NineSet; 27], bool) {
+    let masks = Masks::new(nums);
+    let mut sets = [0u16; 27];
+    let mut filled = [0u32; 27];
+
+    // SAFETY: SSE2 is part of the x86_64 baseline.
+    unsafe {
+        let zero = _mm_setzero_si128();
+        let mut cols = zero;
+        let mut col_counts = zero;
+        let mut bands = [zero; 3];
+
+        for row in 0..9 {
+            let head = masks.row_head(row);
+            let tail = masks.row_tail(row);
+
+            // Columns 0-7 and each band of three rows accumulate lane-wise.
+            cols = _mm_or_si128(cols, head);
+            bands[row / 3] = _mm_or_si128(bands[row / 3], head);
+            // Empty lanes compare equal to zero (all ones); filled lanes
+            // don't, so subtracting the inverse adds one per filled square.
+            let empty = _mm_cmpeq_epi16(head, zero);
+            col_counts = _mm_sub_epi16(col_counts, _mm_andnot_si128(empty, _mm_set1_epi16(-1)));
+
+            let row_lanes = lanes(head);
+            sets[row] = row_lanes.iter().fold(tail, |set, mask| set | mask);
+            filled[row] = row_lanes.iter().filter(|mask| **mask != 0).count() as u32;
+            filled[row] += (tail != 0) as u32;
+
+            sets[17] |= tail;
+            filled[17] += (tail != 0) as u32;
+            let tail_box = 18 + box_index(row, 8);
+            sets[tail_box] |= tail;
+            filled[tail_box] += (tail != 0) as u32;
+        }
+
+        for (col, (mask, count)) in lanes(cols).iter().zip(lanes(col_counts).iter()).enumerate() {
+            sets[9 + col] = *mask;
+            filled[9 + col] = *count as u32;
+        }
+
+        for (band, lanes) in bands.iter().map(|band| lanes(*band)).enumerate() {
+            for (col, mask) in lanes.iter().enumerate() {
+                sets[18 + box_index(band * 3, col)] |= mask;
+            }
+        }
+    }
+
+    // Box counts are cheap enough to take directly from the numbers.
+    for (square, n) in nums.iter().enumerate() {
+        if *n != 0 && square % 9 != 8 {
+            filled[18 + box_index(square / 9, square % 9)] += 1;
+        }
+    }
+
+    let repeats = sets
+        .iter()
+        .zip(filled.iter())
+        .any(|(set, count)| set.count_ones() != *count);
+    let mut unit_sets = [NineSet::empty(); 27];
+    for (unit_set, set) in unit_sets.iter_mut().zip(sets.iter()) {
+        *unit_set = NineSet::from_bits(*set);
+    }
+    (unit_sets, repeats)
+}
+
+/// The candidates of every square of a puzzle, given the sets of numbers in
+/// each of its units. A filled square's only candidate is its own number.
+pub(crate) fn candidates(nums: &[u8; 81], units: &[NineSet; 27]) -> [NineSet; 81] {
+    let masks = Masks::new(nums);
+    let mut candidates = [NineSet::empty(); 81];
+
+    // SAFETY: SSE2 is part of the x86_64 baseline.
+    unsafe {
+        let zero = _mm_setzero_si128();
+        let all = _mm_set1_epi16(ALL_BITS as i16);
+        let col_sets = lanes_of(|col| units[9 + col].bits());
+
+        for row in 0..9 {
+            let box_sets = lanes_of(|col| units[18 + box_index(row, col)].bits());
+            let row_set = _mm_set1_epi16(units[row].bits() as i16);
+            let ruled_out = _mm_or_si128(row_set, _mm_or_si128(col_sets, box_sets));
+            let open = _mm_andnot_si128(ruled_out, all);
+
+            // Filled squares keep their own number instead.
+            let head = masks.row_head(row);
+            let empty = _mm_cmpeq_epi16(head, zero);
+            let result = _mm_or_si128(_mm_and_si128(empty, open), head);
+
+            for (col, mask) in lanes(result).iter().enumerate() {
+                candidates[row * 9 + col] = NineSet::from_bits(*mask);
+            }
+
+            let tail = masks.row_tail(row);
+            candidates[row * 9 + 8] = if tail != 0 {
+                NineSet::from_bits(tail)
+            } else {
+                let ruled_out = units[row] | units[17] | units[18 + box_index(row, 8)];
+                !ruled_out
+            };
+        }
+    }
+
+    candidates
+}
+
+/// A vector whose lane i holds lane(i), for i in [0, 8).
+unsafe fn lanes_of<F: Fn(usize) -> u16>(lane: F) -> __m128i {
+    _mm_set_epi16(
+        lane(7) as i16,
+        lane(6) as i16,
+        lane(5) as i16,
+        lane(4) as i16,
+        lane(3) as i16,
+        lane(2) as i16,
+        lane(1) as i16,
+        lane(0) as i16,
+    )
+}
@@ -0,0 +1,75 @@
+use crate::{nine_by_nine::NineByNine, puzzle::SudokuPuzzle};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
+
+/// A completely filled puzzle that follows all the rules of Sudoku.
+///
+/// Solutions can only be produced by solving a puzzle, so every square
+/// is known to hold a number.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    nums: [u8; 81],
+}
+
+impl Solution {
+    /// Create a Solution from a puzzle that has been correctly solved.
+    pub(crate) fn new(solved: &SudokuPuzzle) -> Self {
+        debug_assert!(solved.is_solved());
+
+        let mut nums = [0; 81];
+        for (i, num) in nums.iter_mut().enumerate() {
+            *num = solved.get(i / 9, i % 9).unwrap_or(0);
+        }
+        Solution { nums }
+    }
+
+    /// The number at (row, col).
+    /// row and column must each be in the range [0, 9).
+    pub fn get(&self, row: usize, col: usize) -> u8 {
+        assert!(row < 9);
+        assert!(col < 9);
+
+        self.nums[row * 9 + col]
+    }
+
+    /// The number at (row, col), or `None` if (row, col) is outside of
+    /// the solution.
+    pub fn get_checked(&self, row: usize, col: usize) -> Option<u8> {
+        if row < 9 && col < 9 {
+            Some(self.nums[row * 9 + col])
+        } else {
+            None
+        }
+    }
+
+    /// A linear, row-major list of the 81 numbers in this solution.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.nums.to_vec()
+    }
+}
+
+impl From<Solution> for SudokuPuzzle {
+    /// Create a puzzle whose givens are all 81 numbers of solution.
+    ///
+    /// ```
+    /// # use sudoku::puzzle::SudokuPuzzle;
+    /// # let puzzle = SudokuPuzzle::from_string(
+    /// #     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// # )
+    /// # .unwrap();
+    /// let solution = puzzle.solve().unwrap();
+    /// let filled = SudokuPuzzle::from(solution);
+    /// assert!(filled.is_solved());
+    /// assert_eq!(57, puzzle.diff(&filled).len());
+    /// ```
+    fn from(solution: Solution) -> Self {
+        SudokuPuzzle::try_from(&solution.nums[..]).expect("solutions are valid puzzles")
+    }
+}
+
+impl fmt::Debug for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nums = NineByNine::from_fn(|row, col| Some(self.get(row, col)));
+        write!(f, "{:?}", nums)
+    }
+}
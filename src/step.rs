@@ -0,0 +1,131 @@
+//! The individual moves made by the solver.
+
+use crate::units::Unit;
+use alloc::{format, string::String};
+use core::fmt;
+
+/// One move made while solving a puzzle, as recorded by
+/// [`SudokuPuzzle::solve_with_steps`](crate::puzzle::SudokuPuzzle::solve_with_steps).
+///
+/// Replaying the steps in order on the puzzle that was solved, filling a
+/// square for each single and guess and emptying squares for each
+/// `Backtrack`, ends at the solution.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Step {
+    /// n is the only number that could go in (row, col).
+    Single { row: usize, col: usize, n: u8 },
+    /// (row, col) is the only square in unit where n could go.
+    HiddenSingle {
+        row: usize,
+        col: usize,
+        n: u8,
+        unit: Unit,
+    },
+    /// Nothing more could be deduced, so n was guessed for (row, col), one of
+    /// several numbers that could go there.
+    Guess { row: usize, col: usize, n: u8 },
+    /// The guess at (row, col) led to a contradiction, so it and every square
+    /// filled after it were emptied again.
+    Backtrack { row: usize, col: usize },
+}
+
+impl Step {
+    /// The square that this step fills or, for a backtrack, empties.
+    pub fn square(&self) -> (usize, usize) {
+        match *self {
+            Step::Single { row, col, .. }
+            | Step::HiddenSingle { row, col, .. }
+            | Step::Guess { row, col, .. }
+            | Step::Backtrack { row, col } => (row, col),
+        }
+    }
+
+    /// The number that this step places, or `None` for a backtrack.
+    pub fn number(&self) -> Option<u8> {
+        match *self {
+            Step::Single { n, .. } | Step::HiddenSingle { n, .. } | Step::Guess { n, .. } => {
+                Some(n)
+            }
+            Step::Backtrack { .. } => None,
+        }
+    }
+
+    /// A sentence explaining the step to a player, with rows, columns, and
+    /// boxes numbered from 1.
+    ///
+    /// ```
+    /// # use sudoku::{step::Step, units::Unit};
+    /// let step = Step::HiddenSingle { row: 2, col: 1, n: 5, unit: Unit::Box(0) };
+    /// assert_eq!(
+    ///     "5 can only go in r3c2 within box 1, because every other empty square \
+    ///      in box 1 shares a row, column, or box with a 5.",
+    ///     step.explain()
+    /// );
+    /// ```
+    pub fn explain(&self) -> String {
+        match *self {
+            Step::Single { row, col, n } => format!(
+                "r{}c{} can only be {}, because every other number is already in its \
+                 row, column, or box.",
+                row + 1,
+                col + 1,
+                n
+            ),
+            Step::HiddenSingle { row, col, n, unit } => format!(
+                "{} can only go in r{}c{} within {}, because every other empty square in \
+                 {} shares a row, column, or box with a {}.",
+                n,
+                row + 1,
+                col + 1,
+                unit,
+                unit,
+                n
+            ),
+            Step::Guess { row, col, n } => format!(
+                "Nothing more can be deduced, so guess that r{}c{} is {}, one of the \
+                 numbers that could go there.",
+                row + 1,
+                col + 1,
+                n
+            ),
+            Step::Backtrack { row, col } => format!(
+                "Guessing at r{}c{} led to a square where no number fits, so take the \
+                 guess back, along with every number placed after it.",
+                row + 1,
+                col + 1
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    /// A one line description of the step, with rows and columns numbered
+    /// from 1.
+    ///
+    /// ```
+    /// # use sudoku::{step::Step, units::Unit};
+    /// let step = Step::HiddenSingle { row: 3, col: 6, n: 2, unit: Unit::Col(6) };
+    /// assert_eq!("r4c7=2: hidden single in column 7", step.to_string());
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Step::Single { row, col, n } => {
+                write!(f, "r{}c{}={}: naked single", row + 1, col + 1, n)
+            }
+            Step::HiddenSingle { row, col, n, unit } => {
+                write!(
+                    f,
+                    "r{}c{}={}: hidden single in {}",
+                    row + 1,
+                    col + 1,
+                    n,
+                    unit
+                )
+            }
+            Step::Guess { row, col, n } => write!(f, "r{}c{}={}: guess", row + 1, col + 1, n),
+            Step::Backtrack { row, col } => {
+                write!(f, "r{}c{}: guess failed, backtracking", row + 1, col + 1)
+            }
+        }
+    }
+}
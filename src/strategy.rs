@@ -0,0 +1,116 @@
+//! Different ways of solving the same puzzle, for comparing how much
+//! searching each one needs.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, strategy::Strategy};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+//! )
+//! .unwrap();
+//! for strategy in Strategy::ALL.iter() {
+//!     let run = strategy.solve(&puzzle);
+//!     assert_eq!(puzzle.solve(), run.solution);
+//!     assert!(run.guesses > 0);
+//! }
+//! ```
+
+use crate::{board, dlx, error::ParseError, puzzle::SudokuPuzzle, solution::Solution};
+use core::{convert::TryFrom, fmt, str::FromStr};
+
+/// A way of solving a puzzle.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Strategy {
+    /// The solver behind [`SudokuPuzzle::solve`]: naked and hidden singles,
+    /// then a guess at the last square with more than one candidate.
+    Deduction,
+    /// Naked and hidden singles, then a guess at the square with the fewest
+    /// candidates.
+    FewestCandidates,
+    /// Naked singles only, then a guess at the last square with more than
+    /// one candidate.
+    NakedSingles,
+    /// No deduction: fill the square with the fewest candidates, guessing
+    /// when there is more than one.
+    Backtracking,
+    /// Knuth's Algorithm X with dancing links, covering the constraint with
+    /// the fewest choices first.
+    Dlx,
+}
+
+/// The result of solving a puzzle with a strategy.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Run {
+    pub solution: Option<Solution>,
+    /// How many times the strategy picked one of several choices, including
+    /// choices it later took back.
+    pub guesses: u64,
+}
+
+impl Strategy {
+    /// Every strategy, in the order they are described.
+    pub const ALL: [Strategy; 5] = [
+        Strategy::Deduction,
+        Strategy::FewestCandidates,
+        Strategy::NakedSingles,
+        Strategy::Backtracking,
+        Strategy::Dlx,
+    ];
+
+    /// The name of this strategy in lowercase, such as "dlx".
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Deduction => "deduction",
+            Strategy::FewestCandidates => "fewest-candidates",
+            Strategy::NakedSingles => "naked-singles",
+            Strategy::Backtracking => "backtracking",
+            Strategy::Dlx => "dlx",
+        }
+    }
+
+    /// Solve puzzle this way, counting the guesses made. Puzzles with more
+    /// than one solution may be solved differently by different strategies.
+    pub fn solve(self, puzzle: &SudokuPuzzle) -> Run {
+        let (solution, guesses) = match self {
+            Strategy::Deduction => puzzle.solve_counting(true, false),
+            Strategy::FewestCandidates => puzzle.solve_counting(true, true),
+            Strategy::NakedSingles => puzzle.solve_counting(false, false),
+            Strategy::Backtracking => {
+                let (solved, guesses) = board::backtrack(puzzle);
+                (solved.map(|solved| Solution::new(&solved)), guesses)
+            }
+            Strategy::Dlx => {
+                let nums = <[u8; 81]>::try_from(&puzzle.to_vec()[..]).unwrap();
+                let (solved, guesses) = dlx::solve(&nums);
+                let solution =
+                    solved.map(|nums| Solution::new(&SudokuPuzzle::try_from(&nums[..]).unwrap()));
+                (solution, guesses)
+            }
+        };
+        Run { solution, guesses }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = ParseError;
+
+    /// Read a strategy from its name, ignoring case.
+    ///
+    /// ```
+    /// # use sudoku::strategy::Strategy;
+    /// assert_eq!(Ok(Strategy::Dlx), "DLX".parse());
+    /// assert!("guesswork".parse::<Strategy>().is_err());
+    /// ```
+    fn from_str(name: &str) -> Result<Self, ParseError> {
+        Strategy::ALL
+            .iter()
+            .copied()
+            .find(|strategy| strategy.name().eq_ignore_ascii_case(name))
+            .ok_or(ParseError::InvalidStrategy)
+    }
+}
@@ -0,0 +1,151 @@
+//! Help for a player who is stuck: the simplest technique that makes
+//! progress from their board, and the unit to look in, without the move it
+//! makes. Unlike a [hint](crate::hint), which leads to a square and its
+//! number, a nudge only says what to look for.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, stuck, technique::Technique};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let nudge = stuck::nudge(&puzzle, false).unwrap();
+//! assert_eq!(Technique::HiddenSingle, nudge.technique);
+//! println!("{}", nudge);
+//! ```
+
+use crate::{
+    puzzle::SudokuPuzzle,
+    technique::{Deduction, Finding, Position, Technique},
+    units::{box_index, Unit},
+};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The technique that gets a player unstuck, and where to use it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Nudge {
+    /// The simplest technique that makes progress.
+    pub technique: Technique,
+    /// The unit to look in: the box of a naked single, the unit a hidden
+    /// single or naked pair lies in, the box of a pointing pair, and the
+    /// first row or column of an x-wing.
+    pub unit: Unit,
+}
+
+impl Nudge {
+    fn of(finding: &Finding) -> Self {
+        let unit = match finding.units.first() {
+            Some(unit) => *unit,
+            None => {
+                let (row, col) = finding.pattern[0];
+                Unit::Box(box_index(row, col))
+            }
+        };
+        Nudge {
+            technique: finding.technique,
+            unit,
+        }
+    }
+}
+
+impl fmt::Display for Nudge {
+    /// What to look for, as a sentence, with rows, columns, and boxes
+    /// numbered from 1.
+    ///
+    /// ```
+    /// # use sudoku::{stuck::Nudge, technique::Technique, units::Unit};
+    /// let nudge = Nudge { technique: Technique::NakedPair, unit: Unit::Row(2) };
+    /// assert_eq!(
+    ///     "Look for a naked pair in row 3: two squares that can only be the same two numbers.",
+    ///     nudge.to_string()
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.technique {
+            Technique::NakedSingle => write!(
+                f,
+                "Look for a naked single in {}: a square where only one number can go.",
+                self.unit
+            ),
+            Technique::HiddenSingle => write!(
+                f,
+                "Look for a hidden single in {}: a number that can only go in one of its squares.",
+                self.unit
+            ),
+            Technique::NakedPair => write!(
+                f,
+                "Look for a naked pair in {}: two squares that can only be the same two numbers.",
+                self.unit
+            ),
+            Technique::PointingPair => write!(
+                f,
+                "Look for a pointing pair in {}: a number whose places in it all lie in one row \
+                 or column.",
+                self.unit
+            ),
+            Technique::XWing => write!(
+                f,
+                "Look for an x-wing starting from {}: a number with two places in it, in the \
+                 same columns or rows as in another line.",
+                self.unit
+            ),
+        }
+    }
+}
+
+/// The simplest technique that makes progress on puzzle, and the unit it
+/// applies to. With marks, the pencil marks of each empty square that has
+/// any narrow its candidates, so that techniques the player has already
+/// used aren't suggested again; marks that rule out the square's number in
+/// the solution are ignored. Returns `None` if puzzle is solved, has no
+/// solution because of a wrong number, or needs more than the techniques
+/// in [`Technique::ALL`].
+///
+/// ```
+/// # use sudoku::{puzzle::SudokuPuzzle, stuck, technique::Technique, units::Unit};
+/// let puzzle = SudokuPuzzle::from_string(
+///     "000000000000000000000000000000000000000000000000000000000000000000000000123456780",
+/// )
+/// .unwrap();
+/// let nudge = stuck::nudge(&puzzle, false).unwrap();
+/// assert_eq!(Technique::NakedSingle, nudge.technique);
+/// assert_eq!(Unit::Box(8), nudge.unit);
+/// assert_eq!(None, stuck::nudge(&SudokuPuzzle::empty(), false));
+///
+/// // A square narrowed to one number by pencil marks is a naked single
+/// let mut puzzle = SudokuPuzzle::from_string(
+///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+/// )
+/// .unwrap();
+/// let n = puzzle.solve().unwrap().get(4, 3);
+/// puzzle.add_mark(4, 3, n).unwrap();
+/// let nudge = stuck::nudge(&puzzle, true).unwrap();
+/// assert_eq!(Technique::NakedSingle, nudge.technique);
+/// assert_eq!(Unit::Box(4), nudge.unit);
+/// ```
+pub fn nudge(puzzle: &SudokuPuzzle, marks: bool) -> Option<Nudge> {
+    if puzzle.is_solved() {
+        return None;
+    }
+    let solution = puzzle.solve()?;
+
+    let mut position = Position::new(puzzle);
+    if marks {
+        let mut eliminations = Vec::new();
+        for (row, col) in (0..9).flat_map(|row| (0..9).map(move |col| (row, col))) {
+            let marked = puzzle.marks(row, col);
+            if marked.is_empty() || !marked.contains(solution.get(row, col)) {
+                continue;
+            }
+            for n in position.candidates(row, col).iter() {
+                if !marked.contains(n) {
+                    eliminations.push((row, col, n));
+                }
+            }
+        }
+        position.apply(&Deduction::Eliminate(eliminations));
+    }
+
+    position.next().map(|finding| Nudge::of(&finding))
+}
@@ -0,0 +1,147 @@
+//! Sukaku, or pencil-mark puzzles: instead of placed numbers, every square
+//! is given the list of numbers that may go there, and the puzzle is solved
+//! by choosing one from each list without breaking the rules.
+//!
+//! A sukaku is usually written as 729 characters, nine for each square in
+//! row-major order, where a digit is a candidate of the square and 0 or '.'
+//! is a number that has been ruled out. It can also be written as 81 lists
+//! of candidates separated by whitespace.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, sukaku::Sukaku};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let sukaku = Sukaku::from_puzzle(&puzzle);
+//! assert_eq!(&sukaku.to_string()[..18], "...4.....1........");
+//!
+//! let parsed: Sukaku = sukaku.to_string().parse().unwrap();
+//! assert_eq!(1, parsed.count_solutions(2));
+//! assert_eq!(puzzle.solve(), parsed.solve());
+//! ```
+
+use crate::{dlx, error::ParseError, nine_set::NineSet, puzzle::SudokuPuzzle, solution::Solution};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt, str::FromStr};
+
+/// A puzzle given as the candidates of each square.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sukaku {
+    candidates: [NineSet; 81],
+}
+
+impl Sukaku {
+    /// A sukaku whose squares, in row-major order, may only hold their
+    /// candidates.
+    pub fn new(candidates: [NineSet; 81]) -> Self {
+        Sukaku { candidates }
+    }
+
+    /// The sukaku with the candidates of each square of puzzle, where a
+    /// filled square has only its own number.
+    pub fn from_puzzle(puzzle: &SudokuPuzzle) -> Self {
+        let mut candidates = [NineSet::empty(); 81];
+        for (square, candidates) in candidates.iter_mut().enumerate() {
+            *candidates = puzzle.candidates(square / 9, square % 9);
+        }
+        Sukaku { candidates }
+    }
+
+    /// The numbers that may go at (row, col).
+    /// row and column must each be in the range [0, 9).
+    pub fn candidates(&self, row: usize, col: usize) -> NineSet {
+        assert!(row < 9);
+        assert!(col < 9);
+
+        self.candidates[row * 9 + col]
+    }
+
+    /// Solve the sukaku, if possible, choosing one candidate for every
+    /// square.
+    pub fn solve(&self) -> Option<Solution> {
+        let (solved, _) = dlx::solve_candidates(&self.candidates);
+        let puzzle = SudokuPuzzle::try_from(&solved?[..]).expect("solved squares are in [1,9]");
+        Some(Solution::new(&puzzle))
+    }
+
+    /// Count the solutions of the sukaku, stopping once limit have been
+    /// found.
+    ///
+    /// ```
+    /// # use sudoku::{nine_set::NineSet, sukaku::Sukaku};
+    /// let open = Sukaku::new([NineSet::full(); 81]);
+    /// assert_eq!(2, open.count_solutions(2));
+    /// assert_eq!(0, Sukaku::new([NineSet::empty(); 81]).count_solutions(2));
+    /// ```
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        dlx::count_candidates(&self.candidates, limit)
+    }
+}
+
+impl FromStr for Sukaku {
+    type Err = ParseError;
+
+    /// Read a sukaku from 729 candidates, ignoring whitespace, or from 81
+    /// candidate lists separated by whitespace.
+    ///
+    /// ```
+    /// # use sudoku::{error::ParseError, sukaku::Sukaku};
+    /// let lists = vec!["123456789"; 81].join(" ");
+    /// assert_eq!(lists.parse::<Sukaku>(), "123456789".repeat(81).parse());
+    /// assert_eq!(
+    ///     Err(ParseError::WrongSukakuLength { len: 9 }),
+    ///     "123456789".parse::<Sukaku>()
+    /// );
+    /// ```
+    fn from_str(source: &str) -> Result<Self, ParseError> {
+        let lists: Vec<&str> = source.split_whitespace().collect();
+        let mut candidates = [NineSet::empty(); 81];
+        if lists.len() == 81 {
+            let mut index = 0;
+            for (square, list) in lists.into_iter().enumerate() {
+                for c in list.chars() {
+                    add(&mut candidates[square], index, c)?;
+                    index += 1;
+                }
+            }
+        } else {
+            let chars: Vec<char> = lists.into_iter().flat_map(str::chars).collect();
+            if chars.len() != 729 {
+                return Err(ParseError::WrongSukakuLength { len: chars.len() });
+            }
+            for (index, c) in chars.into_iter().enumerate() {
+                add(&mut candidates[index / 9], index, c)?;
+            }
+        }
+        Ok(Sukaku { candidates })
+    }
+}
+
+/// Add the candidate written as c, at index of the source, to candidates.
+fn add(candidates: &mut NineSet, index: usize, c: char) -> Result<(), ParseError> {
+    match c {
+        '.' | '0' => {}
+        c => match c.to_digit(10) {
+            Some(n) => candidates.add(n as u8),
+            None => return Err(ParseError::InvalidCandidate { index, c }),
+        },
+    }
+    Ok(())
+}
+
+impl fmt::Display for Sukaku {
+    /// The sukaku as 729 candidates, in the form read by `from_str`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for candidates in self.candidates.iter() {
+            for n in 1..=9 {
+                if candidates.contains(n) {
+                    write!(f, "{}", n)?;
+                } else {
+                    write!(f, ".")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
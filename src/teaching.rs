@@ -0,0 +1,266 @@
+//! Teaching mode: each step of a solve annotated with the numbers on the
+//! board that force it, such as "box 7 already contains 2, 4, 8; row 9
+//! contains 5, 9 ⇒ r9c2 = 7", for tutorials that point at the squares a
+//! deduction rests on rather than only naming the technique.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, step::Step, teaching};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let (_, steps) = puzzle.solve_with_steps();
+//! let annotations = teaching::annotate(&puzzle, &steps);
+//! assert_eq!(steps.len(), annotations.len());
+//!
+//! // Every single is backed by numbers already on the board
+//! for annotation in &annotations {
+//!     if let Step::Single { .. } = annotation.step {
+//!         assert!(!annotation.evidence.is_empty());
+//!     }
+//! }
+//! ```
+
+use crate::{
+    nine_set::NineSet,
+    puzzle::SudokuPuzzle,
+    step::Step,
+    units::{box_index, Unit},
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// Numbers already in a unit, which rule them out of its other squares.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Fact {
+    pub unit: Unit,
+    pub numbers: NineSet,
+    /// The (row, col) of each of the numbers, in the order of the numbers.
+    pub squares: Vec<(usize, usize)>,
+}
+
+/// A step of a solve and the facts it follows from.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Annotation {
+    pub step: Step,
+    /// For a single, the numbers in the square's box, row, and column that
+    /// rule out everything but its number. For a hidden single, the units
+    /// holding the number that rule it out of the other empty squares of
+    /// its unit, which are none if the square is the last empty one. Guesses
+    /// and backtracks follow from nothing on the board.
+    pub evidence: Vec<Fact>,
+}
+
+impl Annotation {
+    /// Annotate step, made in the position before.
+    ///
+    /// ```
+    /// # use sudoku::{puzzle::SudokuPuzzle, step::Step, teaching::Annotation};
+    /// let before = SudokuPuzzle::from_string(
+    ///     "000000000000000000000000000000000000000000000000000000000000000000000000123456780",
+    /// )
+    /// .unwrap();
+    /// let step = Step::Single { row: 8, col: 8, n: 9 };
+    /// assert_eq!(
+    ///     "box 9 already contains 7, 8; row 9 contains 1, 2, 3, 4, 5, 6 ⇒ r9c9 = 9",
+    ///     Annotation::new(step, &before).to_string()
+    /// );
+    /// ```
+    pub fn new(step: Step, before: &SudokuPuzzle) -> Self {
+        let mut nums = [0; 81];
+        nums.copy_from_slice(&before.to_vec());
+        Annotation {
+            step,
+            evidence: evidence(step, &nums),
+        }
+    }
+
+    /// Every square that the evidence points at, in the order of the facts.
+    pub fn squares(&self) -> Vec<(usize, usize)> {
+        self.evidence
+            .iter()
+            .flat_map(|fact| fact.squares.iter().copied())
+            .collect()
+    }
+}
+
+impl fmt::Display for Annotation {
+    /// The facts, then what follows from them, with rows, columns, and boxes
+    /// numbered from 1.
+    ///
+    /// ```
+    /// # use sudoku::{puzzle::SudokuPuzzle, step::Step, teaching::Annotation, units::Unit};
+    /// let before = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// let step = Step::HiddenSingle { row: 1, col: 8, n: 6, unit: Unit::Row(1) };
+    /// let annotation = Annotation::new(step, &before);
+    /// assert_eq!(
+    ///     "box 1 contains 6; box 2 contains 6 ⇒ r2c9 = 6, the only place for 6 in row 2",
+    ///     annotation.to_string()
+    /// );
+    /// assert_eq!(vec![(2, 0), (0, 5)], annotation.squares());
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, fact) in self.evidence.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "; " };
+            let already = match self.step {
+                Step::Single { .. } if i == 0 => "already ",
+                _ => "",
+            };
+            let numbers: Vec<String> = fact.numbers.iter().map(|n| n.to_string()).collect();
+            write!(
+                f,
+                "{}{} {}contains {}",
+                separator,
+                fact.unit,
+                already,
+                numbers.join(", ")
+            )?;
+        }
+        if !self.evidence.is_empty() {
+            write!(f, " ⇒ ")?;
+        }
+
+        match self.step {
+            Step::Single { row, col, n } => write!(f, "r{}c{} = {}", row + 1, col + 1, n),
+            Step::HiddenSingle { row, col, n, unit } if self.evidence.is_empty() => write!(
+                f,
+                "r{}c{} = {}, the last empty square in {}",
+                row + 1,
+                col + 1,
+                n,
+                unit
+            ),
+            Step::HiddenSingle { row, col, n, unit } => write!(
+                f,
+                "r{}c{} = {}, the only place for {} in {}",
+                row + 1,
+                col + 1,
+                n,
+                n,
+                unit
+            ),
+            Step::Guess { row, col, n } => write!(
+                f,
+                "nothing more can be deduced, so guess r{}c{} = {}",
+                row + 1,
+                col + 1,
+                n
+            ),
+            Step::Backtrack { row, col } => write!(
+                f,
+                "the guess at r{}c{} led to a contradiction, so take it back",
+                row + 1,
+                col + 1
+            ),
+        }
+    }
+}
+
+/// Annotate the steps taken to solve puzzle, as returned by
+/// [`SudokuPuzzle::solve_with_steps`], replaying them to find the position
+/// each was made in.
+pub fn annotate(puzzle: &SudokuPuzzle, steps: &[Step]) -> Vec<Annotation> {
+    let mut nums = [0; 81];
+    nums.copy_from_slice(&puzzle.to_vec());
+    // The squares filled so far, so that they can be emptied on backtracking
+    let mut filled = Vec::new();
+
+    let mut annotations = Vec::with_capacity(steps.len());
+    for step in steps {
+        annotations.push(Annotation {
+            step: *step,
+            evidence: evidence(*step, &nums),
+        });
+
+        let (row, col) = step.square();
+        match step.number() {
+            Some(n) => {
+                nums[row * 9 + col] = n;
+                filled.push(row * 9 + col);
+            }
+            None => {
+                while let Some(square) = filled.pop() {
+                    nums[square] = 0;
+                    if square == row * 9 + col {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    annotations
+}
+
+/// The box, row, and column of (row, col), in the order they are cited.
+fn units_of(row: usize, col: usize) -> [Unit; 3] {
+    [
+        Unit::Box(box_index(row, col)),
+        Unit::Row(row),
+        Unit::Col(col),
+    ]
+}
+
+/// The facts that step follows from in the position nums.
+fn evidence(step: Step, nums: &[u8; 81]) -> Vec<Fact> {
+    match step {
+        Step::Single { row, col, n } => {
+            // Each other number is cited once, in the first unit it is in
+            let mut cited = NineSet::empty();
+            cited.add(n);
+            let mut facts = Vec::new();
+            for unit in units_of(row, col).iter() {
+                let fact = fact(*unit, nums, |m| !cited.contains(m));
+                if !fact.numbers.is_empty() {
+                    for m in fact.numbers.iter() {
+                        cited.add(m);
+                    }
+                    facts.push(fact);
+                }
+            }
+            facts
+        }
+        Step::HiddenSingle { row, col, n, unit } => {
+            // Cover each other empty square of the unit with a unit holding n
+            let mut facts: Vec<Fact> = Vec::new();
+            for square in unit.squares().iter().copied() {
+                if square == row * 9 + col || nums[square] != 0 {
+                    continue;
+                }
+                let units = units_of(square / 9, square % 9);
+                if facts.iter().any(|fact| units.contains(&fact.unit)) {
+                    continue;
+                }
+                let cover = units
+                    .iter()
+                    .filter(|other| **other != unit)
+                    .map(|other| fact(*other, nums, |m| m == n))
+                    .find(|fact| !fact.numbers.is_empty());
+                facts.extend(cover);
+            }
+            facts
+        }
+        Step::Guess { .. } | Step::Backtrack { .. } => Vec::new(),
+    }
+}
+
+/// The numbers in unit that cite accepts, and where they are.
+fn fact(unit: Unit, nums: &[u8; 81], cite: impl Fn(u8) -> bool) -> Fact {
+    let mut found: Vec<(u8, (usize, usize))> = unit
+        .squares()
+        .iter()
+        .filter(|square| nums[**square] != 0 && cite(nums[**square]))
+        .map(|square| (nums[*square], (square / 9, square % 9)))
+        .collect();
+    found.sort_unstable();
+    Fact {
+        unit,
+        numbers: found.iter().map(|(n, _)| *n).collect(),
+        squares: found.into_iter().map(|(_, square)| square).collect(),
+    }
+}
@@ -0,0 +1,473 @@
+//! The techniques that players use to solve puzzles by hand, and a way to
+//! find positions where a given technique is the one needed next, for
+//! practising it.
+//!
+//! Unlike the solver, which only fills squares, a [`Position`] tracks the
+//! candidates that have been ruled out, since techniques beyond singles
+//! remove candidates rather than place numbers.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, technique::{Position, Technique}};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let position = Position::practice(&puzzle, Technique::HiddenSingle).unwrap();
+//! let finding = position.next().unwrap();
+//! assert_eq!(Technique::HiddenSingle, finding.technique);
+//! ```
+
+use crate::{
+    error::ParseError,
+    nine_set::NineSet,
+    puzzle::SudokuPuzzle,
+    trace::Elimination,
+    units::{box_index, Unit, BOXES, COLS, PEERS, ROWS, UNITS},
+};
+use alloc::{format, string::String, vec::Vec};
+use core::{convert::TryFrom, fmt, str::FromStr};
+
+/// A way of making progress on a puzzle by hand, from the simplest to the
+/// hardest.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Technique {
+    /// A square with only one candidate.
+    NakedSingle,
+    /// A number with only one place in a row, column, or box.
+    HiddenSingle,
+    /// Two squares of a unit with the same two candidates, which rule those
+    /// numbers out of the rest of the unit.
+    NakedPair,
+    /// A number whose places in a box all lie in one row or column, which
+    /// rules it out of the rest of that row or column.
+    PointingPair,
+    /// A number with exactly two places in each of two rows, in the same
+    /// two columns, which rules it out of the rest of those columns. The
+    /// same holds with rows and columns swapped.
+    XWing,
+}
+
+impl Technique {
+    /// Every technique, from the simplest to the hardest.
+    pub const ALL: [Technique; 5] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::NakedPair,
+        Technique::PointingPair,
+        Technique::XWing,
+    ];
+
+    /// The name of this technique in lowercase, such as "x-wing".
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "naked-single",
+            Technique::HiddenSingle => "hidden-single",
+            Technique::NakedPair => "naked-pair",
+            Technique::PointingPair => "pointing-pair",
+            Technique::XWing => "x-wing",
+        }
+    }
+
+    /// The first deduction this technique makes in position, if any.
+    pub fn find(self, position: &Position) -> Option<Finding> {
+        match self {
+            Technique::NakedSingle => position.naked_single(),
+            Technique::HiddenSingle => position.hidden_single(),
+            Technique::NakedPair => position.naked_pair(),
+            Technique::PointingPair => position.pointing_pair(),
+            Technique::XWing => position.x_wing(),
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Technique {
+    type Err = ParseError;
+
+    /// Read a technique from its name, ignoring case.
+    ///
+    /// ```
+    /// # use sudoku::technique::Technique;
+    /// assert_eq!(Ok(Technique::XWing), "X-Wing".parse());
+    /// assert!("swordfish".parse::<Technique>().is_err());
+    /// ```
+    fn from_str(name: &str) -> Result<Self, ParseError> {
+        Technique::ALL
+            .iter()
+            .copied()
+            .find(|technique| technique.name().eq_ignore_ascii_case(name))
+            .ok_or(ParseError::InvalidTechnique)
+    }
+}
+
+/// What a technique found.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Deduction {
+    /// n goes in (row, col).
+    Place { row: usize, col: usize, n: u8 },
+    /// These candidates can be ruled out.
+    Eliminate(Vec<Elimination>),
+}
+
+impl Deduction {
+    /// Indicates whether the deduction places n in (row, col).
+    pub fn places(&self, row: usize, col: usize, n: u8) -> bool {
+        *self == Deduction::Place { row, col, n }
+    }
+
+    /// Indicates whether the deduction rules n out of (row, col).
+    pub fn eliminates(&self, row: usize, col: usize, n: u8) -> bool {
+        match self {
+            Deduction::Place { .. } => false,
+            Deduction::Eliminate(eliminations) => eliminations.contains(&(row, col, n)),
+        }
+    }
+}
+
+/// A deduction made by a technique, and the pattern that it follows from.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Finding {
+    pub technique: Technique,
+    pub deduction: Deduction,
+    /// The squares that make up the pattern, such as the corners of an
+    /// x-wing.
+    pub pattern: Vec<(usize, usize)>,
+    /// The units that the pattern lies in.
+    pub units: Vec<Unit>,
+    /// The numbers that the pattern is made of.
+    pub numbers: NineSet,
+}
+
+impl Finding {
+    /// A sentence explaining the deduction, with rows, columns, and boxes
+    /// numbered from 1.
+    pub fn explain(&self) -> String {
+        let square = |(row, col): (usize, usize)| format!("r{}c{}", row + 1, col + 1);
+        let numbers = self
+            .numbers
+            .iter()
+            .map(|n| format!("{}", n))
+            .collect::<Vec<_>>()
+            .join(" or ");
+        match (self.technique, &self.units[..]) {
+            (Technique::NakedSingle, _) => format!(
+                "{} can only be {}, because every other number is already in its row, \
+                 column, or box.",
+                square(self.pattern[0]),
+                numbers
+            ),
+            (Technique::HiddenSingle, [unit]) => format!(
+                "{} can only go in {} within {}.",
+                numbers,
+                square(self.pattern[0]),
+                unit
+            ),
+            (Technique::NakedPair, [unit]) => format!(
+                "{} and {} can only be {}, so no other square in {} can be {}.",
+                square(self.pattern[0]),
+                square(self.pattern[1]),
+                numbers,
+                unit,
+                numbers
+            ),
+            (Technique::PointingPair, [within, line]) => format!(
+                "In {}, {} can only go in {}, so it can be ruled out of the rest of {}.",
+                within, numbers, line, line
+            ),
+            (Technique::XWing, [a, b, c, d]) => format!(
+                "In {} and {}, {} can only go in {} and {}, so it can be ruled out of the \
+                 rest of {} and {}.",
+                a, b, numbers, c, d, c, d
+            ),
+            _ => unreachable!("every technique records the units of its pattern"),
+        }
+    }
+}
+
+/// A puzzle part way through being solved by hand: the numbers placed and
+/// the candidates left in each empty square.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Position {
+    nums: [u8; 81],
+    candidates: [NineSet; 81],
+}
+
+impl Position {
+    /// The position at the start of puzzle, where each empty square's
+    /// candidates are the numbers not already in its row, column, or box.
+    pub fn new(puzzle: &SudokuPuzzle) -> Self {
+        let mut position = Position {
+            nums: [0; 81],
+            candidates: [NineSet::empty(); 81],
+        };
+        for square in 0..81 {
+            let (row, col) = (square / 9, square % 9);
+            match puzzle.get(row, col) {
+                Some(n) => position.nums[square] = n,
+                None => position.candidates[square] = puzzle.candidates(row, col),
+            }
+        }
+        position
+    }
+
+    /// Solve puzzle by hand, always making the simplest deduction there is,
+    /// and return the first position where the simplest deduction takes
+    /// technique. Returns `None` if the puzzle is solved, or gets stuck,
+    /// without ever needing it.
+    pub fn practice(puzzle: &SudokuPuzzle, technique: Technique) -> Option<Self> {
+        let mut position = Position::new(puzzle);
+        while let Some(finding) = position.next() {
+            if finding.technique == technique {
+                return Some(position);
+            }
+            position.apply(&finding.deduction);
+        }
+        None
+    }
+
+    /// Indicates whether puzzle can be solved by hand with only techniques,
+    /// making the simplest of their deductions each time and never
+    /// guessing.
+    ///
+    /// ```
+    /// # use sudoku::{puzzle::SudokuPuzzle, technique::{Position, Technique}};
+    /// let puzzle = SudokuPuzzle::from_string(
+    ///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+    /// )
+    /// .unwrap();
+    /// assert!(Position::solves(&puzzle, &Technique::ALL));
+    /// assert!(!Position::solves(&puzzle, &[Technique::NakedSingle]));
+    /// ```
+    pub fn solves(puzzle: &SudokuPuzzle, techniques: &[Technique]) -> bool {
+        let mut position = Position::new(puzzle);
+        while position.nums.contains(&0) {
+            let finding = Technique::ALL
+                .iter()
+                .filter(|technique| techniques.contains(technique))
+                .find_map(|technique| technique.find(&position));
+            match finding {
+                Some(finding) => position.apply(&finding.deduction),
+                None => return false,
+            }
+        }
+        position.puzzle().is_solved()
+    }
+
+    /// The numbers placed so far as a puzzle, with each as a given.
+    pub fn puzzle(&self) -> SudokuPuzzle {
+        SudokuPuzzle::try_from(&self.nums[..]).expect("positions hold numbers in [0,9]")
+    }
+
+    /// The number at (row, col), if that square has been filled.
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        match self.nums[row * 9 + col] {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    /// The candidates left in (row, col), which are empty for a filled
+    /// square.
+    pub fn candidates(&self, row: usize, col: usize) -> NineSet {
+        self.candidates[row * 9 + col]
+    }
+
+    /// The deduction made by the simplest technique that makes one.
+    pub fn next(&self) -> Option<Finding> {
+        Technique::ALL
+            .iter()
+            .find_map(|technique| technique.find(self))
+    }
+
+    /// Place a number or rule out candidates, as deduction says.
+    pub fn apply(&mut self, deduction: &Deduction) {
+        match deduction {
+            Deduction::Place { row, col, n } => {
+                let square = row * 9 + col;
+                self.nums[square] = *n;
+                self.candidates[square] = NineSet::empty();
+                for peer in PEERS[square].iter() {
+                    self.candidates[*peer].remove(*n);
+                }
+            }
+            Deduction::Eliminate(eliminations) => {
+                for (row, col, n) in eliminations {
+                    self.candidates[row * 9 + col].remove(*n);
+                }
+            }
+        }
+    }
+
+    fn naked_single(&self) -> Option<Finding> {
+        (0..81).find_map(|square| {
+            let n = self.candidates[square].single()?;
+            let (row, col) = (square / 9, square % 9);
+            Some(Finding {
+                technique: Technique::NakedSingle,
+                deduction: Deduction::Place { row, col, n },
+                pattern: alloc::vec![(row, col)],
+                units: Vec::new(),
+                numbers: self.candidates[square],
+            })
+        })
+    }
+
+    fn hidden_single(&self) -> Option<Finding> {
+        for (i, unit) in UNITS.iter().enumerate() {
+            for n in 1..=9 {
+                let mut places = unit.iter().filter(|s| self.candidates[**s].contains(n));
+                if let (Some(square), None) = (places.next(), places.next()) {
+                    let (row, col) = (square / 9, square % 9);
+                    return Some(Finding {
+                        technique: Technique::HiddenSingle,
+                        deduction: Deduction::Place { row, col, n },
+                        pattern: alloc::vec![(row, col)],
+                        units: alloc::vec![Unit::from_index(i)],
+                        numbers: NineSet::from(alloc::vec![n]),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn naked_pair(&self) -> Option<Finding> {
+        for (i, unit) in UNITS.iter().enumerate() {
+            for (j, a) in unit.iter().enumerate() {
+                let pair = self.candidates[*a];
+                if pair.size() != 2 {
+                    continue;
+                }
+                for b in unit[j + 1..]
+                    .iter()
+                    .filter(|b| self.candidates[**b] == pair)
+                {
+                    let others = unit.iter().copied().filter(|s| s != a && s != b);
+                    let eliminations = self.eliminations(others, pair);
+                    if !eliminations.is_empty() {
+                        return Some(Finding {
+                            technique: Technique::NakedPair,
+                            deduction: Deduction::Eliminate(eliminations),
+                            pattern: alloc::vec![(a / 9, a % 9), (b / 9, b % 9)],
+                            units: alloc::vec![Unit::from_index(i)],
+                            numbers: pair,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn pointing_pair(&self) -> Option<Finding> {
+        for (i, squares) in BOXES.iter().enumerate() {
+            for n in 1..=9 {
+                let places: Vec<usize> = squares
+                    .iter()
+                    .copied()
+                    .filter(|s| self.candidates[*s].contains(n))
+                    .collect();
+                if places.len() < 2 {
+                    continue;
+                }
+                let row = places[0] / 9;
+                let col = places[0] % 9;
+                let line = if places.iter().all(|s| s / 9 == row) {
+                    (Unit::Row(row), &ROWS[row])
+                } else if places.iter().all(|s| s % 9 == col) {
+                    (Unit::Col(col), &COLS[col])
+                } else {
+                    continue;
+                };
+
+                let number = NineSet::from(alloc::vec![n]);
+                let outside = line
+                    .1
+                    .iter()
+                    .copied()
+                    .filter(|s| box_index(s / 9, s % 9) != i);
+                let eliminations = self.eliminations(outside, number);
+                if !eliminations.is_empty() {
+                    return Some(Finding {
+                        technique: Technique::PointingPair,
+                        deduction: Deduction::Eliminate(eliminations),
+                        pattern: places.iter().map(|s| (s / 9, s % 9)).collect(),
+                        units: alloc::vec![Unit::Box(i), line.0],
+                        numbers: number,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn x_wing(&self) -> Option<Finding> {
+        // n confined to two columns of two rows rules it out of the rest of
+        // those columns, and then the same with rows and columns swapped
+        for transposed in [false, true].iter().copied() {
+            let square = |line: usize, i: usize| {
+                if transposed {
+                    i * 9 + line
+                } else {
+                    line * 9 + i
+                }
+            };
+            let base: fn(usize) -> Unit = if transposed { Unit::Col } else { Unit::Row };
+            let cover: fn(usize) -> Unit = if transposed { Unit::Row } else { Unit::Col };
+
+            for n in 1..=9 {
+                // For each line, the places along it where n can go
+                let places: Vec<Vec<usize>> = (0..9)
+                    .map(|line| {
+                        (0..9)
+                            .filter(|i| self.candidates[square(line, *i)].contains(n))
+                            .collect()
+                    })
+                    .collect();
+                for (a, pair) in places.iter().enumerate() {
+                    if pair.len() != 2 {
+                        continue;
+                    }
+                    for b in (a + 1..9).filter(|b| places[*b] == *pair) {
+                        let (c, d) = (pair[0], pair[1]);
+                        let rest = (0..9)
+                            .filter(|line| *line != a && *line != b)
+                            .flat_map(|line| alloc::vec![square(line, c), square(line, d)]);
+                        let number = NineSet::from(alloc::vec![n]);
+                        let eliminations = self.eliminations(rest, number);
+                        if !eliminations.is_empty() {
+                            let corners = [square(a, c), square(a, d), square(b, c), square(b, d)];
+                            return Some(Finding {
+                                technique: Technique::XWing,
+                                deduction: Deduction::Eliminate(eliminations),
+                                pattern: corners.iter().map(|s| (s / 9, s % 9)).collect(),
+                                units: alloc::vec![base(a), base(b), cover(c), cover(d)],
+                                numbers: number,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The candidates in numbers that can be ruled out of squares.
+    fn eliminations<I>(&self, squares: I, numbers: NineSet) -> Vec<Elimination>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let mut eliminations = Vec::new();
+        for square in squares {
+            for n in self.candidates[square].intersection(&numbers).iter() {
+                eliminations.push((square / 9, square % 9, n));
+            }
+        }
+        eliminations
+    }
+}
@@ -0,0 +1,273 @@
+//! A full record of a solve, with the candidates of every square after each
+//! step, for replaying the solve outside of this crate.
+//!
+//! [`Trace::to_json`] writes a trace as a JSON object with these fields.
+//! Rows, columns, and units are numbered from 0, and numbers are 1 to 9.
+//!
+//! - `"version"`: the version of this schema, currently 1.
+//! - `"puzzle"`: the puzzle that was solved, as 81 digits with 0 for an
+//!   empty square.
+//! - `"solution"`: the solution as 81 digits, or `null` if there is none.
+//! - `"candidates"`: the candidates of each square before the first step,
+//!   as an array of 81 strings of digits in row-major order. Filled squares
+//!   have no candidates, so their strings are empty.
+//! - `"steps"`: every step the solver took, in order, each an object with:
+//!   - `"kind"`: `"single"`, `"hidden_single"`, `"guess"`, or
+//!     `"backtrack"`.
+//!   - `"row"` and `"col"`: the square that the step filled or, for a
+//!     backtrack, the guess that was taken back.
+//!   - `"n"`: the number placed, left out for a backtrack.
+//!   - `"unit"`: for a hidden single only, the unit in which n could only
+//!     go in this square, as `{"kind": "row" | "col" | "box", "index": i}`.
+//!   - `"eliminations"`: the candidates that placing n removed from the
+//!     square's peers, as an array of `{"row", "col", "n"}` objects. Empty
+//!     for a backtrack.
+//!   - `"emptied"`: for a backtrack, the squares emptied again, the guess
+//!     and every square filled after it, as an array of `[row, col]` pairs
+//!     in the order they were filled. Empty for every other step.
+//!   - `"candidates"`: the candidates of each square after the step, like
+//!     the top level `"candidates"`.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, trace::Trace};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let trace = Trace::of(&puzzle);
+//! assert_eq!(puzzle.solve(), trace.solution());
+//!
+//! let json = trace.to_json();
+//! assert!(json.starts_with("{\"version\":1,\"puzzle\":\"4100360"));
+//! assert!(json.contains("\"kind\":\"single\""));
+//! ```
+
+use crate::{
+    nine_set::NineSet,
+    puzzle::SudokuPuzzle,
+    solution::Solution,
+    step::Step,
+    units::{Unit, PEERS},
+};
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+/// The version of the JSON schema written by [`Trace::to_json`].
+pub const VERSION: u32 = 1;
+
+/// A candidate removed from a square, as (row, col, n).
+pub type Elimination = (usize, usize, u8);
+
+/// One step of a solve and what it changed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Event {
+    pub step: Step,
+    /// The candidates that the step's number removed from its peers.
+    pub eliminations: Vec<Elimination>,
+    /// For a backtrack, the squares emptied again, in the order they were
+    /// filled.
+    pub emptied: Vec<(usize, usize)>,
+    /// The candidates of each square after the step, in row-major order,
+    /// with none for filled squares.
+    pub candidates: Vec<NineSet>,
+}
+
+/// Every step taken to solve a puzzle, with the candidates before and after
+/// each one.
+#[derive(Debug, Clone)]
+pub struct Trace {
+    puzzle: SudokuPuzzle,
+    solution: Option<Solution>,
+    candidates: Vec<NineSet>,
+    events: Vec<Event>,
+}
+
+impl Trace {
+    /// Solve puzzle with [`SudokuPuzzle::solve_with_steps`] and replay its
+    /// steps to find what each one changed.
+    pub fn of(puzzle: &SudokuPuzzle) -> Self {
+        let (solution, steps) = puzzle.solve_with_steps();
+        let mut working = puzzle.clone();
+        let candidates = open_candidates(&working);
+
+        // The squares filled since each guess that has not been taken back,
+        // starting with the guess itself
+        let mut guesses: Vec<Vec<(usize, usize)>> = Vec::new();
+        let events = steps
+            .into_iter()
+            .map(|step| {
+                let (row, col) = step.square();
+                let mut eliminations = Vec::new();
+                let mut emptied = Vec::new();
+                match step.number() {
+                    Some(n) => {
+                        for peer in PEERS[row * 9 + col].iter() {
+                            let (r, c) = (peer / 9, peer % 9);
+                            if working.get(r, c).is_none() && working.candidates(r, c).contains(n) {
+                                eliminations.push((r, c, n));
+                            }
+                        }
+                        working.put(row, col, Some(n));
+                        if let Step::Guess { .. } = step {
+                            guesses.push(Vec::new());
+                        }
+                        if let Some(filled) = guesses.last_mut() {
+                            filled.push((row, col));
+                        }
+                    }
+                    None => {
+                        while let Some(filled) = guesses.pop() {
+                            let guess = filled[0];
+                            emptied.splice(0..0, filled);
+                            if guess == (row, col) {
+                                break;
+                            }
+                        }
+                        for (r, c) in emptied.iter().rev() {
+                            working.put(*r, *c, None);
+                        }
+                    }
+                }
+                Event {
+                    step,
+                    eliminations,
+                    emptied,
+                    candidates: open_candidates(&working),
+                }
+            })
+            .collect();
+
+        Trace {
+            puzzle: puzzle.clone(),
+            solution,
+            candidates,
+            events,
+        }
+    }
+
+    /// The puzzle that was solved.
+    pub fn puzzle(&self) -> &SudokuPuzzle {
+        &self.puzzle
+    }
+
+    /// The solution that was found, if there is one.
+    pub fn solution(&self) -> Option<Solution> {
+        self.solution
+    }
+
+    /// The candidates of each square before the first step, in row-major
+    /// order, with none for filled squares.
+    pub fn candidates(&self) -> &[NineSet] {
+        &self.candidates
+    }
+
+    /// Each step the solver took, in order.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// The trace as a single line of JSON, following the schema described
+    /// in the [module documentation](self).
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        let _ = write!(
+            json,
+            "{{\"version\":{},\"puzzle\":\"{}\",\"solution\":",
+            VERSION,
+            digits(&self.puzzle.to_vec())
+        );
+        match self.solution {
+            Some(solution) => {
+                let _ = write!(json, "\"{}\"", digits(&solution.to_vec()));
+            }
+            None => json.push_str("null"),
+        }
+        json.push_str(",\"candidates\":");
+        push_candidates(&mut json, &self.candidates);
+
+        json.push_str(",\"steps\":[");
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            push_event(&mut json, event);
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// The candidates of each empty square of puzzle, and none for the rest.
+fn open_candidates(puzzle: &SudokuPuzzle) -> Vec<NineSet> {
+    (0..81)
+        .map(|square| match puzzle.get(square / 9, square % 9) {
+            Some(_) => NineSet::empty(),
+            None => puzzle.candidates(square / 9, square % 9),
+        })
+        .collect()
+}
+
+fn digits(nums: &[u8]) -> String {
+    nums.iter().map(|n| char::from(b'0' + n)).collect()
+}
+
+fn push_candidates(json: &mut String, candidates: &[NineSet]) {
+    json.push('[');
+    for (i, set) in candidates.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push('"');
+        json.extend(set.iter().map(|n| char::from(b'0' + n)));
+        json.push('"');
+    }
+    json.push(']');
+}
+
+fn push_event(json: &mut String, event: &Event) {
+    let (row, col) = event.step.square();
+    let kind = match event.step {
+        Step::Single { .. } => "single",
+        Step::HiddenSingle { .. } => "hidden_single",
+        Step::Guess { .. } => "guess",
+        Step::Backtrack { .. } => "backtrack",
+    };
+    let _ = write!(
+        json,
+        "{{\"kind\":\"{}\",\"row\":{},\"col\":{}",
+        kind, row, col
+    );
+    if let Some(n) = event.step.number() {
+        let _ = write!(json, ",\"n\":{}", n);
+    }
+    if let Step::HiddenSingle { unit, .. } = event.step {
+        let (kind, index) = match unit {
+            Unit::Row(i) => ("row", i),
+            Unit::Col(i) => ("col", i),
+            Unit::Box(i) => ("box", i),
+        };
+        let _ = write!(
+            json,
+            ",\"unit\":{{\"kind\":\"{}\",\"index\":{}}}",
+            kind, index
+        );
+    }
+
+    json.push_str(",\"eliminations\":[");
+    for (i, (row, col, n)) in event.eliminations.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        let _ = write!(json, "{{\"row\":{},\"col\":{},\"n\":{}}}", row, col, n);
+    }
+    json.push_str("],\"emptied\":[");
+    for (i, (row, col)) in event.emptied.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        let _ = write!(json, "[{},{}]", row, col);
+    }
+    json.push_str("],\"candidates\":");
+    push_candidates(json, &event.candidates);
+    json.push('}');
+}
@@ -0,0 +1,159 @@
+//! The rearrangements of a grid that keep it a valid Sudoku: any puzzle made
+//! from another by these has the same number of solutions, rearranged the
+//! same way, and is just as hard.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, transform::{Axis, Transform}};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let moved = puzzle
+//!     .transform(Transform::Rotate { quarter_turns: 1 })
+//!     .and_then(|p| p.transform(Transform::Reflect(Axis::Vertical)))
+//!     .unwrap();
+//! // A quarter turn and a mirror image is the same as transposing
+//! assert_eq!(moved.to_vec(), puzzle.transform(Transform::Transpose).unwrap().to_vec());
+//! assert_eq!(puzzle.canonical().to_vec(), moved.canonical().to_vec());
+//! ```
+
+use crate::error::TransformError;
+use alloc::vec::Vec;
+use rand::{seq::SliceRandom, Rng};
+
+/// A line across the grid to reflect it in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Axis {
+    /// The line between the left and right halves, which swaps the columns
+    /// left to right.
+    Vertical,
+    /// The line between the top and bottom halves, which swaps the rows top
+    /// to bottom.
+    Horizontal,
+}
+
+/// One rearrangement of a grid. Bands are the three groups of three rows
+/// and stacks the three groups of three columns, numbered from 0 like rows
+/// and columns.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Transform {
+    /// Turn the grid clockwise by a number of quarter turns.
+    Rotate { quarter_turns: usize },
+    /// Mirror the grid in an axis.
+    Reflect(Axis),
+    /// Swap the rows with the columns, mirroring the grid in the diagonal
+    /// from the top left.
+    Transpose,
+    /// Swap two bands.
+    SwapBands(usize, usize),
+    /// Swap two stacks.
+    SwapStacks(usize, usize),
+    /// Swap two rows in the same band.
+    SwapRows(usize, usize),
+    /// Swap two columns in the same stack.
+    SwapColumns(usize, usize),
+    /// Replace each number n with `labels[n - 1]`. The labels must be 1-9 in
+    /// some order.
+    Relabel([u8; 9]),
+}
+
+impl Transform {
+    /// Check that the bands, stacks, rows, columns, or labels are valid.
+    pub(crate) fn check(&self) -> Result<(), TransformError> {
+        let in_range = |index: usize, len: usize| {
+            if index < len {
+                Ok(())
+            } else {
+                Err(TransformError::OutOfRange { index })
+            }
+        };
+        match *self {
+            Transform::Rotate { .. } | Transform::Reflect(_) | Transform::Transpose => Ok(()),
+            Transform::SwapBands(a, b) | Transform::SwapStacks(a, b) => {
+                in_range(a, 3)?;
+                in_range(b, 3)
+            }
+            Transform::SwapRows(a, b) | Transform::SwapColumns(a, b) => {
+                in_range(a, 9)?;
+                in_range(b, 9)?;
+                if a / 3 == b / 3 {
+                    Ok(())
+                } else {
+                    Err(TransformError::DifferentBoxes { a, b })
+                }
+            }
+            Transform::Relabel(labels) => {
+                let mut seen = [false; 10];
+                for n in labels.iter() {
+                    match seen.get_mut(*n as usize) {
+                        Some(seen) if *n != 0 && !*seen => *seen = true,
+                        _ => return Err(TransformError::NotPermutation),
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The square of the original grid that moves to (row, col).
+    pub(crate) fn source(&self, row: usize, col: usize) -> (usize, usize) {
+        // Exchange a and b, leaving any other line where it is
+        let swap = |line: usize, a: usize, b: usize| match line {
+            _ if line == a => b,
+            _ if line == b => a,
+            _ => line,
+        };
+        match *self {
+            Transform::Rotate { quarter_turns } => {
+                (0..quarter_turns % 4).fold((row, col), |(row, col), _| (8 - col, row))
+            }
+            Transform::Reflect(Axis::Vertical) => (row, 8 - col),
+            Transform::Reflect(Axis::Horizontal) => (8 - row, col),
+            Transform::Transpose => (col, row),
+            Transform::SwapBands(a, b) => (swap(row / 3, a, b) * 3 + row % 3, col),
+            Transform::SwapStacks(a, b) => (row, swap(col / 3, a, b) * 3 + col % 3),
+            Transform::SwapRows(a, b) => (swap(row, a, b), col),
+            Transform::SwapColumns(a, b) => (row, swap(col, a, b)),
+            Transform::Relabel(_) => (row, col),
+        }
+    }
+
+    /// The number that n becomes.
+    pub(crate) fn label(&self, n: u8) -> u8 {
+        match self {
+            Transform::Relabel(labels) if (1..=9).contains(&n) => labels[n as usize - 1],
+            _ => n,
+        }
+    }
+}
+
+/// A random sequence of transformations, which together can make any of
+/// the puzzles equivalent to a given one, each as likely as any other.
+pub(crate) fn random<R: Rng + ?Sized>(rng: &mut R) -> Vec<Transform> {
+    let mut transforms = Vec::new();
+    if rng.random_bool(0.5) {
+        transforms.push(Transform::Transpose);
+    }
+
+    // Shuffle each group of three by swapping the last with one at random,
+    // then the middle with one of the first two
+    let mut shuffle = |swap: &dyn Fn(usize, usize) -> Transform| {
+        for i in (1..3).rev() {
+            let j = rng.random_range(0..=i);
+            if i != j {
+                transforms.push(swap(i, j));
+            }
+        }
+    };
+    shuffle(&Transform::SwapBands);
+    shuffle(&Transform::SwapStacks);
+    for first in [0, 3, 6].iter() {
+        shuffle(&|a, b| Transform::SwapRows(first + a, first + b));
+        shuffle(&|a, b| Transform::SwapColumns(first + a, first + b));
+    }
+
+    let mut labels = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    labels.shuffle(rng);
+    transforms.push(Transform::Relabel(labels));
+    transforms
+}
@@ -0,0 +1,179 @@
+//! The rows, columns, and 3x3 boxes of a 9x9 puzzle, collectively called
+//! units. Squares are identified by their linear, row-major index in the
+//! range [0, 81), and units are indexed left to right, top to bottom.
+
+use core::fmt;
+
+/// The indices of the squares in each row.
+pub const ROWS: [[usize; 9]; 9] = build_rows();
+
+/// The indices of the squares in each column.
+pub const COLS: [[usize; 9]; 9] = build_cols();
+
+/// The indices of the squares in each 3x3 box.
+pub const BOXES: [[usize; 9]; 9] = build_boxes();
+
+/// The indices of the squares in all 27 units: the rows, then the columns,
+/// then the boxes.
+pub const UNITS: [[usize; 9]; 27] = build_units();
+
+/// For each square, the indices into `UNITS` of its row, column, and box.
+///
+/// ```
+/// # use sudoku::units::{SQUARE_UNITS, UNITS};
+/// let [row, col, bx] = SQUARE_UNITS[40];
+/// assert_eq!([36, 37, 38, 39, 40, 41, 42, 43, 44], UNITS[row]);
+/// assert_eq!([4, 13, 22, 31, 40, 49, 58, 67, 76], UNITS[col]);
+/// assert_eq!([30, 31, 32, 39, 40, 41, 48, 49, 50], UNITS[bx]);
+/// ```
+pub const SQUARE_UNITS: [[usize; 3]; 81] = build_square_units();
+
+/// For each square, the indices of the 20 other squares that share its
+/// row, column, or box.
+pub const PEERS: [[usize; 20]; 81] = build_peers();
+
+/// A single row, column, or 3x3 box, identified by its index in [0, 9).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Unit {
+    Row(usize),
+    Col(usize),
+    Box(usize),
+}
+
+impl Unit {
+    /// The unit at index i of `UNITS`.
+    ///
+    /// ```
+    /// # use sudoku::units::Unit;
+    /// assert_eq!(Unit::Row(4), Unit::from_index(4));
+    /// assert_eq!(Unit::Col(0), Unit::from_index(9));
+    /// assert_eq!(Unit::Box(8), Unit::from_index(26));
+    /// ```
+    pub fn from_index(i: usize) -> Self {
+        assert!(i < 27);
+
+        match i / 9 {
+            0 => Unit::Row(i),
+            1 => Unit::Col(i - 9),
+            _ => Unit::Box(i - 18),
+        }
+    }
+
+    /// The indices of the squares in this unit.
+    pub fn squares(&self) -> [usize; 9] {
+        match *self {
+            Unit::Row(i) => ROWS[i],
+            Unit::Col(i) => COLS[i],
+            Unit::Box(i) => BOXES[i],
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    /// The unit as it would be described to a person, numbered from 1.
+    ///
+    /// ```
+    /// # use sudoku::units::Unit;
+    /// assert_eq!("column 7", Unit::Col(6).to_string());
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Row(i) => write!(f, "row {}", i + 1),
+            Unit::Col(i) => write!(f, "column {}", i + 1),
+            Unit::Box(i) => write!(f, "box {}", i + 1),
+        }
+    }
+}
+
+/// The index of the 3x3 box containing (row, col).
+pub const fn box_index(row: usize, col: usize) -> usize {
+    row / 3 * 3 + col / 3
+}
+
+const fn build_rows() -> [[usize; 9]; 9] {
+    let mut rows = [[0; 9]; 9];
+    let mut row = 0;
+    while row < 9 {
+        let mut col = 0;
+        while col < 9 {
+            rows[row][col] = row * 9 + col;
+            col += 1;
+        }
+        row += 1;
+    }
+    rows
+}
+
+const fn build_cols() -> [[usize; 9]; 9] {
+    let mut cols = [[0; 9]; 9];
+    let mut col = 0;
+    while col < 9 {
+        let mut row = 0;
+        while row < 9 {
+            cols[col][row] = row * 9 + col;
+            row += 1;
+        }
+        col += 1;
+    }
+    cols
+}
+
+const fn build_boxes() -> [[usize; 9]; 9] {
+    let mut boxes = [[0; 9]; 9];
+    let mut bx = 0;
+    while bx < 9 {
+        let mut i = 0;
+        while i < 9 {
+            let row = bx / 3 * 3 + i / 3;
+            let col = bx % 3 * 3 + i % 3;
+            boxes[bx][i] = row * 9 + col;
+            i += 1;
+        }
+        bx += 1;
+    }
+    boxes
+}
+
+const fn build_units() -> [[usize; 9]; 27] {
+    let mut units = [[0; 9]; 27];
+    let mut i = 0;
+    while i < 9 {
+        units[i] = ROWS[i];
+        units[9 + i] = COLS[i];
+        units[18 + i] = BOXES[i];
+        i += 1;
+    }
+    units
+}
+
+const fn build_square_units() -> [[usize; 3]; 81] {
+    let mut square_units = [[0; 3]; 81];
+    let mut square = 0;
+    while square < 81 {
+        let (row, col) = (square / 9, square % 9);
+        square_units[square] = [row, 9 + col, 18 + box_index(row, col)];
+        square += 1;
+    }
+    square_units
+}
+
+const fn build_peers() -> [[usize; 20]; 81] {
+    let mut peers = [[0; 20]; 81];
+    let mut square = 0;
+    while square < 81 {
+        let (row, col) = (square / 9, square % 9);
+        let mut count = 0;
+        let mut other = 0;
+        while other < 81 {
+            let (r, c) = (other / 9, other % 9);
+            let shares_unit = r == row || c == col || box_index(r, c) == box_index(row, col);
+            if other != square && shares_unit {
+                peers[square][count] = other;
+                count += 1;
+            }
+            other += 1;
+        }
+        square += 1;
+    }
+    peers
+}
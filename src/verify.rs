@@ -0,0 +1,195 @@
+//! Checks of the invariants that this crate's solutions and canonical forms
+//! keep, for programs that want to assert them at runtime. A failed check
+//! means there is a bug in this crate.
+//!
+//! [`verify_solution`] is the exception: it checks a solution proposed from
+//! outside the crate, such as a player's submission.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, verify};
+//! let puzzle = SudokuPuzzle::from_string(
+//!     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+//! )
+//! .unwrap();
+//! let solution = puzzle.solve().unwrap();
+//! assert_eq!(Ok(()), verify::check_solution(&puzzle, &solution));
+//! assert_eq!(Ok(()), verify::check_solver(&puzzle));
+//! assert_eq!(Ok(()), verify::check_canonical(&puzzle));
+//! ```
+
+use crate::{
+    error::{VerificationError, Violation},
+    nine_set::NineSet,
+    puzzle::SudokuPuzzle,
+    solution::Solution,
+    transform::{Axis, Transform},
+    units::{Unit, UNITS},
+};
+use alloc::vec::Vec;
+
+/// Transformations that make an equivalent puzzle, together moving every
+/// square and relabeling every digit.
+const MOVES: [Transform; 5] = [
+    Transform::Transpose,
+    Transform::Reflect(Axis::Horizontal),
+    Transform::SwapStacks(0, 2),
+    Transform::SwapRows(3, 5),
+    Transform::Relabel([2, 3, 4, 5, 6, 7, 8, 9, 1]),
+];
+
+/// Check that every row, column, and box of solution holds each of 1-9 once.
+pub fn check_units(solution: &Solution) -> Result<(), Violation> {
+    let nums = solution.to_vec();
+    for (i, unit) in UNITS.iter().enumerate() {
+        let mut seen = NineSet::empty();
+        for square in unit.iter() {
+            let n = nums[*square];
+            if !(1..=9).contains(&n) || seen.contains(n) {
+                return Err(Violation::NotPermutation {
+                    unit: Unit::from_index(i),
+                });
+            }
+            seen.add(n);
+        }
+    }
+    Ok(())
+}
+
+/// Check that solution is a valid grid that keeps every filled square of
+/// puzzle.
+///
+/// ```
+/// # use sudoku::{error::Violation, puzzle::SudokuPuzzle, verify};
+/// let puzzle = SudokuPuzzle::from_string(
+///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+/// )
+/// .unwrap();
+/// let mut other = SudokuPuzzle::empty();
+/// other.set(0, 0, Some(5)).unwrap();
+/// let err = verify::check_solution(&puzzle, &other.solve().unwrap());
+/// assert_eq!(Err(Violation::Changed { row: 0, col: 0, n: 4 }), err);
+/// ```
+pub fn check_solution(puzzle: &SudokuPuzzle, solution: &Solution) -> Result<(), Violation> {
+    for row in 0..9 {
+        for col in 0..9 {
+            match puzzle.get(row, col) {
+                Some(n) if solution.get(row, col) != n => {
+                    return Err(Violation::Changed { row, col, n })
+                }
+                _ => {}
+            }
+        }
+    }
+    check_units(solution)
+}
+
+/// Check a solution proposed for puzzle, given as its 81 squares row by row
+/// with 0 for an empty square: that it is complete, that no row, column, or
+/// box repeats a number, and that it keeps every given. Problems are
+/// looked for in that order, and the first one found is returned.
+///
+/// This does not solve the puzzle, so it accepts any valid grid that keeps
+/// the givens, including one of several solutions of an ambiguous puzzle.
+///
+/// ```
+/// # use sudoku::{error::VerificationError, puzzle::SudokuPuzzle, units::Unit, verify};
+/// let puzzle = SudokuPuzzle::from_string(
+///     "410036000007000850600000000090000200006070008000000091002014000000003000740008509",
+/// )
+/// .unwrap();
+/// let mut proposed = puzzle.solve().unwrap().to_vec();
+/// assert_eq!(Ok(()), verify::verify_solution(&puzzle, &proposed));
+///
+/// proposed[80] = 0;
+/// let err = verify::verify_solution(&puzzle, &proposed);
+/// assert_eq!(Err(VerificationError::Incomplete { row: 8, col: 8 }), err);
+///
+/// proposed[80] = 4;
+/// let err = verify::verify_solution(&puzzle, &proposed);
+/// assert_eq!(Err(VerificationError::Repeated { unit: Unit::Row(8), n: 4 }), err);
+/// ```
+pub fn verify_solution(puzzle: &SudokuPuzzle, proposed: &[u8]) -> Result<(), VerificationError> {
+    if proposed.len() != 81 {
+        return Err(VerificationError::WrongLength {
+            len: proposed.len(),
+        });
+    }
+    for (square, n) in proposed.iter().copied().enumerate() {
+        let (row, col) = (square / 9, square % 9);
+        match n {
+            0 => return Err(VerificationError::Incomplete { row, col }),
+            1..=9 => {}
+            _ => return Err(VerificationError::InvalidNumber { row, col, n }),
+        }
+    }
+
+    for (i, unit) in UNITS.iter().enumerate() {
+        let mut seen = NineSet::empty();
+        for square in unit.iter() {
+            let n = proposed[*square];
+            if seen.contains(n) {
+                let unit = Unit::from_index(i);
+                return Err(VerificationError::Repeated { unit, n });
+            }
+            seen.add(n);
+        }
+    }
+
+    for (square, found) in proposed.iter().copied().enumerate() {
+        let (row, col) = (square / 9, square % 9);
+        match puzzle.get(row, col) {
+            Some(given) if puzzle.is_given(row, col) && given != found => {
+                return Err(VerificationError::ChangedGiven {
+                    row,
+                    col,
+                    given,
+                    found,
+                })
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Check that every solution the solver finds for puzzle, up to two, is a
+/// solution of it, and that the solver and the solution counter agree on
+/// how many there are.
+pub fn check_solver(puzzle: &SudokuPuzzle) -> Result<(), Violation> {
+    let solutions: Vec<Solution> = puzzle.solutions().take(2).collect();
+    for solution in &solutions {
+        check_solution(puzzle, solution)?;
+    }
+
+    let counted = puzzle.count_solutions(2);
+    if counted != solutions.len() {
+        return Err(Violation::Miscounted {
+            found: solutions.len(),
+            counted,
+        });
+    }
+    match puzzle.solve() {
+        Some(solution) => check_solution(puzzle, &solution),
+        None if counted > 0 => Err(Violation::Miscounted { found: 0, counted }),
+        None => Ok(()),
+    }
+}
+
+/// Check that puzzle's canonical form is its own canonical form, and is the
+/// same as that of several puzzles equivalent to it.
+pub fn check_canonical(puzzle: &SudokuPuzzle) -> Result<(), Violation> {
+    let canonical = puzzle.canonical();
+    if canonical.canonical().to_vec() != canonical.to_vec() {
+        return Err(Violation::NotCanonical);
+    }
+
+    for transform in MOVES.iter() {
+        let equivalent = puzzle
+            .transform(*transform)
+            .expect("the transformations are valid");
+        if equivalent.canonical().to_vec() != canonical.to_vec() {
+            return Err(Violation::NotCanonical);
+        }
+    }
+    Ok(())
+}
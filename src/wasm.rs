@@ -0,0 +1,141 @@
+//! JavaScript bindings for WebAssembly, enabled with the `wasm` feature.
+//!
+//! Puzzles are passed to and from JavaScript as strings of 81 digits, where
+//! 0 (or '.') is an empty square, and grids are returned as `Uint8Array`s in
+//! row-major order.
+//!
+//! ```js
+//! import { solve, rate } from "sudoku";
+//! const puzzle = "410036000007000850600000000090000200006070008000000091002014000000003000740008509";
+//! solve(puzzle);             // "415836927237149856..."
+//! rate(puzzle).difficulty;   // "medium"
+//! ```
+
+use crate::{
+    deterministic,
+    hint::{self, Level},
+    puzzle::SudokuPuzzle,
+    rating::Difficulty,
+    step::Step,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use wasm_bindgen::prelude::*;
+
+/// Read a puzzle, treating '.' as an empty square.
+fn read(puzzle: &str) -> Result<SudokuPuzzle, JsError> {
+    Ok(SudokuPuzzle::from_string(&puzzle.replace('.', "0"))?)
+}
+
+/// The squares of a puzzle, checking that it can be read.
+#[wasm_bindgen]
+pub fn parse(puzzle: &str) -> Result<Vec<u8>, JsError> {
+    Ok(read(puzzle)?.to_vec())
+}
+
+/// The solution of a puzzle as 81 digits, or `undefined` if it has none.
+#[wasm_bindgen]
+pub fn solve(puzzle: &str) -> Result<Option<String>, JsError> {
+    let solution = read(puzzle)?.solve();
+    Ok(solution.map(|solution| {
+        solution
+            .to_vec()
+            .iter()
+            .map(|n| char::from(b'0' + n))
+            .collect()
+    }))
+}
+
+/// A number that can be placed next, and why.
+#[wasm_bindgen]
+pub struct Hint {
+    pub row: usize,
+    pub col: usize,
+    pub n: u8,
+    hint: hint::Hint,
+}
+
+#[wasm_bindgen]
+impl Hint {
+    /// The solver's description of the step, such as "r4c7=2: naked single".
+    #[wasm_bindgen(getter)]
+    pub fn reason(&self) -> String {
+        match self.hint.step() {
+            Step::Guess { .. } => {
+                String::from("nothing can be deduced, so this is from the solution")
+            }
+            step => step.to_string(),
+        }
+    }
+
+    /// A sentence explaining the hint to a player, such as "r4c7 can only
+    /// be 2, because every other number is already in its row, column, or
+    /// box."
+    #[wasm_bindgen(getter)]
+    pub fn explanation(&self) -> String {
+        self.hint.reveal(Level::Cell)
+    }
+
+    /// The hint as a sentence that gives away more at each level: 1 names
+    /// the technique, 2 the row, column, or box to look in, and 3 (or more)
+    /// the square and its number.
+    pub fn reveal(&self, level: u8) -> String {
+        let level = match level {
+            0 | 1 => Level::Technique,
+            2 => Level::Region,
+            _ => Level::Cell,
+        };
+        self.hint.reveal(level)
+    }
+}
+
+/// The next step the solver would take, or `undefined` if the puzzle has no
+/// solution or is already solved. When nothing can be deduced, the hint is
+/// the solution's number for the square the solver would guess.
+#[wasm_bindgen]
+pub fn hint(puzzle: &str) -> Result<Option<Hint>, JsError> {
+    Ok(read(puzzle)?.hint().map(|hint| {
+        let (row, col, n) = hint.cell();
+        Hint { row, col, n, hint }
+    }))
+}
+
+/// How hard a puzzle is.
+#[wasm_bindgen]
+pub struct Rating {
+    difficulty: Difficulty,
+    pub score: u32,
+}
+
+#[wasm_bindgen]
+impl Rating {
+    /// "easy", "medium", "hard", or "expert".
+    #[wasm_bindgen(getter)]
+    pub fn difficulty(&self) -> String {
+        String::from(self.difficulty.name())
+    }
+}
+
+/// Rate a puzzle, or return `undefined` if it has no solution.
+#[wasm_bindgen]
+pub fn rate(puzzle: &str) -> Result<Option<Rating>, JsError> {
+    Ok(read(puzzle)?.rating().map(|rating| Rating {
+        difficulty: rating.difficulty,
+        score: rating.score,
+    }))
+}
+
+/// Generate a puzzle of the named difficulty as 81 digits. The same seed
+/// always gives the same puzzle.
+#[wasm_bindgen]
+pub fn generate(difficulty: &str, seed: u32) -> Result<String, JsError> {
+    let difficulty: Difficulty = difficulty.parse()?;
+    let puzzle = deterministic::generate(u64::from(seed), difficulty);
+    Ok(puzzle
+        .to_vec()
+        .iter()
+        .map(|n| char::from(b'0' + n))
+        .collect())
+}
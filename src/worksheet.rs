@@ -0,0 +1,347 @@
+//! Printable worksheets: several puzzles to a page, followed by an answer
+//! key, written as a PDF.
+//!
+//! ```
+//! # use sudoku::{puzzle::SudokuPuzzle, worksheet::{self, WorksheetOptions}};
+//! let puzzles = vec![SudokuPuzzle::empty(); 5];
+//! let options = WorksheetOptions {
+//!     per_page: 4,
+//!     title: Some(String::from("Week 1")),
+//!     ..WorksheetOptions::default()
+//! };
+//! let pdf = worksheet::pdf(&puzzles, &options);
+//! assert!(pdf.starts_with(b"%PDF-1.4"));
+//! assert!(pdf.ends_with(b"%%EOF\n"));
+//! ```
+
+use crate::puzzle::SudokuPuzzle;
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+/// The space around the edge of each page, in points.
+const MARGIN: f32 = 36.0;
+/// The height of the title at the top of a page.
+const TITLE_HEIGHT: f32 = 32.0;
+/// The height of the page number at the bottom of a page.
+const FOOTER_HEIGHT: f32 = 20.0;
+/// The height of the label above each grid.
+const LABEL_HEIGHT: f32 = 16.0;
+/// The space between neighbouring grids.
+const GAP: f32 = 24.0;
+/// How many solutions fit on a page of the answer key.
+const ANSWERS_PER_PAGE: usize = 12;
+/// The width of a digit in Helvetica, as a fraction of the font size.
+const DIGIT_WIDTH: f32 = 0.556;
+/// The height of a digit in Helvetica, as a fraction of the font size.
+const DIGIT_HEIGHT: f32 = 0.703;
+
+/// The size of the pages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Paper {
+    /// US Letter, 8.5 by 11 inches.
+    #[default]
+    Letter,
+    /// A4, 210 by 297 millimetres.
+    A4,
+}
+
+impl Paper {
+    /// The width and height of the page, in points.
+    fn size(self) -> (f32, f32) {
+        match self {
+            Paper::Letter => (612.0, 792.0),
+            Paper::A4 => (595.28, 841.89),
+        }
+    }
+}
+
+/// How to lay out a worksheet.
+#[derive(Debug, Clone)]
+pub struct WorksheetOptions {
+    /// How many puzzles to put on each page, from 1 to 12.
+    pub per_page: usize,
+    pub paper: Paper,
+    /// A title for the top of each page of puzzles. Characters other than
+    /// printable ASCII are written as '?'.
+    pub title: Option<String>,
+    /// Add pages with the solution of each puzzle after the puzzles.
+    pub answers: bool,
+}
+
+impl Default for WorksheetOptions {
+    fn default() -> Self {
+        WorksheetOptions {
+            per_page: 4,
+            paper: Paper::default(),
+            title: None,
+            answers: true,
+        }
+    }
+}
+
+/// The puzzles as a PDF, numbered from 1, per_page to a page, and then the
+/// answer key if options.answers is set.
+pub fn pdf(puzzles: &[SudokuPuzzle], options: &WorksheetOptions) -> Vec<u8> {
+    let per_page = options.per_page.clamp(1, 12);
+    let mut pages = Vec::new();
+    for (i, chunk) in puzzles.chunks(per_page).enumerate() {
+        let mut page = Page::new(options.paper, options.title.as_deref());
+        let slots = page.slots(per_page);
+        for (j, (puzzle, slot)) in chunk.iter().zip(slots).enumerate() {
+            let label = format!("Puzzle {}", i * per_page + j + 1);
+            page.grid(slot, &label, |row, col| {
+                puzzle.get(row, col).map(|n| (n, Style::Given))
+            });
+        }
+        pages.push(page);
+    }
+
+    if options.answers {
+        for (i, chunk) in puzzles.chunks(ANSWERS_PER_PAGE).enumerate() {
+            let mut page = Page::new(options.paper, Some("Answers"));
+            let slots = page.slots(ANSWERS_PER_PAGE);
+            for (j, (puzzle, slot)) in chunk.iter().zip(slots).enumerate() {
+                let number = i * ANSWERS_PER_PAGE + j + 1;
+                match puzzle.solve() {
+                    Some(solution) => page.grid(slot, &format!("Puzzle {}", number), |row, col| {
+                        let style = match puzzle.get(row, col) {
+                            Some(_) => Style::Given,
+                            None => Style::Solved,
+                        };
+                        Some((solution.get(row, col), style))
+                    }),
+                    None => page.grid(
+                        slot,
+                        &format!("Puzzle {} has no solution", number),
+                        |row, col| puzzle.get(row, col).map(|n| (n, Style::Given)),
+                    ),
+                }
+            }
+            pages.push(page);
+        }
+    }
+
+    // A PDF needs at least one page
+    if pages.is_empty() {
+        pages.push(Page::new(options.paper, options.title.as_deref()));
+    }
+    write_pdf(options.paper, pages)
+}
+
+/// How a number in a grid is written.
+#[derive(Clone, Copy)]
+enum Style {
+    Given,
+    Solved,
+}
+
+/// Where a grid goes: its bottom left corner and its width.
+#[derive(Clone, Copy)]
+struct Slot {
+    x: f32,
+    y: f32,
+    size: f32,
+}
+
+/// A page being drawn, as a PDF content stream.
+struct Page {
+    width: f32,
+    /// The top of the area below the title.
+    top: f32,
+    content: String,
+}
+
+impl Page {
+    fn new(paper: Paper, title: Option<&str>) -> Self {
+        let (width, height) = paper.size();
+        let mut page = Page {
+            width,
+            top: height - MARGIN,
+            content: String::new(),
+        };
+        if let Some(title) = title {
+            let baseline = page.top - 20.0;
+            page.text("F2", 20.0, MARGIN, baseline, title);
+            page.top -= TITLE_HEIGHT;
+        }
+        page
+    }
+
+    /// The places for count grids, in reading order, in rows of one, two,
+    /// or three depending on how many there are.
+    fn slots(&self, count: usize) -> Vec<Slot> {
+        let columns = match count {
+            1 | 2 => 1,
+            3..=6 => 2,
+            _ => 3,
+        };
+        let rows = count.div_ceil(columns);
+        let bottom = MARGIN + FOOTER_HEIGHT;
+        let slot_width = (self.width - 2.0 * MARGIN) / columns as f32;
+        let slot_height = (self.top - bottom) / rows as f32;
+        let size = (slot_width - GAP).min(slot_height - LABEL_HEIGHT - GAP);
+
+        (0..count)
+            .map(|i| {
+                let (row, col) = (i / columns, i % columns);
+                Slot {
+                    x: MARGIN + col as f32 * slot_width + (slot_width - size) / 2.0,
+                    y: self.top - (row + 1) as f32 * slot_height + GAP / 2.0,
+                    size,
+                }
+            })
+            .collect()
+    }
+
+    /// Draw a grid with a label above it, filling each square with the
+    /// number returned by square.
+    fn grid<F>(&mut self, slot: Slot, label: &str, square: F)
+    where
+        F: Fn(usize, usize) -> Option<(u8, Style)>,
+    {
+        let Slot { x, y, size } = slot;
+        let cell = size / 9.0;
+        self.text("F1", 11.0, x, y + size + 5.0, label);
+
+        let font_size = cell * 0.6;
+        for row in 0..9 {
+            for col in 0..9 {
+                let (n, style) = match square(row, col) {
+                    Some(square) => square,
+                    None => continue,
+                };
+                let left = x + col as f32 * cell + (cell - DIGIT_WIDTH * font_size) / 2.0;
+                let bottom = y + (8 - row) as f32 * cell + (cell - DIGIT_HEIGHT * font_size) / 2.0;
+                let (font, gray) = match style {
+                    Style::Given => ("F2", 0.0),
+                    Style::Solved => ("F1", 0.45),
+                };
+                let _ = writeln!(self.content, "{:.2} g", gray);
+                self.text(font, font_size, left, bottom, &format!("{}", n));
+            }
+        }
+        self.content.push_str("0 g\n");
+
+        for i in 0..=9 {
+            let at = i as f32 * cell;
+            let width = if i % 3 == 0 { 1.5 } else { 0.5 };
+            let _ = writeln!(
+                self.content,
+                "{:.2} w {:.2} {:.2} m {:.2} {:.2} l S {:.2} {:.2} m {:.2} {:.2} l S",
+                width,
+                x + at,
+                y,
+                x + at,
+                y + size,
+                x,
+                y + at,
+                x + size,
+                y + at
+            );
+        }
+    }
+
+    /// Write text with its baseline starting at (x, y).
+    fn text(&mut self, font: &str, size: f32, x: f32, y: f32, text: &str) {
+        let _ = writeln!(
+            self.content,
+            "BT /{} {:.2} Tf {:.2} {:.2} Td ({}) Tj ET",
+            font,
+            size,
+            x,
+            y,
+            escape(text)
+        );
+    }
+
+    /// Write the page number, centered at the bottom of the page.
+    fn footer(&mut self, number: usize) {
+        let number = format!("{}", number);
+        let x = (self.width - number.len() as f32 * DIGIT_WIDTH * 10.0) / 2.0;
+        self.text("F1", 10.0, x, MARGIN, &number);
+    }
+}
+
+/// Text as the inside of a PDF string, with its delimiters escaped and
+/// anything that isn't printable ASCII replaced.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+/// Write the pages as a PDF document, using the two Helvetica fonts that
+/// every PDF reader has, so that nothing needs to be embedded.
+fn write_pdf(paper: Paper, pages: Vec<Page>) -> Vec<u8> {
+    let (width, height) = paper.size();
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    let mut object = |pdf: &mut String, body: &str| {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{}\nendobj\n", offsets.len(), body);
+    };
+
+    // Objects 1 to 4, then a page and its contents for each page
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 5 + 2 * i))
+        .collect();
+    object(&mut pdf, "<< /Type /Catalog /Pages 2 0 R >>");
+    object(
+        &mut pdf,
+        &format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+    );
+    object(
+        &mut pdf,
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+    );
+    object(
+        &mut pdf,
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>",
+    );
+    for (i, mut page) in pages.into_iter().enumerate() {
+        page.footer(i + 1);
+        object(
+            &mut pdf,
+            &format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                width,
+                height,
+                6 + 2 * i
+            ),
+        );
+        object(
+            &mut pdf,
+            &format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.content.len(),
+                page.content
+            ),
+        );
+    }
+
+    let xref = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+    for offset in &offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        offsets.len() + 1,
+        xref
+    );
+    pdf.into_bytes()
+}
@@ -0,0 +1,147 @@
+//! Run the `sudoku` command line and check its output and exit codes.
+
+use std::process::{Command, Output};
+
+const PUZZLE: &str =
+    "410036000007000850600000000090000200006070008000000091002014000000003000740008509";
+
+/// Run `sudoku` with args and no configuration file, keeping its output.
+fn sudoku(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sudoku"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", "/nonexistent")
+        .env_remove("COLUMNS")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+/// A consistent puzzle with nowhere left for a number in r1c9.
+fn unsolvable() -> String {
+    format!("123456780{}000000009{}", "0".repeat(27), "0".repeat(36))
+}
+
+#[test]
+fn solving_succeeds() {
+    let output = sudoku(&["--format", "line", "solve", "-p", PUZZLE]);
+    assert_eq!(Some(0), output.status.code());
+    let solution = stdout(&output);
+    assert_eq!(81, solution.trim().len());
+    assert!(solution.starts_with("41"));
+}
+
+#[test]
+fn unreadable_puzzles_are_parse_errors() {
+    let output = sudoku(&["--format", "line", "solve", "-p", "12"]);
+    assert_eq!(Some(3), output.status.code());
+}
+
+#[test]
+fn unsolvable_puzzles_exit_with_1() {
+    let puzzle = unsolvable();
+    assert_eq!(Some(1), sudoku(&["solve", "-p", &puzzle]).status.code());
+    assert_eq!(Some(1), sudoku(&["check", "-p", &puzzle]).status.code());
+}
+
+#[test]
+fn puzzles_with_several_solutions_exit_with_2() {
+    let several = format!("{}0", &PUZZLE[..80]);
+    let output = sudoku(&["check", "-p", &several]);
+    assert_eq!(Some(2), output.status.code());
+    assert!(stdout(&output).contains("1 multiple solutions"));
+}
+
+#[test]
+fn limits_exit_with_4() {
+    let open = format!("1{}", "0".repeat(80));
+    let output = sudoku(&[
+        "solve",
+        "--count-solutions=1000",
+        "--max-nodes",
+        "10",
+        "-p",
+        &open,
+    ]);
+    assert_eq!(Some(4), output.status.code());
+}
+
+#[test]
+fn bad_arguments_are_usage_errors() {
+    assert_eq!(Some(64), sudoku(&["solve", "--no-such-flag"]).status.code());
+    let output = sudoku(&["solve", "--box", "99999x99999", "-p", "1"]);
+    assert_eq!(Some(64), output.status.code());
+}
+
+#[test]
+fn the_greatest_exit_code_wins() {
+    let puzzles = format!("{}\n{}\n12\n", PUZZLE, unsolvable());
+    let file = std::env::temp_dir().join(format!("sudoku-cli-{}.txt", std::process::id()));
+    std::fs::write(&file, puzzles).unwrap();
+    let output = sudoku(&["--format", "line", "solve", file.to_str().unwrap()]);
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(Some(3), output.status.code());
+}
+
+#[test]
+fn puzzles_that_are_not_utf8_are_parse_errors() {
+    let file = std::env::temp_dir().join(format!("sudoku-bytes-{}.txt", std::process::id()));
+    std::fs::write(&file, b"\xff\xfe123\n").unwrap();
+    let output = sudoku(&["solve", file.to_str().unwrap()]);
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(Some(3), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not valid UTF-8"));
+}
+
+#[test]
+fn invalid_saved_games_are_reported_on_stderr() {
+    let file = std::env::temp_dir().join(format!("sudoku-save-{}.json", std::process::id()));
+    std::fs::write(&file, r#"{"history": "givens 12"}"#).unwrap();
+    let output = sudoku(&["play", "--resume", file.to_str().unwrap()]);
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(Some(3), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Invalid saved game"));
+}
+
+#[test]
+fn check_gives_up_at_its_limits() {
+    let open = format!("1{}", "0".repeat(80));
+    let output = sudoku(&["check", "--max-nodes", "10", "-p", &open]);
+    assert_eq!(Some(4), output.status.code());
+    assert!(stdout(&output).contains("1 gave up"));
+}
+
+#[test]
+fn the_configuration_file_sets_limits_and_symmetry() {
+    let home = std::env::temp_dir().join(format!("sudoku-config-{}", std::process::id()));
+    std::fs::create_dir_all(home.join("sudoku")).unwrap();
+    std::fs::write(
+        home.join("sudoku").join("config.toml"),
+        "[solve]\nmax_nodes = 10\n\n[generate]\nsymmetry = \"rotational\"\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_sudoku"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", &home)
+            .output()
+            .unwrap()
+    };
+    let open = format!("1{}", "0".repeat(80));
+    let solved = run(&["solve", "--count-solutions=1000", "-p", &open]);
+    let checked = run(&["check", "-p", &open]);
+    let generated = run(&["--seed", "1", "--format", "line", "generate"]);
+    std::fs::remove_dir_all(&home).unwrap();
+
+    assert_eq!(Some(4), solved.status.code());
+    assert_eq!(Some(4), checked.status.code());
+    let puzzle = stdout(&generated);
+    let squares: Vec<char> = puzzle.trim().chars().collect();
+    assert_eq!(81, squares.len());
+    for square in 0..81 {
+        assert_eq!(squares[square] == '0', squares[80 - square] == '0');
+    }
+}
@@ -0,0 +1,48 @@
+//! Check that every puzzle in the corpus is an example of its category.
+
+use sudoku::{
+    corpus::{self, Category},
+    verify,
+};
+
+#[test]
+fn puzzles_fit_their_categories() {
+    for entry in corpus::all() {
+        let puzzle = match (entry.category, entry.puzzle()) {
+            (Category::Invalid, Err(_)) => continue,
+            (_, Ok(puzzle)) => puzzle,
+            (_, Err(e)) => panic!("{} could not be read: {}", entry.name, e),
+        };
+        let givens = puzzle.to_vec().iter().filter(|n| **n != 0).count();
+        let solutions = puzzle.count_solutions(2);
+        assert_eq!(Ok(()), verify::check_solver(&puzzle), "{}", entry.name);
+
+        let fits = match entry.category {
+            Category::Trivial => solutions == 1 && givens >= 72,
+            Category::SeventeenClue => solutions == 1 && givens == 17,
+            Category::Invalid => solutions == 0,
+            Category::MultipleSolutions => solutions == 2,
+            category => solutions == 1 && puzzle.difficulty() == category.difficulty(),
+        };
+        assert!(
+            fits,
+            "{} is not an example of {:?}",
+            entry.name, entry.category
+        );
+    }
+}
+
+#[test]
+fn names_are_unique_and_categories_are_grouped() {
+    let all = corpus::all();
+    for (i, entry) in all.iter().enumerate() {
+        assert_eq!(Some(entry), corpus::get(entry.name));
+        if let Some(next) = all.get(i + 1) {
+            let position = |c| Category::ALL.iter().position(|other| *other == c);
+            assert!(position(entry.category) <= position(next.category));
+        }
+    }
+    for category in Category::ALL.iter() {
+        assert!(corpus::category(*category).count() >= 2);
+    }
+}
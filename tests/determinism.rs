@@ -0,0 +1,51 @@
+//! Golden values for deterministic mode. These must come out the same on
+//! every platform; a change to any of them changes what seeds produce, and
+//! needs to be called out as such.
+
+use sudoku::{batch, deterministic, puzzle::SudokuPuzzle, rating::Difficulty};
+
+fn digits(nums: &[u8]) -> String {
+    nums.iter().map(|n| char::from(b'0' + n)).collect()
+}
+
+/// An FNV-1a hash of text.
+fn hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[test]
+fn seeds_generate_the_same_puzzles() {
+    assert_eq!(
+        "159736482823154769674298315267813594918542673345967128432681957791325846586479231",
+        digits(&deterministic::random_solution(7).to_vec())
+    );
+    assert_eq!(
+        "050000400003004000004008015000013000900000670000007008432001900001300006500000200",
+        digits(&deterministic::generate(7, Difficulty::Medium).to_vec())
+    );
+}
+
+#[test]
+fn solves_take_the_same_steps() {
+    let puzzle = SudokuPuzzle::from_string(
+        "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    )
+    .unwrap();
+    let (_, steps) = puzzle.solve_with_steps();
+    let log: Vec<String> = steps.iter().map(|step| step.to_string()).collect();
+    assert_eq!(1502, log.len());
+    assert_eq!(0x69cb_83f5_c65d_ee30, hash(&log.join("\n")));
+}
+
+#[test]
+fn batches_keep_their_order() {
+    let puzzles: Vec<SudokuPuzzle> = (0..8)
+        .map(|seed| SudokuPuzzle::from(deterministic::random_solution(seed)))
+        .collect();
+    let solutions = batch::solve_all(&puzzles);
+    for (puzzle, solution) in puzzles.iter().zip(solutions) {
+        assert_eq!(puzzle.to_vec(), solution.unwrap().to_vec());
+    }
+}
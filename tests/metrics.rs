@@ -0,0 +1,66 @@
+//! Check the metrics that the HTTP service reports about its requests, and
+//! the limits it puts on them. The metrics are shared by every router in the
+//! process, so these tests run apart from those of the endpoints.
+
+use axum::{
+    body::{self, Body},
+    http::Request,
+    Router,
+};
+use sudoku::server;
+use tower::ServiceExt;
+
+async fn send(router: &Router, method: &str, uri: &str, body: &str) -> String {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(String::from(body)))
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    let bytes = body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn metrics_count_requests() {
+    let router = server::router();
+    let multiple = r#"{"puzzle": "410036000007000850600000000090000200006070008000000091002014000000003000740008500"}"#;
+    send(&router, "POST", "/check", multiple).await;
+    send(&router, "POST", "/solve", r#"{"puzzle": "12"}"#).await;
+
+    let metrics = send(&router, "GET", "/metrics", "").await;
+    assert!(metrics.contains("# TYPE sudoku_requests_total counter"));
+    assert!(metrics.contains(r#"sudoku_requests_total{endpoint="/check",status="200"} 1"#));
+    assert!(metrics.contains(r#"sudoku_requests_total{endpoint="/solve",status="400"} 1"#));
+    assert!(metrics.contains(r#"sudoku_request_duration_seconds_count{endpoint="/check"} 1"#));
+    assert!(metrics
+        .contains(r#"sudoku_request_duration_seconds_bucket{endpoint="/solve",le="+Inf"} 1"#));
+    assert!(metrics.contains("sudoku_timeouts_total 0"));
+    assert!(metrics.contains("sudoku_not_unique_total 1"));
+    assert!(!metrics.contains(r#"endpoint="/metrics""#));
+}
+
+#[tokio::test]
+async fn requests_stay_within_limits() {
+    let router = server::router();
+    let puzzle =
+        r#""410036000007000850600000000090000200006070008000000091002014000000003000740008509""#;
+    let puzzles = vec![puzzle; 1001].join(",");
+    let response = send(
+        &router,
+        "POST",
+        "/solve-all",
+        &format!(r#"{{"puzzles": [{}]}}"#, puzzles),
+    )
+    .await;
+    assert!(response.contains("at most 1000 puzzles"));
+
+    send(&router, "POST", "/solve-all", r#"{"puzzles": []}"#).await;
+
+    let metrics = send(&router, "GET", "/metrics", "").await;
+    assert!(metrics.contains(r#"sudoku_requests_total{endpoint="/solve-all",status="413"} 1"#));
+    assert!(metrics.contains(r#"sudoku_requests_total{endpoint="/solve-all",status="200"} 1"#));
+}
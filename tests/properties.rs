@@ -0,0 +1,266 @@
+//! Property tests of the solver, generator, and canonical forms, using the
+//! checks in `sudoku::verify`.
+
+use proptest::{collection::vec, prelude::*};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::convert::TryFrom;
+use sudoku::{
+    board,
+    context::SolverContext,
+    generate,
+    grid::Grid,
+    limits::SolverLimits,
+    puzzle::SudokuPuzzle,
+    rating::Difficulty,
+    search::Solutions,
+    solution::Solution,
+    strategy, stuck,
+    sukaku::Sukaku,
+    technique::{Deduction, Position, Technique},
+    trace::Trace,
+    transform::{Axis, Transform},
+    verify,
+};
+
+/// A random grid with the squares not in keep emptied, which always has at
+/// least one solution.
+fn puzzle() -> impl Strategy<Value = SudokuPuzzle> {
+    (any::<u64>(), vec(prop::bool::weighted(0.4), 81)).prop_map(|(seed, keep)| {
+        let solution = generate::random_solution(&mut ChaCha8Rng::seed_from_u64(seed));
+        let nums: Vec<u8> = solution
+            .to_vec()
+            .into_iter()
+            .zip(keep)
+            .map(|(n, keep)| if keep { n } else { 0 })
+            .collect();
+        SudokuPuzzle::try_from(&nums[..]).unwrap()
+    })
+}
+
+/// Any valid transformation.
+fn transform() -> impl Strategy<Value = Transform> {
+    let line = || (0usize..3, 0usize..3, 0usize..3);
+    prop_oneof![
+        (0usize..4).prop_map(|quarter_turns| Transform::Rotate { quarter_turns }),
+        Just(Transform::Reflect(Axis::Vertical)),
+        Just(Transform::Reflect(Axis::Horizontal)),
+        Just(Transform::Transpose),
+        (0usize..3, 0usize..3).prop_map(|(a, b)| Transform::SwapBands(a, b)),
+        (0usize..3, 0usize..3).prop_map(|(a, b)| Transform::SwapStacks(a, b)),
+        line().prop_map(|(band, a, b)| Transform::SwapRows(band * 3 + a, band * 3 + b)),
+        line().prop_map(|(stack, a, b)| Transform::SwapColumns(stack * 3 + a, stack * 3 + b)),
+        Just((1..=9).collect::<Vec<u8>>())
+            .prop_shuffle()
+            .prop_map(|labels| Transform::Relabel(<[u8; 9]>::try_from(labels).unwrap())),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn solutions_extend_their_puzzles(puzzle in puzzle()) {
+        prop_assert!(puzzle.solve().is_some());
+        prop_assert_eq!(Ok(()), verify::check_solver(&puzzle));
+    }
+
+    #[test]
+    fn the_generic_solver_agrees(puzzle in puzzle()) {
+        let solved = board::solve(&puzzle).unwrap();
+        prop_assert!(solved.is_solved());
+        prop_assert!(puzzle.diff(&solved).iter().all(|diff| diff.this.is_none()));
+        prop_assert_eq!(puzzle.count_solutions(3), board::count_solutions(&puzzle, 3));
+
+        // A grid of the same puzzle goes through the same search
+        let line: String = puzzle.to_vec().iter().map(|n| char::from(b'0' + n)).collect();
+        let grid = Grid::from_string(3, 3, &line).unwrap();
+        prop_assert_eq!(solved.to_vec(), grid.solve().unwrap().to_vec());
+        prop_assert_eq!(puzzle.count_solutions(3), grid.count_solutions(3));
+    }
+
+    #[test]
+    fn every_strategy_solves_every_puzzle(puzzle in puzzle()) {
+        for strategy in strategy::Strategy::ALL.iter() {
+            let solved = SudokuPuzzle::from(strategy.solve(&puzzle).solution.unwrap());
+            prop_assert!(solved.is_solved());
+            prop_assert!(puzzle.diff(&solved).iter().all(|diff| diff.this.is_none()));
+        }
+    }
+
+    #[test]
+    fn traces_replay_their_eliminations(puzzle in puzzle()) {
+        let trace = Trace::of(&puzzle);
+        let mut candidates = trace.candidates().to_vec();
+        for event in trace.events() {
+            if event.step.number().is_some() {
+                let (row, col) = event.step.square();
+                candidates[row * 9 + col].clear();
+                for (row, col, n) in &event.eliminations {
+                    candidates[row * 9 + col].remove(*n);
+                }
+                prop_assert_eq!(&candidates, &event.candidates);
+            }
+            candidates = event.candidates.clone();
+        }
+        prop_assert!(candidates.iter().all(|set| set.is_empty()));
+    }
+
+    #[test]
+    fn techniques_agree_with_the_solution(seed in any::<u64>()) {
+        let puzzle = generate::generate(&mut ChaCha8Rng::seed_from_u64(seed), Difficulty::Hard);
+        let solution = puzzle.solve().unwrap();
+        for technique in Technique::ALL.iter() {
+            if let Some(position) = Position::practice(&puzzle, *technique) {
+                let finding = position.next().unwrap();
+                prop_assert_eq!(*technique, finding.technique);
+                match finding.deduction {
+                    Deduction::Place { row, col, n } => prop_assert_eq!(n, solution.get(row, col)),
+                    Deduction::Eliminate(eliminations) => {
+                        for (row, col, n) in eliminations {
+                            prop_assert_ne!(n, solution.get(row, col));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nudges_name_the_next_technique(puzzle in puzzle()) {
+        let nudge = stuck::nudge(&puzzle, false);
+        let finding = Position::new(&puzzle).next();
+        prop_assert_eq!(finding.map(|f| f.technique), nudge.map(|n| n.technique));
+
+        // Notes holding every candidate narrow nothing
+        let mut marked = puzzle.clone();
+        for (row, col) in (0..9).flat_map(|row| (0..9).map(move |col| (row, col))) {
+            if puzzle.get(row, col).is_none() {
+                marked.set_marks(row, col, puzzle.candidates(row, col)).unwrap();
+            }
+        }
+        prop_assert_eq!(nudge, stuck::nudge(&marked, true));
+    }
+
+    #[test]
+    fn grids_of_every_box_shape_solve(
+        (box_rows, box_cols) in prop_oneof![Just((2, 2)), Just((2, 3)), Just((3, 2)), Just((2, 4))],
+        keep in vec(prop::bool::weighted(0.3), 64),
+    ) {
+        let solved = Grid::empty(box_rows, box_cols).solve().unwrap();
+        prop_assert!(solved.is_solved());
+        let line: String = solved
+            .to_line()
+            .chars()
+            .zip(keep)
+            .map(|(c, keep)| if keep { c } else { '.' })
+            .collect();
+        let grid = Grid::from_string(box_rows, box_cols, &line).unwrap();
+        let solution = grid.solve().unwrap();
+        prop_assert!(solution.is_solved());
+        let size = grid.size();
+        for (row, col) in (0..size).flat_map(|row| (0..size).map(move |col| (row, col))) {
+            prop_assert!(grid.get(row, col).is_none_or(|n| solution.get(row, col) == Some(n)));
+        }
+    }
+
+    #[test]
+    fn sukakus_solve_within_their_candidates(puzzle in puzzle()) {
+        let sukaku = Sukaku::from_puzzle(&puzzle);
+        prop_assert_eq!(puzzle.count_solutions(2), sukaku.count_solutions(2));
+        let solution = sukaku.solve().unwrap();
+        prop_assert!(SudokuPuzzle::from(solution).is_solved());
+        for (row, col) in (0..9).flat_map(|row| (0..9).map(move |col| (row, col))) {
+            prop_assert!(sukaku.candidates(row, col).contains(solution.get(row, col)));
+        }
+    }
+
+    #[test]
+    fn reused_contexts_solve_like_fresh_ones(puzzles in vec(puzzle(), 1..4)) {
+        let mut context = SolverContext::new();
+        for puzzle in &puzzles {
+            prop_assert_eq!(puzzle.count_solutions(3), context.count_solutions(puzzle, 3));
+            prop_assert_eq!(puzzle.solve(), context.solve(puzzle));
+        }
+    }
+
+    #[test]
+    fn unlimited_searches_agree_with_the_solver(puzzle in puzzle()) {
+        let limits = SolverLimits::default();
+        prop_assert_eq!(Ok(puzzle.solve()), puzzle.solve_within(&limits));
+        prop_assert_eq!(Ok(puzzle.count_solutions(5)), puzzle.count_solutions_within(5, &limits));
+    }
+
+    #[test]
+    fn proposals_verify_only_when_they_solve(puzzle in puzzle(), square in 0usize..81, n in 1u8..=9) {
+        let mut proposed = puzzle.solve().unwrap().to_vec();
+        prop_assert_eq!(Ok(()), verify::verify_solution(&puzzle, &proposed));
+
+        // Changing any square of a solution breaks a rule
+        let changed = proposed[square] != n;
+        proposed[square] = n;
+        prop_assert_eq!(!changed, verify::verify_solution(&puzzle, &proposed).is_ok());
+    }
+
+    #[test]
+    fn canonical_forms_are_invariant(puzzle in puzzle()) {
+        prop_assert_eq!(Ok(()), verify::check_canonical(&puzzle));
+    }
+
+    #[test]
+    fn transforms_move_solutions_with_puzzles(puzzle in puzzle(), transform in transform()) {
+        // Puzzles may have several solutions, found in a different order
+        let transformed = puzzle.transform(transform).unwrap();
+        let solution = SudokuPuzzle::try_from(&puzzle.solve().unwrap().to_vec()[..]).unwrap();
+        let moved = solution.transform(transform).unwrap();
+        prop_assert!(moved.is_solved());
+        prop_assert!(transformed.diff(&moved).iter().all(|diff| diff.this.is_none()));
+        prop_assert_eq!(puzzle.count_solutions(3), transformed.count_solutions(3));
+        prop_assert_eq!(puzzle.canonical().to_vec(), transformed.canonical().to_vec());
+    }
+
+    #[test]
+    fn checkpoints_resume_the_same_search(puzzle in puzzle(), skip in 0usize..4) {
+        let mut search = puzzle.solutions();
+        search.by_ref().take(skip).for_each(drop);
+        let resumed = Solutions::resume(&search.checkpoint()).unwrap();
+        let expected: Vec<Solution> = search.take(3).collect();
+        prop_assert_eq!(expected, resumed.take(3).collect::<Vec<_>>());
+    }
+}
+
+proptest! {
+    // Generating a puzzle takes far longer than solving one
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn generated_puzzles_have_one_solution(seed in any::<u64>(), i in 0..Difficulty::ALL.len()) {
+        let difficulty = Difficulty::ALL[i];
+        let puzzle = generate::generate(&mut ChaCha8Rng::seed_from_u64(seed), difficulty);
+        prop_assert_eq!(1, puzzle.count_solutions(2));
+        prop_assert_eq!(Some(difficulty), puzzle.difficulty());
+        prop_assert_eq!(Ok(()), verify::check_solver(&puzzle));
+        prop_assert_eq!(Ok(()), verify::check_canonical(&puzzle));
+    }
+
+    #[test]
+    fn puzzles_generated_for_techniques_need_no_guessing(
+        seed in any::<u64>(),
+        i in 0..Difficulty::ALL.len(),
+        hardest in 2..Technique::ALL.len(),
+    ) {
+        let difficulty = Difficulty::ALL[i];
+        let techniques = &Technique::ALL[..=hardest];
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        // Singles and naked pairs give puzzles of every difficulty, a hard
+        // one from fewer than 100 grids in every seed tried
+        let found = (0..400)
+            .find_map(|_| generate::generate_solvable_with(&mut rng, difficulty, techniques));
+        prop_assert!(found.is_some());
+        let puzzle = found.unwrap();
+        prop_assert_eq!(1, puzzle.count_solutions(2));
+        prop_assert_eq!(Some(difficulty), puzzle.difficulty());
+        prop_assert!(Position::solves(&puzzle, techniques));
+    }
+}
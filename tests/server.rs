@@ -0,0 +1,123 @@
+//! Check the answers of each endpoint of the HTTP service.
+
+use axum::{
+    body::{self, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use serde_json::{json, Value};
+use sudoku::{puzzle::SudokuPuzzle, server};
+use tower::ServiceExt;
+
+const PUZZLE: &str =
+    "410036000007000850600000000090000200006070008000000091002014000000003000740008509";
+
+/// PUZZLE without its last clue, which leaves it with more than one solution.
+const MULTIPLE: &str =
+    "410036000007000850600000000090000200006070008000000091002014000000003000740008500";
+
+/// The top right square can hold none of 1-9.
+fn unsolvable() -> String {
+    format!(
+        "{}{}{}{}",
+        "123456780",
+        "0".repeat(27),
+        "000000009",
+        "0".repeat(36)
+    )
+}
+
+async fn send(router: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn solve_answers_with_the_solution() {
+    let router = server::router();
+    let (status, body) = send(&router, "/solve", json!({ "puzzle": PUZZLE })).await;
+    assert_eq!(StatusCode::OK, status);
+    let solution = body["solution"].as_str().unwrap();
+    let solved = SudokuPuzzle::from_string(solution).unwrap();
+    assert!(solved.is_solved());
+    assert!(PUZZLE
+        .chars()
+        .zip(solution.chars())
+        .all(|(clue, n)| clue == '0' || clue == n));
+
+    let (status, body) = send(&router, "/solve", json!({ "puzzle": unsolvable() })).await;
+    assert_eq!(StatusCode::OK, status);
+    assert_eq!(json!({ "solution": null }), body);
+}
+
+#[tokio::test]
+async fn rate_answers_with_the_difficulty_and_score() {
+    let router = server::router();
+    let rating = SudokuPuzzle::from_string(PUZZLE).unwrap().rating().unwrap();
+    let (status, body) = send(&router, "/rate", json!({ "puzzle": PUZZLE })).await;
+    assert_eq!(StatusCode::OK, status);
+    assert_eq!(
+        json!({ "difficulty": rating.difficulty.name(), "score": rating.score }),
+        body
+    );
+
+    let (_, body) = send(&router, "/rate", json!({ "puzzle": unsolvable() })).await;
+    assert_eq!(json!({ "difficulty": null, "score": null }), body);
+}
+
+#[tokio::test]
+async fn generate_with_a_seed_is_reproducible() {
+    let router = server::router();
+    let request = json!({ "difficulty": "easy", "seed": 7 });
+    let (status, first) = send(&router, "/generate", request.clone()).await;
+    assert_eq!(StatusCode::OK, status);
+    let (_, second) = send(&router, "/generate", request).await;
+    assert_eq!(first, second);
+    assert_eq!("easy", first["difficulty"]);
+
+    let puzzle = SudokuPuzzle::from_string(first["puzzle"].as_str().unwrap()).unwrap();
+    assert_eq!(1, puzzle.count_solutions(2));
+    assert_eq!(puzzle.rating().unwrap().score, first["score"]);
+}
+
+#[tokio::test]
+async fn check_answers_with_the_status_and_conflicts() {
+    let router = server::router();
+    let inconsistent = format!("11{}", "0".repeat(79));
+    let cases = vec![
+        (String::from(PUZZLE), "unique"),
+        (String::from(MULTIPLE), "multiple"),
+        (unsolvable(), "unsolvable"),
+    ];
+    for (puzzle, expected) in cases {
+        let (status, body) = send(&router, "/check", json!({ "puzzle": puzzle })).await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(json!({ "status": expected, "conflicts": [] }), body);
+    }
+
+    let (_, body) = send(&router, "/check", json!({ "puzzle": inconsistent })).await;
+    assert_eq!(
+        json!({ "status": "inconsistent", "conflicts": [[0, 0], [0, 1]] }),
+        body
+    );
+}
+
+#[tokio::test]
+async fn an_unreadable_puzzle_is_a_bad_request() {
+    let router = server::router();
+    for uri in &["/solve", "/rate", "/check"] {
+        let (status, body) = send(&router, uri, json!({ "puzzle": "12" })).await;
+        assert_eq!(StatusCode::BAD_REQUEST, status);
+        assert_eq!(json!({ "error": "expected 81 squares but found 2" }), body);
+    }
+}
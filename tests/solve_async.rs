@@ -0,0 +1,39 @@
+//! Check that solving asynchronously agrees with solving directly.
+
+use futures_lite::future;
+use sudoku::puzzle::SudokuPuzzle;
+
+const PUZZLES: [&str; 3] = [
+    "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+    "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    // Two 1s in the first row
+    "110000000000000000000000000000000000000000000000000000000000000000000000000000000",
+];
+
+#[test]
+fn solutions_match_solve() {
+    for text in PUZZLES.iter() {
+        let puzzle = SudokuPuzzle::from_string(text).unwrap();
+        assert_eq!(puzzle.solve(), future::block_on(puzzle.solve_async()));
+    }
+}
+
+#[test]
+fn solves_run_concurrently() {
+    let easy = SudokuPuzzle::from_string(PUZZLES[0]).unwrap();
+    let hard = SudokuPuzzle::from_string(PUZZLES[1]).unwrap();
+    let (easy_solution, hard_solution) =
+        future::block_on(future::zip(easy.solve_async(), hard.solve_async()));
+    assert_eq!(easy.solve(), easy_solution);
+    assert_eq!(hard.solve(), hard_solution);
+}
+
+#[test]
+fn dropped_solves_are_cancelled() {
+    let puzzle = SudokuPuzzle::from_string(PUZZLES[1]).unwrap();
+    let mut solving = Box::pin(puzzle.solve_async());
+    // Start the search, then abandon it
+    let _ = future::block_on(future::poll_once(&mut solving));
+    drop(solving);
+    assert!(future::block_on(puzzle.solve_async()).is_some());
+}
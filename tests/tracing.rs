@@ -0,0 +1,98 @@
+//! Check the spans and events the solver reports through tracing.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use sudoku::puzzle::SudokuPuzzle;
+use tracing::{span, Event, Metadata, Subscriber};
+
+/// A subscriber that counts spans by name, and events.
+#[derive(Default)]
+struct Counter {
+    spans: Mutex<HashMap<&'static str, usize>>,
+    events: AtomicUsize,
+    next_id: AtomicU64,
+}
+
+impl Counter {
+    fn spans(&self, name: &str) -> usize {
+        self.spans.lock().unwrap().get(name).copied().unwrap_or(0)
+    }
+}
+
+impl Subscriber for Counter {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        *self
+            .spans
+            .lock()
+            .unwrap()
+            .entry(span.metadata().name())
+            .or_default() += 1;
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, _: &Event<'_>) {
+        self.events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Run f with a new Counter as the default subscriber, and return it.
+fn count(f: impl FnOnce()) -> Arc<Counter> {
+    let counter = Arc::new(Counter::default());
+    tracing::subscriber::with_default(counter.clone(), f);
+    counter
+}
+
+#[test]
+fn solving_reports_passes_guesses_and_steps() {
+    let puzzle = SudokuPuzzle::from_string(
+        "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    )
+    .unwrap();
+    let counter = count(|| {
+        puzzle.solve().unwrap();
+    });
+    assert_eq!(1, counter.spans("solve"));
+    assert!(counter.spans("guess") > 0);
+    assert!(counter.spans("pass") > counter.spans("guess"));
+    assert!(counter.events.load(Ordering::Relaxed) > 81);
+}
+
+#[test]
+fn easy_puzzles_need_no_guesses() {
+    let puzzle = SudokuPuzzle::from_string(
+        "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+    )
+    .unwrap();
+    let counter = count(|| {
+        puzzle.solve().unwrap();
+    });
+    assert_eq!(0, counter.spans("guess"));
+    // One event for each square the solver fills
+    let unfilled = puzzle.to_vec().iter().filter(|n| **n == 0).count();
+    assert_eq!(unfilled, counter.events.load(Ordering::Relaxed));
+}
+
+#[test]
+fn enumerating_solutions_reports_each_search() {
+    let counter = count(|| {
+        SudokuPuzzle::empty().solutions().take(3).for_each(drop);
+    });
+    assert_eq!(3, counter.spans("next_solution"));
+}
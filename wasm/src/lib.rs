@@ -0,0 +1,4 @@
+//! The WebAssembly package of the sudoku crate, built with
+//! `wasm-pack build wasm`. The bindings themselves are in `sudoku::wasm`.
+
+pub use sudoku::wasm::*;